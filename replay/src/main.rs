@@ -4,7 +4,10 @@ use rpc_state_reader::{
     rpc_state::{RpcChain, RpcTransactionReceipt},
 };
 use starknet_api::block::BlockNumber;
-use starknet_in_rust::execution::TransactionExecutionInfo;
+use starknet_in_rust::{
+    definitions::block_context::StarknetChainId, execution::TransactionExecutionInfo,
+    felt::Felt252, utils::Address,
+};
 
 #[derive(Debug, Parser)]
 #[command(about = "Replay is a tool for executing Starknet transactions.", long_about = None)]
@@ -21,12 +24,18 @@ enum ReplayExecute {
         chain: String,
         block_number: u64,
         silent: Option<bool>,
+        /// The fee token of a custom chain, as a hex felt.
+        #[arg(long)]
+        fee_token_address: Option<String>,
     },
     #[clap(about = "Execute all the invoke transactions in a given block.")]
     Block {
         chain: String,
         block_number: u64,
         silent: Option<bool>,
+        /// The fee token of a custom chain, as a hex felt.
+        #[arg(long)]
+        fee_token_address: Option<String>,
     },
     #[clap(about = "Execute all the invoke transactions in a given range of blocks.")]
     BlockRange {
@@ -34,6 +43,9 @@ enum ReplayExecute {
         block_end: u64,
         chain: String,
         silent: Option<bool>,
+        /// The fee token of a custom chain, as a hex felt.
+        #[arg(long)]
+        fee_token_address: Option<String>,
     },
 }
 
@@ -46,14 +58,18 @@ fn main() {
             chain,
             block_number,
             silent,
+            fee_token_address,
         } => {
-            show_execution_data(tx_hash, &chain, block_number, silent);
+            let fee_token_address = fee_token_address.as_deref().map(parse_fee_token_address);
+            show_execution_data(tx_hash, &chain, block_number, silent, fee_token_address);
         }
         ReplayExecute::Block {
             block_number,
             chain,
             silent,
+            fee_token_address,
         } => {
+            let fee_token_address = fee_token_address.as_deref().map(parse_fee_token_address);
             println!("Executing block number: {}", block_number);
             let rpc_chain = parse_network(&chain);
             let block_number = BlockNumber(block_number);
@@ -61,7 +77,13 @@ fn main() {
                 .expect("Unable to fetch the transaction hashes.");
 
             for tx_hash in transaction_hashes {
-                show_execution_data(tx_hash, &chain, block_number.0, silent);
+                show_execution_data(
+                    tx_hash,
+                    &chain,
+                    block_number.0,
+                    silent,
+                    fee_token_address.clone(),
+                );
             }
         }
         ReplayExecute::BlockRange {
@@ -69,7 +91,9 @@ fn main() {
             block_end,
             chain,
             silent,
+            fee_token_address,
         } => {
+            let fee_token_address = fee_token_address.as_deref().map(parse_fee_token_address);
             println!("Executing block range: {} - {}", block_start, block_end);
             let rpc_chain = parse_network(&chain);
             for block_number in block_start..=block_end {
@@ -78,23 +102,59 @@ fn main() {
                     .expect("Unable to fetch the transaction hashes.");
 
                 for tx_hash in transaction_hashes {
-                    show_execution_data(tx_hash, &chain, block_number.0, silent);
+                    show_execution_data(
+                        tx_hash,
+                        &chain,
+                        block_number.0,
+                        silent,
+                        fee_token_address.clone(),
+                    );
                 }
             }
         }
     }
 }
 
+/// Parses a public network name, or the chain ID of a custom chain: either a short string (like
+/// `SN_APPCHAIN`) or a hex felt. Custom chains are read from the `RPC_ENDPOINT` and
+/// `FEEDER_GATEWAY_URL` environment variables, and need `--fee-token-address`.
 fn parse_network(network: &str) -> RpcChain {
     match network.to_lowercase().as_str() {
         "mainnet" => RpcChain::MainNet,
         "testnet" => RpcChain::TestNet,
         "testnet2" => RpcChain::TestNet2,
-        _ => panic!("Invalid network name, it should be one of: mainnet, testnet, testnet2"),
+        "sepolia" => RpcChain::Sepolia,
+        _ => {
+            let chain_id = match network.strip_prefix("0x") {
+                Some(hex) => Felt252::parse_bytes(hex.as_bytes(), 16).map(StarknetChainId::from),
+                None => StarknetChainId::from_short_string(network),
+            };
+            chain_id.map(RpcChain::from).unwrap_or_else(|| {
+                panic!(
+                    "Invalid network name, it should be one of: mainnet, testnet, testnet2, \
+                     sepolia, or the chain ID of a custom chain"
+                )
+            })
+        }
     }
 }
 
-fn show_execution_data(tx_hash: String, chain: &str, block_number: u64, silent: Option<bool>) {
+fn parse_fee_token_address(fee_token_address: &str) -> Address {
+    let hex = fee_token_address
+        .strip_prefix("0x")
+        .unwrap_or(fee_token_address);
+    Felt252::parse_bytes(hex.as_bytes(), 16)
+        .map(Address)
+        .expect("Invalid fee token address, it should be a hex felt")
+}
+
+fn show_execution_data(
+    tx_hash: String,
+    chain: &str,
+    block_number: u64,
+    silent: Option<bool>,
+    fee_token_address: Option<Address>,
+) {
     let rpc_chain = parse_network(chain);
     if silent.is_none() || !silent.unwrap() {
         println!("Executing transaction with hash: {}", tx_hash);
@@ -103,14 +163,20 @@ fn show_execution_data(tx_hash: String, chain: &str, block_number: u64, silent:
     }
     let previous_block_number = BlockNumber(block_number - 1);
 
-    let (tx_info, _trace, receipt) =
-        match execute_tx_configurable(&tx_hash, rpc_chain, previous_block_number, false, true) {
-            Ok(x) => x,
-            Err(error_reason) => {
-                println!("Error: {}", error_reason);
-                return;
-            }
-        };
+    let (tx_info, _trace, receipt) = match execute_tx_configurable(
+        &tx_hash,
+        rpc_chain,
+        previous_block_number,
+        false,
+        true,
+        fee_token_address,
+    ) {
+        Ok(x) => x,
+        Err(error_reason) => {
+            println!("Error: {}", error_reason);
+            return;
+        }
+    };
     let TransactionExecutionInfo {
         revert_error,
         actual_fee,
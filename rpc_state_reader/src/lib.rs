@@ -22,11 +22,9 @@ mod tests {
         hash::{StarkFelt, StarkHash},
         patricia_key, stark_felt,
         state::StorageKey,
-        transaction::{Transaction as SNTransaction, TransactionHash},
-    };
-    use starknet_in_rust::{
-        definitions::block_context::StarknetChainId, transaction::InvokeFunction,
+        transaction::TransactionHash,
     };
+    use starknet_in_rust::transaction::{StarknetApiTransaction, Transaction};
     use std::collections::HashMap;
 
     use crate::rpc_state::*;
@@ -112,13 +110,8 @@ mod tests {
         ));
 
         let tx = rpc_state.get_transaction(&tx_hash).unwrap();
-        match tx {
-            SNTransaction::Invoke(tx) => {
-                InvokeFunction::from_invoke_transaction(tx, StarknetChainId::MainNet)
-            }
-            _ => unreachable!(),
-        }
-        .unwrap();
+        let tx = Transaction::try_from(StarknetApiTransaction::new(tx, tx_hash)).unwrap();
+        assert!(matches!(tx, Transaction::InvokeFunction(_)));
     }
 
     #[test]
@@ -1,4 +1,6 @@
-use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
+use cairo_vm::{
+    felt::Felt252, vm::runners::cairo_runner::ExecutionResources as VmExecutionResources,
+};
 use core::fmt;
 use dotenv::dotenv;
 use serde::{Deserialize, Deserializer};
@@ -16,12 +18,16 @@ use std::{collections::HashMap, env, fmt::Display};
 
 use crate::{rpc_state_errors::RpcStateError, utils};
 
-/// Starknet chains supported in Infura.
+/// Starknet chains supported by the RPC state reader. The public networks are served by Infura,
+/// while custom chains need their own endpoints, see [`RpcState::new_from_env`].
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum RpcChain {
     MainNet,
     TestNet,
     TestNet2,
+    Sepolia,
+    /// Any other chain, like an app-chain or a private network, identified by its chain ID.
+    Custom(StarknetChainId),
 }
 
 impl From<RpcChain> for StarknetChainId {
@@ -30,6 +36,20 @@ impl From<RpcChain> for StarknetChainId {
             RpcChain::MainNet => StarknetChainId::MainNet,
             RpcChain::TestNet => StarknetChainId::TestNet,
             RpcChain::TestNet2 => StarknetChainId::TestNet2,
+            RpcChain::Sepolia => StarknetChainId::Sepolia,
+            RpcChain::Custom(chain_id) => chain_id,
+        }
+    }
+}
+
+impl From<StarknetChainId> for RpcChain {
+    fn from(chain_id: StarknetChainId) -> RpcChain {
+        match chain_id {
+            StarknetChainId::MainNet => RpcChain::MainNet,
+            StarknetChainId::TestNet => RpcChain::TestNet,
+            StarknetChainId::TestNet2 => RpcChain::TestNet2,
+            StarknetChainId::Sepolia => RpcChain::Sepolia,
+            chain_id => RpcChain::Custom(chain_id),
         }
     }
 }
@@ -40,6 +60,8 @@ impl fmt::Display for RpcChain {
             RpcChain::MainNet => write!(f, "starknet-mainnet"),
             RpcChain::TestNet => write!(f, "starknet-goerli"),
             RpcChain::TestNet2 => write!(f, "starknet-goerli2"),
+            RpcChain::Sepolia => write!(f, "starknet-sepolia"),
+            RpcChain::Custom(chain_id) => write!(f, "{chain_id}"),
         }
     }
 }
@@ -50,6 +72,8 @@ impl From<RpcChain> for ChainId {
             RpcChain::MainNet => "alpha-mainnet".to_string(),
             RpcChain::TestNet => "alpha4".to_string(),
             RpcChain::TestNet2 => "alpha4-2".to_string(),
+            RpcChain::Sepolia => "alpha-sepolia".to_string(),
+            RpcChain::Custom(_) => value.to_string(),
         })
     }
 }
@@ -137,6 +161,8 @@ pub struct RpcBlockInfo {
     pub block_timestamp: BlockTimestamp,
     /// The sequencer address of this block.
     pub sequencer_address: ContractAddress,
+    /// The Starknet version of this block.
+    pub starknet_version: String,
     /// The transactions of this block.
     pub transactions: Vec<SNTransaction>,
 }
@@ -289,6 +315,9 @@ impl RpcState {
     }
 
     pub fn new_infura(chain: RpcChain, block: BlockValue) -> Result<Self, RpcStateError> {
+        if let RpcChain::Custom(_) = chain {
+            return Err(RpcStateError::UnsupportedChain(chain.to_string()));
+        }
         if env::var("INFURA_API_KEY").is_err() {
             dotenv().map_err(|_| RpcStateError::MissingEnvFile)?;
         }
@@ -305,6 +334,26 @@ impl RpcState {
         Ok(Self::new(chain, block, &rpc_endpoint, &feeder_url))
     }
 
+    /// Creates a state reader for the chain. The public networks are read through Infura (see
+    /// [`RpcState::new_infura`]), while custom chains are read from the endpoints in the
+    /// `RPC_ENDPOINT` and `FEEDER_GATEWAY_URL` environment variables (which can be set in the
+    /// `.env` file).
+    pub fn new_from_env(chain: RpcChain, block: BlockValue) -> Result<Self, RpcStateError> {
+        if !matches!(chain, RpcChain::Custom(_)) {
+            return Self::new_infura(chain, block);
+        }
+        if env::var("RPC_ENDPOINT").is_err() || env::var("FEEDER_GATEWAY_URL").is_err() {
+            dotenv().map_err(|_| RpcStateError::MissingEnvFile)?;
+        }
+
+        let env_var =
+            |name: &str| env::var(name).map_err(|_| RpcStateError::MissingEnvVar(name.to_string()));
+        let rpc_endpoint = env_var("RPC_ENDPOINT")?;
+        let feeder_url = env_var("FEEDER_GATEWAY_URL")?;
+
+        Ok(Self::new(chain, block, &rpc_endpoint, &feeder_url))
+    }
+
     fn rpc_call_result<T: for<'a> Deserialize<'a>>(
         &self,
         method: &str,
@@ -463,6 +512,17 @@ impl RpcState {
                     .try_into()
                     .map_err(|_| RpcStateError::StarkFeltToParticiaKeyConversion)?,
             ),
+            starknet_version: block_info
+                .get("result")
+                .and_then(|result| result.get("starknet_version"))
+                .and_then(|v| v.as_str())
+                .map(ToString::to_string)
+                .ok_or_else(|| {
+                    RpcStateError::RpcObjectHasNoField(
+                        "block_info".into(),
+                        "starknet_version".into(),
+                    )
+                })?,
             transactions,
         })
     }
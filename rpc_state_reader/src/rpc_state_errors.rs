@@ -6,6 +6,10 @@ pub enum RpcStateError {
     MissingEnvFile,
     #[error("Missing infura api key")]
     MissingInfuraApiKey,
+    #[error("Missing environment variable {0}")]
+    MissingEnvVar(String),
+    #[error("Chain {0} isn't supported by Infura")]
+    UnsupportedChain(String),
     #[error("RPC call failed with error: {0}")]
     RpcCall(String),
     #[error("Request failed with error: {0}")]
@@ -1,23 +1,23 @@
 use std::sync::Arc;
 
-use cairo_vm::felt::{felt_str, Felt252};
+use cairo_vm::felt::Felt252;
 use starknet_api::{
     block::BlockNumber,
     core::{ClassHash as SNClassHash, ContractAddress, PatriciaKey},
     hash::{StarkFelt, StarkHash},
     stark_felt,
     state::StorageKey,
-    transaction::{Transaction as SNTransaction, TransactionHash, TransactionVersion},
+    transaction::{Fee, Transaction as SNTransaction, TransactionHash},
 };
 use starknet_in_rust::{
-    core::{contract_address::compute_casm_class_hash, errors::state_errors::StateError},
+    core::errors::state_errors::StateError,
     definitions::{
         block_context::{BlockContext, StarknetChainId, StarknetOsConfig},
         constants::{
-            DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS, DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT,
-            DEFAULT_GLOBAL_STATE_COMMITMENT_TREE_HEIGHT, DEFAULT_INVOKE_TX_MAX_N_STEPS,
-            DEFAULT_VALIDATE_MAX_N_STEPS,
+            DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT,
+            DEFAULT_GLOBAL_STATE_COMMITMENT_TREE_HEIGHT,
         },
+        versioned_constants::VersionedConstants,
     },
     execution::TransactionExecutionInfo,
     services::api::contract_classes::compiled_class::CompiledClass,
@@ -25,9 +25,7 @@ use starknet_in_rust::{
         cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
         state_api::StateReader, state_cache::StorageEntry, BlockInfo,
     },
-    transaction::{
-        error::TransactionError, Declare, DeclareV2, DeployAccount, InvokeFunction, L1Handler,
-    },
+    transaction::{error::TransactionError, StarknetApiTransaction, Transaction},
     utils::{Address, ClassHash},
 };
 
@@ -98,12 +96,15 @@ impl StateReader for RpcStateReader {
     }
 }
 
+/// Re-executes the transaction on top of the state of `block_number`. The fee token of the public
+/// networks is known, but custom chains must be given theirs in `fee_token_address`.
 pub fn execute_tx_configurable(
     tx_hash: &str,
     network: RpcChain,
     block_number: BlockNumber,
     skip_validate: bool,
     skip_nonce_check: bool,
+    fee_token_address: Option<Address>,
 ) -> Result<
     (
         TransactionExecutionInfo,
@@ -112,30 +113,31 @@ pub fn execute_tx_configurable(
     ),
     TransactionError,
 > {
-    let fee_token_address = Address(felt_str!(
-        "049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7",
-        16
-    ));
-
     let tx_hash = tx_hash.strip_prefix("0x").unwrap();
 
     // Instantiate the RPC StateReader and the CachedState
-    let rpc_reader = RpcStateReader(RpcState::new_infura(network, block_number.into()).unwrap());
+    let rpc_reader = RpcStateReader(RpcState::new_from_env(network, block_number.into()).unwrap());
     let gas_price = rpc_reader.0.get_gas_price(block_number.0).unwrap();
 
     // Get values for block context before giving ownership of the reader
-    let chain_id = match rpc_reader.0.chain {
-        RpcChain::MainNet => StarknetChainId::MainNet,
-        RpcChain::TestNet => StarknetChainId::TestNet,
-        RpcChain::TestNet2 => StarknetChainId::TestNet2,
+    let chain_id = StarknetChainId::from(rpc_reader.0.chain);
+    let starknet_os_config = match fee_token_address {
+        Some(fee_token_address) => {
+            StarknetOsConfig::new(chain_id.to_felt(), fee_token_address, gas_price)
+        }
+        None => StarknetOsConfig::for_public_network(chain_id, gas_price).ok_or_else(|| {
+            TransactionError::CustomError(format!(
+                "The fee token address of the custom chain {} must be given",
+                chain_id.to_felt().to_str_radix(16)
+            ))
+        })?,
     };
-    let starknet_os_config =
-        StarknetOsConfig::new(chain_id.to_felt(), fee_token_address, gas_price);
-    let block_info = {
+    let (block_info, versioned_constants) = {
         let RpcBlockInfo {
             block_number,
             block_timestamp,
             sequencer_address,
+            starknet_version,
             ..
         } = rpc_reader.0.get_block_info().unwrap();
 
@@ -143,95 +145,45 @@ pub fn execute_tx_configurable(
         let block_timestamp = block_timestamp.0;
         let sequencer_address = Address(Felt252::from_bytes_be(sequencer_address.0.key().bytes()));
 
-        BlockInfo {
-            block_number,
-            block_timestamp,
-            gas_price,
-            sequencer_address,
-        }
+        (
+            BlockInfo {
+                block_number,
+                block_timestamp,
+                gas_price,
+                sequencer_address,
+                ..Default::default()
+            },
+            VersionedConstants::for_starknet_version(&starknet_version)?,
+        )
     };
 
     // Get transaction before giving ownership of the reader
     let tx_hash = TransactionHash(stark_felt!(tx_hash));
-    let tx = match rpc_reader.0.get_transaction(&tx_hash).unwrap() {
-        SNTransaction::Invoke(tx) => InvokeFunction::from_invoke_transaction(tx, chain_id)
-            .unwrap()
-            .create_for_simulation(skip_validate, false, false, false, skip_nonce_check),
-        SNTransaction::DeployAccount(tx) => {
-            DeployAccount::from_sn_api_transaction(tx, chain_id.to_felt())
-                .unwrap()
-                .create_for_simulation(skip_validate, false, false, false, skip_nonce_check)
-        }
-        SNTransaction::Declare(tx) => {
-            // Fetch the contract_class from the next block (as we don't have it in the previous one)
-            let next_block_state_reader = RpcStateReader(
-                RpcState::new_infura(network, (block_number.next()).into()).unwrap(),
-            );
-            let class_hash = tx.class_hash().0.bytes().try_into().unwrap();
-            let contract_class = next_block_state_reader
-                .get_contract_class(&ClassHash(class_hash))
-                .unwrap();
-
-            if tx.version() != TransactionVersion(2_u8.into()) {
-                let contract_class = match contract_class {
-                    CompiledClass::Deprecated(cc) => cc.as_ref().clone(),
-                    _ => unreachable!(),
-                };
-
-                let class_hash = tx.class_hash().0.bytes().try_into().unwrap();
-                let declare = Declare::new_with_tx_and_class_hash(
-                    contract_class,
-                    Address(Felt252::from_bytes_be(tx.sender_address().0.key().bytes())),
-                    tx.max_fee().0,
-                    Felt252::from_bytes_be(tx.version().0.bytes()),
-                    tx.signature()
-                        .0
-                        .iter()
-                        .map(|f| Felt252::from_bytes_be(f.bytes()))
-                        .collect(),
-                    Felt252::from_bytes_be(tx.nonce().0.bytes()),
-                    Felt252::from_bytes_be(tx_hash.0.bytes()),
-                    ClassHash(class_hash),
-                )
-                .unwrap();
-                declare.create_for_simulation(skip_validate, false, false, false, skip_nonce_check)
-            } else {
-                let contract_class = match contract_class {
-                    CompiledClass::Casm(cc) => cc.as_ref().clone(),
-                    _ => unreachable!(),
-                };
-
-                let compiled_class_hash = compute_casm_class_hash(&contract_class).unwrap();
-
-                let declare = DeclareV2::new_with_sierra_class_hash_and_tx_hash(
-                    None,
-                    Felt252::from_bytes_be(tx.class_hash().0.bytes()),
-                    Some(contract_class),
-                    compiled_class_hash,
-                    Address(Felt252::from_bytes_be(tx.sender_address().0.key().bytes())),
-                    tx.max_fee().0,
-                    Felt252::from_bytes_be(tx.version().0.bytes()),
-                    tx.signature()
-                        .0
-                        .iter()
-                        .map(|f| Felt252::from_bytes_be(f.bytes()))
-                        .collect(),
-                    Felt252::from_bytes_be(tx.nonce().0.bytes()),
-                    Felt252::from_bytes_be(tx_hash.0.bytes()),
-                )
-                .unwrap();
-                declare.create_for_simulation(skip_validate, false, false, false, skip_nonce_check)
-            }
-        }
-        SNTransaction::L1Handler(tx) => L1Handler::from_sn_api_tx(
-            tx,
-            Felt252::from_bytes_be(tx_hash.0.bytes()),
-            Some(Felt252::from(u128::MAX)),
-        )
-        .unwrap()
-        .create_for_simulation(skip_validate, false),
-        SNTransaction::Deploy(_) => unimplemented!(),
+    let sn_api_tx = rpc_reader.0.get_transaction(&tx_hash).unwrap();
+    let class_hash = match &sn_api_tx {
+        SNTransaction::Declare(tx) => Some(tx.class_hash()),
+        SNTransaction::Deploy(tx) => Some(tx.class_hash),
+        _ => None,
     };
+    let mut sn_api_tx =
+        StarknetApiTransaction::new(sn_api_tx, tx_hash).with_paid_fee_on_l1(Fee(u128::MAX));
+    if let Some(class_hash) = class_hash {
+        // Fetch the contract_class from the next block (as we don't have it in the previous one)
+        let next_block_state_reader =
+            RpcStateReader(RpcState::new_from_env(network, (block_number.next()).into()).unwrap());
+        let class_hash = class_hash.0.bytes().try_into().unwrap();
+        let contract_class = next_block_state_reader
+            .get_contract_class(&ClassHash(class_hash))
+            .unwrap();
+        sn_api_tx = sn_api_tx.with_contract_class(contract_class);
+    }
+    let tx = Transaction::try_from(sn_api_tx)?.create_for_simulation(
+        skip_validate,
+        false,
+        false,
+        false,
+        skip_nonce_check,
+    );
 
     let trace = rpc_reader.0.get_transaction_trace(&tx_hash).unwrap();
     let receipt = rpc_reader.0.get_transaction_receipt(&tx_hash).unwrap();
@@ -239,13 +191,12 @@ pub fn execute_tx_configurable(
     let class_cache = PermanentContractClassCache::default();
     let mut state = CachedState::new(Arc::new(rpc_reader), Arc::new(class_cache));
 
-    let block_context = BlockContext::new(
+    // Execute the transaction with the constants of the block's Starknet version.
+    let block_context = BlockContext::new_with_versioned_constants(
         starknet_os_config,
         DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT,
         DEFAULT_GLOBAL_STATE_COMMITMENT_TREE_HEIGHT,
-        DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS.clone(),
-        DEFAULT_INVOKE_TX_MAX_N_STEPS,
-        DEFAULT_VALIDATE_MAX_N_STEPS,
+        Arc::new(versioned_constants),
         block_info,
         Default::default(),
         true,
@@ -271,7 +222,7 @@ pub fn execute_tx(
     ),
     TransactionError,
 > {
-    execute_tx_configurable(tx_hash, network, block_number, false, false)
+    execute_tx_configurable(tx_hash, network, block_number, false, false, None)
 }
 
 pub fn execute_tx_without_validate(
@@ -286,13 +237,13 @@ pub fn execute_tx_without_validate(
     ),
     TransactionError,
 > {
-    execute_tx_configurable(tx_hash, network, block_number, true, true)
+    execute_tx_configurable(tx_hash, network, block_number, true, true, None)
 }
 
 pub fn get_transaction_hashes(
     block_number: BlockNumber,
     network: RpcChain,
 ) -> Result<Vec<String>, RpcStateError> {
-    let rpc_state = RpcState::new_infura(network, BlockValue::Number(block_number))?;
+    let rpc_state = RpcState::new_from_env(network, BlockValue::Number(block_number))?;
     rpc_state.get_transaction_hashes()
 }
@@ -5,16 +5,10 @@ use pretty_assertions_sorted::{assert_eq, assert_eq_sorted};
 use rpc_state_reader::{
     execute_tx, execute_tx_configurable, execute_tx_without_validate, rpc_state::*,
 };
-use starknet_api::{
-    block::BlockNumber,
-    hash::StarkFelt,
-    stark_felt,
-    transaction::{Transaction as SNTransaction, TransactionHash},
-};
+use starknet_api::{block::BlockNumber, hash::StarkFelt, stark_felt, transaction::TransactionHash};
 use starknet_in_rust::{
-    definitions::block_context::StarknetChainId,
     execution::{CallInfo, TransactionExecutionInfo},
-    transaction::InvokeFunction,
+    transaction::{StarknetApiTransaction, Transaction},
 };
 use test_case::test_case;
 
@@ -25,15 +19,10 @@ fn test_get_transaction_try_from() {
     let tx_hash = TransactionHash(str_hash);
 
     let sn_tx = rpc_state.get_transaction(&tx_hash).unwrap();
-    match &sn_tx {
-        SNTransaction::Invoke(sn_tx) => {
-            let tx =
-                InvokeFunction::from_invoke_transaction(sn_tx.clone(), StarknetChainId::MainNet)
-                    .unwrap();
-            assert_eq!(tx.hash_value().to_be_bytes().as_slice(), str_hash.bytes())
-        }
-        _ => unimplemented!(),
-    };
+    let tx = Transaction::try_from(StarknetApiTransaction::new(sn_tx.clone(), tx_hash)).unwrap();
+    assert!(matches!(tx, Transaction::InvokeFunction(_)));
+    assert_eq!(tx.hash_value().to_be_bytes().as_slice(), str_hash.bytes());
+    assert_eq!(StarknetApiTransaction::try_from(tx).unwrap().tx, sn_tx);
 }
 
 #[test]
@@ -323,7 +312,7 @@ fn starknet_in_rust_test_case_declare_tx(hash: &str, block_number: u64, chain: R
 )]
 fn starknet_in_rust_test_case_tx_skip_nonce_check(hash: &str, block_number: u64, chain: RpcChain) {
     let (tx_info, trace, receipt) =
-        execute_tx_configurable(hash, chain, BlockNumber(block_number), false, true).unwrap();
+        execute_tx_configurable(hash, chain, BlockNumber(block_number), false, true, None).unwrap();
 
     let TransactionExecutionInfo {
         call_info,
@@ -139,7 +139,7 @@ pub fn new_starknet_block_context_for_testing() -> BlockContext {
         0,
         Default::default(),
         1_000_000,
-        0,
+        1_000_000,
         BlockInfo::default(),
         HashMap::default(),
         true,
@@ -0,0 +1,282 @@
+//! # Bouncer
+//!
+//! Block builders use a [`Bouncer`] to decide which transactions fit in the block under
+//! construction. It accumulates the weights of the transactions added to the block (VM steps,
+//! builtins, state diff size, L1 gas used by messages and emitted events) and rejects those that
+//! would push the block over its limits.
+//!
+//! The weights of a transaction are taken from its execution info and the state changes it made,
+//! which can be counted by executing it on a transactional state:
+//!
+//! ```ignore
+//! let mut transactional_state = state.create_transactional()?;
+//! let execution_info = tx.execute(&mut transactional_state, &block_context, remaining_gas)?;
+//! let state_changes = transactional_state.count_actual_state_changes(None)?;
+//! if bouncer.try_add(&execution_info, &state_changes)? {
+//!     state.apply_state_update(&StateDiff::from_cached_state(transactional_state.cache())?)?;
+//! }
+//! ```
+
+use crate::{
+    definitions::transaction_type::TransactionType,
+    execution::{
+        gas_usage::{calculate_tx_gas_vector, get_onchain_data_segment_length},
+        TransactionExecutionInfo,
+    },
+    state::state_api::StateChangesCount,
+    transaction::error::TransactionError,
+};
+use getset::Getters;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Resources counted against the capacity of a block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BouncerWeights {
+    /// VM steps, including the ones used by the OS.
+    pub n_steps: usize,
+    /// Builtin instances used, by builtin name (e.g. `pedersen_builtin`). When used as limits,
+    /// builtins without an entry are unlimited.
+    pub builtin_count: HashMap<String, usize>,
+    /// Words of the state diff posted to L1.
+    pub state_diff_size: usize,
+    /// L1 gas used by the messages sent to and consumed from L1.
+    pub message_l1_gas: usize,
+    /// Emitted events.
+    pub n_events: usize,
+}
+
+impl BouncerWeights {
+    /// Returns limits which no block can reach.
+    pub fn unlimited() -> Self {
+        Self {
+            n_steps: usize::MAX,
+            builtin_count: HashMap::new(),
+            state_diff_size: usize::MAX,
+            message_l1_gas: usize::MAX,
+            n_events: usize::MAX,
+        }
+    }
+
+    /// Calculates the weights of an executed transaction, given the state changes it made.
+    pub fn from_execution_info(
+        execution_info: &TransactionExecutionInfo,
+        state_changes: &StateChangesCount,
+    ) -> Result<Self, TransactionError> {
+        let builtin_count = execution_info
+            .actual_resources
+            .iter()
+            .filter(|(name, _)| name.ends_with("_builtin"))
+            .map(|(name, count)| (name.clone(), *count))
+            .collect();
+
+        // The L1 handler's calldata includes the "from" field, which is not part of the payload.
+        let l1_handler_payload_size = match execution_info.tx_type {
+            Some(TransactionType::L1Handler) => Some(
+                execution_info
+                    .call_info
+                    .as_ref()
+                    .map(|call_info| call_info.calldata.len().saturating_sub(1))
+                    .unwrap_or_default(),
+            ),
+            _ => None,
+        };
+        // With the state diff posted as blobs, the L1 gas only accounts for the messages.
+        let message_l1_gas = calculate_tx_gas_vector(
+            execution_info.get_sorted_l2_to_l1_messages()?,
+            &StateChangesCount::default(),
+            l1_handler_payload_size,
+            true,
+        )
+        .l1_gas;
+
+        let n_events = execution_info
+            .non_optional_calls()
+            .iter()
+            .flat_map(|call_info| call_info.gen_call_topology())
+            .map(|call_info| call_info.events.len())
+            .sum();
+
+        Ok(Self {
+            n_steps: execution_info
+                .actual_resources
+                .get("n_steps")
+                .copied()
+                .unwrap_or_default(),
+            builtin_count,
+            state_diff_size: get_onchain_data_segment_length(state_changes),
+            message_l1_gas,
+            n_events,
+        })
+    }
+
+    /// Returns whether these weights are within the given limits.
+    pub fn fits_within(&self, limits: &BouncerWeights) -> bool {
+        self.n_steps <= limits.n_steps
+            && self.state_diff_size <= limits.state_diff_size
+            && self.message_l1_gas <= limits.message_l1_gas
+            && self.n_events <= limits.n_events
+            && self.builtin_count.iter().all(|(name, count)| {
+                limits
+                    .builtin_count
+                    .get(name)
+                    .map_or(true, |limit| count <= limit)
+            })
+    }
+
+    /// Returns the sum of both weights.
+    pub fn checked_add(&self, other: &BouncerWeights) -> Option<BouncerWeights> {
+        let mut builtin_count = self.builtin_count.clone();
+        for (name, count) in other.builtin_count.iter() {
+            let total = builtin_count.entry(name.clone()).or_default();
+            *total = total.checked_add(*count)?;
+        }
+
+        Some(BouncerWeights {
+            n_steps: self.n_steps.checked_add(other.n_steps)?,
+            builtin_count,
+            state_diff_size: self.state_diff_size.checked_add(other.state_diff_size)?,
+            message_l1_gas: self.message_l1_gas.checked_add(other.message_l1_gas)?,
+            n_events: self.n_events.checked_add(other.n_events)?,
+        })
+    }
+}
+
+/// Tracks the capacity used by the block under construction.
+#[derive(Clone, Debug, Getters)]
+#[getset(get = "pub")]
+pub struct Bouncer {
+    /// Capacity of the block.
+    limits: BouncerWeights,
+    /// Weights of the transactions added to the block so far.
+    accumulated: BouncerWeights,
+}
+
+impl Bouncer {
+    /// Creates a bouncer for an empty block with the given capacity.
+    pub fn new(limits: BouncerWeights) -> Self {
+        Self {
+            limits,
+            accumulated: BouncerWeights::default(),
+        }
+    }
+
+    /// Returns whether a transaction with the given weights still fits in the block.
+    pub fn fits(&self, weights: &BouncerWeights) -> bool {
+        self.accumulated
+            .checked_add(weights)
+            .map_or(false, |total| total.fits_within(&self.limits))
+    }
+
+    /// Adds the transaction to the block if it still fits, and returns whether it was added.
+    pub fn try_add(
+        &mut self,
+        execution_info: &TransactionExecutionInfo,
+        state_changes: &StateChangesCount,
+    ) -> Result<bool, TransactionError> {
+        let weights = BouncerWeights::from_execution_info(execution_info, state_changes)?;
+        Ok(self.try_add_weights(&weights))
+    }
+
+    /// Adds the weights to the block if they still fit, and returns whether they were added.
+    pub fn try_add_weights(&mut self, weights: &BouncerWeights) -> bool {
+        match self.accumulated.checked_add(weights) {
+            Some(total) if total.fits_within(&self.limits) => {
+                self.accumulated = total;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Empties the block, keeping its capacity.
+    pub fn reset(&mut self) {
+        self.accumulated = BouncerWeights::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        execution::{CallInfo, OrderedEvent, OrderedL2ToL1Message},
+        utils::Address,
+    };
+    use coverage_helper::test;
+
+    fn execution_info() -> TransactionExecutionInfo {
+        let call_info = CallInfo {
+            events: vec![
+                OrderedEvent::new(0, vec![1.into()], vec![]),
+                OrderedEvent::new(1, vec![2.into()], vec![]),
+            ],
+            l2_to_l1_messages: vec![OrderedL2ToL1Message::new(0, Address(1.into()), vec![])],
+            ..Default::default()
+        };
+        TransactionExecutionInfo {
+            call_info: Some(call_info),
+            actual_resources: HashMap::from([
+                ("n_steps".to_string(), 1000),
+                ("pedersen_builtin".to_string(), 10),
+                ("l1_gas_usage".to_string(), 5000),
+            ]),
+            tx_type: Some(TransactionType::InvokeFunction),
+            ..Default::default()
+        }
+    }
+
+    fn state_changes() -> StateChangesCount {
+        StateChangesCount {
+            n_storage_updates: 2,
+            n_modified_contracts: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn transaction_weights() {
+        let weights =
+            BouncerWeights::from_execution_info(&execution_info(), &state_changes()).unwrap();
+
+        assert_eq!(weights.n_steps, 1000);
+        assert_eq!(
+            weights.builtin_count,
+            HashMap::from([("pedersen_builtin".to_string(), 10)])
+        );
+        assert_eq!(weights.state_diff_size, 6);
+        assert!(weights.message_l1_gas > 0);
+        assert_eq!(weights.n_events, 2);
+    }
+
+    #[test]
+    fn bouncer_rejects_transactions_over_the_limits() {
+        let limits = BouncerWeights {
+            n_steps: 2500,
+            builtin_count: HashMap::from([("pedersen_builtin".to_string(), 100)]),
+            ..BouncerWeights::unlimited()
+        };
+        let mut bouncer = Bouncer::new(limits);
+
+        assert!(bouncer
+            .try_add(&execution_info(), &state_changes())
+            .unwrap());
+        assert!(bouncer
+            .try_add(&execution_info(), &state_changes())
+            .unwrap());
+        // A third transaction would use 3000 steps.
+        assert!(!bouncer
+            .try_add(&execution_info(), &state_changes())
+            .unwrap());
+        assert_eq!(bouncer.accumulated().n_steps, 2000);
+        assert_eq!(bouncer.accumulated().n_events, 4);
+
+        let pedersen_heavy = BouncerWeights {
+            builtin_count: HashMap::from([("pedersen_builtin".to_string(), 81)]),
+            ..Default::default()
+        };
+        assert!(!bouncer.fits(&pedersen_heavy));
+
+        bouncer.reset();
+        assert!(bouncer.fits(&pedersen_heavy));
+    }
+}
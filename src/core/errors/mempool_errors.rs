@@ -0,0 +1,28 @@
+use crate::{
+    core::errors::{state_errors::StateError, validation_errors::ValidationError},
+    utils::Address,
+};
+use cairo_vm::felt::Felt252;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MempoolError {
+    #[error("Transaction {0} is already in the mempool")]
+    DuplicateTransaction(Felt252),
+    #[error("Transaction {0} can't be added to the mempool, as it isn't sent by an account")]
+    UnsupportedTransaction(Felt252),
+    #[error("Invalid transaction nonce for account {}. Expected at least {1}, got {2}", (.0).0)]
+    NonceTooOld(Address, Felt252, Felt252),
+    #[error("Transaction nonce {2} for account {} is too far ahead of the account nonce {1}", (.0).0)]
+    NonceTooFarAhead(Address, Felt252, Felt252),
+    #[error("A transaction with nonce {1} for account {} is already in the mempool", (.0).0)]
+    ReplacementDisabled(Address, Felt252),
+    #[error("Replacement transaction underpriced. Required max fee: {0}, got: {1}")]
+    ReplacementUnderpriced(u128, u128),
+    #[error("The mempool is full")]
+    MempoolFull,
+    #[error("Transaction validation failed: {0}")]
+    Validation(#[from] ValidationError),
+    #[error(transparent)]
+    State(#[from] StateError),
+}
@@ -1,3 +1,6 @@
 pub mod contract_address_errors;
 pub mod hash_errors;
+pub mod mempool_errors;
+pub mod signer_errors;
 pub mod state_errors;
+pub mod validation_errors;
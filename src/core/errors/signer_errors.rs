@@ -0,0 +1,17 @@
+use crate::{
+    definitions::transaction_type::TransactionType,
+    syscalls::syscall_handler_errors::SyscallHandlerError,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SignerError {
+    #[error("Invalid private key")]
+    InvalidPrivateKey,
+    #[error("Failed to sign the hash: {0}")]
+    Sign(String),
+    #[error("{0:?} transactions aren't signed")]
+    UnsignedTransaction(TransactionType),
+    #[error(transparent)]
+    Conversion(#[from] SyscallHandlerError),
+}
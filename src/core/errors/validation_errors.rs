@@ -0,0 +1,53 @@
+use crate::{
+    core::errors::state_errors::StateError, definitions::transaction_type::TransactionType,
+    transaction::error::TransactionError,
+};
+use cairo_vm::felt::Felt252;
+use thiserror::Error;
+
+/// The reason a transaction was rejected by [`validate_transaction`](crate::validate_transaction).
+#[derive(Debug, Error)]
+pub enum ValidationError {
+    #[error("Invalid transaction nonce. Expected: {0} got {1}")]
+    InvalidNonce(String, String),
+    #[error("Max fee ({0}) is too low. Minimum fee: {1}.")]
+    MaxFeeTooLow(u128, u128),
+    #[error("Max fee ({0}) exceeds balance (Uint256({1}, {2})).")]
+    InsufficientBalance(u128, Felt252, Felt252),
+    #[error("The account's validate entry point failed (e.g. the signature is invalid): {0}")]
+    ValidateFailed(String),
+    #[error("The `validate` entry point should return `VALID`.")]
+    InvalidValidateRetdata,
+    #[error("The validation exceeded the limit of {0} steps")]
+    StepLimitExceeded(u64),
+    #[error("{0:?} transactions aren't validated")]
+    NotValidated(TransactionType),
+    #[error(transparent)]
+    State(#[from] StateError),
+    #[error(transparent)]
+    Transaction(TransactionError),
+}
+
+impl From<TransactionError> for ValidationError {
+    fn from(error: TransactionError) -> Self {
+        match error {
+            TransactionError::InvalidTransactionNonce(expected, actual) => {
+                ValidationError::InvalidNonce(expected, actual)
+            }
+            TransactionError::MaxFeeTooLow(max_fee, minimal_fee) => {
+                ValidationError::MaxFeeTooLow(max_fee, minimal_fee)
+            }
+            TransactionError::MaxFeeExceedsBalance(max_fee, balance_low, balance_high) => {
+                ValidationError::InsufficientBalance(max_fee, balance_low, balance_high)
+            }
+            TransactionError::WrongValidateRetdata => ValidationError::InvalidValidateRetdata,
+            TransactionError::Vm(_)
+            | TransactionError::CairoRunner(_)
+            | TransactionError::Syscall(_)
+            | TransactionError::InvalidContractCall => {
+                ValidationError::ValidateFailed(error.to_string())
+            }
+            error => ValidationError::Transaction(error),
+        }
+    }
+}
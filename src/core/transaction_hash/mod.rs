@@ -251,4 +251,33 @@ mod tests {
             )
         )
     }
+
+    #[test]
+    fn custom_chain_ids_are_part_of_the_hash() {
+        let sender_address = Address(0x42.into());
+        let calculate_hash = |chain_id: StarknetChainId| {
+            calculate_transaction_hash_common(
+                TransactionHashPrefix::Invoke,
+                1.into(),
+                &sender_address,
+                Felt252::zero(),
+                &[],
+                0,
+                chain_id.into(),
+                &[0.into()],
+            )
+            .unwrap()
+        };
+
+        let app_chain_hash =
+            calculate_hash(StarknetChainId::from_short_string("SN_APPCHAIN").unwrap());
+        assert_eq!(
+            app_chain_hash,
+            calculate_hash(StarknetChainId::from_felt(Felt252::from_bytes_be(
+                b"SN_APPCHAIN"
+            )))
+        );
+        assert_ne!(app_chain_hash, calculate_hash(StarknetChainId::Sepolia));
+        assert_ne!(app_chain_hash, calculate_hash(StarknetChainId::MainNet));
+    }
 }
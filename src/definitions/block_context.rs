@@ -2,21 +2,29 @@
 //!
 //! This module contains structs representing the context of a specific Starknet block.
 
-use crate::{state::BlockInfo, utils::Address};
+use crate::{
+    state::BlockInfo,
+    syscalls::{cheatcodes::Cheatcodes, custom_syscalls::CustomSyscalls},
+    utils::Address,
+};
 use cairo_vm::felt::Felt252;
 use core::fmt;
 use getset::{CopyGetters, Getters, MutGetters};
 use starknet_api::block::Block;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use super::constants::{
-    DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS, DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT,
-    DEFAULT_GLOBAL_STATE_COMMITMENT_TREE_HEIGHT, DEFAULT_INVOKE_TX_MAX_N_STEPS,
-    DEFAULT_SEQUENCER_ADDRESS, DEFAULT_STARKNET_OS_CONFIG, DEFAULT_VALIDATE_MAX_N_STEPS,
+    DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT, DEFAULT_GLOBAL_STATE_COMMITMENT_TREE_HEIGHT,
+    DEFAULT_SEQUENCER_ADDRESS, DEFAULT_STARKNET_OS_CONFIG, ETH_FEE_TOKEN_ADDRESS,
 };
+use super::libfunc_allow_list::LibfuncAllowList;
+use super::versioned_constants::VersionedConstants;
 
 /// Unique identifier of a Starknet chain.
-#[derive(Debug, Clone, Copy)]
+///
+/// The public networks have named variants; any other chain (like an app-chain or a private
+/// network) is represented by its raw chain ID with [`StarknetChainId::Custom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum StarknetChainId {
     /// Starknet main chain
     MainNet,
@@ -24,6 +32,25 @@ pub enum StarknetChainId {
     TestNet,
     /// Starknet second test chain (Goerli 2)
     TestNet2,
+    /// Starknet Sepolia test chain
+    Sepolia,
+    /// Starknet integration chain (Sepolia)
+    IntegrationSepolia,
+    /// Any other chain, identified by its chain ID.
+    Custom(CustomChainId),
+}
+
+/// The chain ID of a chain which isn't a public network. It can only be built through
+/// [`StarknetChainId::from_felt`], so public networks are always represented by their named
+/// variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CustomChainId([u8; 32]);
+
+impl CustomChainId {
+    /// Returns the chain ID as a field element.
+    pub fn to_felt(self) -> Felt252 {
+        Felt252::from_bytes_be(&self.0)
+    }
 }
 
 impl fmt::Display for StarknetChainId {
@@ -32,11 +59,35 @@ impl fmt::Display for StarknetChainId {
             StarknetChainId::MainNet => write!(f, "SN_MAIN"),
             StarknetChainId::TestNet => write!(f, "SN_GOERLI"),
             StarknetChainId::TestNet2 => write!(f, "SN_GOERLI2"),
+            StarknetChainId::Sepolia => write!(f, "SN_SEPOLIA"),
+            StarknetChainId::IntegrationSepolia => write!(f, "SN_INTEGRATION_SEPOLIA"),
+            // Chain IDs are usually short strings, otherwise they're shown in hex.
+            StarknetChainId::Custom(CustomChainId(bytes)) => {
+                let start = bytes
+                    .iter()
+                    .position(|byte| *byte != 0)
+                    .unwrap_or(bytes.len());
+                match std::str::from_utf8(&bytes[start..]) {
+                    Ok(name) if !name.is_empty() && name.chars().all(|c| c.is_ascii_graphic()) => {
+                        write!(f, "{name}")
+                    }
+                    _ => write!(f, "0x{}", self.to_felt().to_str_radix(16)),
+                }
+            }
         }
     }
 }
 
 impl StarknetChainId {
+    /// The public Starknet networks.
+    pub const PUBLIC_NETWORKS: [StarknetChainId; 5] = [
+        StarknetChainId::MainNet,
+        StarknetChainId::TestNet,
+        StarknetChainId::TestNet2,
+        StarknetChainId::Sepolia,
+        StarknetChainId::IntegrationSepolia,
+    ];
+
     /// Returns the chain ID's representation as a field element.
     ///
     /// # Examples
@@ -57,9 +108,54 @@ impl StarknetChainId {
     ///     StarknetChainId::TestNet2.to_felt(),
     ///     felt_str!("393402129659245999442226"),
     /// );
+    /// assert_eq!(
+    ///     StarknetChainId::from_felt(felt_str!("1234")).to_felt(),
+    ///     felt_str!("1234"),
+    /// );
     /// ```
     pub fn to_felt(self) -> Felt252 {
-        Felt252::from_bytes_be(self.to_string().as_bytes())
+        match self {
+            StarknetChainId::Custom(chain_id) => chain_id.to_felt(),
+            chain_id => Felt252::from_bytes_be(chain_id.to_string().as_bytes()),
+        }
+    }
+
+    /// Returns the chain with the given chain ID, which is one of the named public networks if it
+    /// matches one.
+    pub fn from_felt(chain_id: Felt252) -> Self {
+        Self::PUBLIC_NETWORKS
+            .into_iter()
+            .find(|network| network.to_felt() == chain_id)
+            .unwrap_or(StarknetChainId::Custom(CustomChainId(
+                chain_id.to_be_bytes(),
+            )))
+    }
+
+    /// Returns the chain whose chain ID is the given short string (like `SN_SEPOLIA`), or `None`
+    /// if it isn't a valid short string: between 1 and 31 ASCII characters.
+    pub fn from_short_string(chain_id: &str) -> Option<Self> {
+        (!chain_id.is_empty() && chain_id.len() <= 31 && chain_id.is_ascii())
+            .then(|| Self::from_felt(Felt252::from_bytes_be(chain_id.as_bytes())))
+    }
+
+    /// Returns the address of the token used to pay fees in the chain, if it's a public network.
+    pub fn fee_token_address(self) -> Option<Address> {
+        match self {
+            StarknetChainId::Custom(_) => None,
+            _ => Some(ETH_FEE_TOKEN_ADDRESS.clone()),
+        }
+    }
+}
+
+impl From<Felt252> for StarknetChainId {
+    fn from(chain_id: Felt252) -> Self {
+        Self::from_felt(chain_id)
+    }
+}
+
+impl From<StarknetChainId> for Felt252 {
+    fn from(chain_id: StarknetChainId) -> Self {
+        chain_id.to_felt()
     }
 }
 
@@ -92,6 +188,23 @@ impl StarknetOsConfig {
             gas_price,
         }
     }
+
+    /// Creates the configuration of a public network, with its fee token. Returns `None` for
+    /// custom chains, whose fee token must be given to [`StarknetOsConfig::new`].
+    pub fn for_public_network(chain_id: StarknetChainId, gas_price: u128) -> Option<Self> {
+        let fee_token_address = chain_id.fee_token_address()?;
+        Some(StarknetOsConfig::new(
+            chain_id.to_felt(),
+            fee_token_address,
+            gas_price,
+        ))
+    }
+
+    /// Returns the configured chain, which is one of the named public networks if its chain ID
+    /// matches one.
+    pub fn starknet_chain_id(&self) -> StarknetChainId {
+        StarknetChainId::from_felt(self.chain_id.clone())
+    }
 }
 
 impl Default for StarknetOsConfig {
@@ -109,18 +222,28 @@ pub struct BlockContext {
     pub(crate) contract_storage_commitment_tree_height: u64,
     #[get_copy = "pub"]
     global_state_commitment_tree_height: u64,
-    #[get = "pub"]
-    pub(crate) cairo_resource_fee_weights: HashMap<String, f64>,
-    #[get_copy = "pub"]
-    pub(crate) invoke_tx_max_n_steps: u64,
-    #[get_copy = "pub"]
-    pub(crate) validate_max_n_steps: u64,
+    /// Execution parameters of the block's Starknet version.
+    #[getset(get = "pub", get_mut = "pub")]
+    pub(crate) versioned_constants: Arc<VersionedConstants>,
     #[getset(get = "pub", get_mut = "pub")]
     pub(crate) block_info: BlockInfo,
     /// Contains the blocks in the range [ current_block - 1024, current_block - 10 ]
     #[getset(get = "pub", get_mut = "pub")]
     pub(crate) blocks: HashMap<u64, Block>,
     pub(crate) enforce_l1_handler_fee: bool,
+    /// Libfuncs allowed in the Sierra classes declared with `DeclareV2` transactions.
+    /// Allows every libfunc by default.
+    #[getset(get = "pub", get_mut = "pub")]
+    pub(crate) allowed_libfuncs: LibfuncAllowList,
+    /// Overrides of the execution environment seen by contracts, for testing.
+    #[getset(get = "pub", get_mut = "pub")]
+    pub(crate) cheatcodes: Cheatcodes,
+    /// Syscalls registered by the embedder, available to Cairo 1 contracts.
+    #[getset(get = "pub", get_mut = "pub")]
+    pub(crate) custom_syscalls: CustomSyscalls,
+    /// Whether to record the syscalls made by every call in its `CallInfo`. Disabled by default.
+    #[getset(get_copy = "pub", get_mut = "pub")]
+    pub(crate) trace_syscalls: bool,
 }
 
 impl BlockContext {
@@ -138,8 +261,13 @@ impl BlockContext {
     /// * `blocks` - Blocks in the range [ current_block - 1024, current_block - 10 ].
     ///     Example: for block number 6351, this includes the blocks 5327, 5328, ..., 6340, 6341.
     /// * `enforce_l1_handler_fee` - Whether to enforce the L1 handler fee.
+    ///
+    /// The fee weights and step limits override those of the default Starknet version's
+    /// [`VersionedConstants`] (see `DEFAULT_STARKNET_VERSION`), which provide the remaining
+    /// execution parameters. Use [`BlockContext::new_with_versioned_constants`] to execute blocks
+    /// of other Starknet versions.
     #[allow(clippy::too_many_arguments)]
-    pub const fn new(
+    pub fn new(
         starknet_os_config: StarknetOsConfig,
         contract_storage_commitment_tree_height: u64,
         global_state_commitment_tree_height: u64,
@@ -149,19 +277,78 @@ impl BlockContext {
         block_info: BlockInfo,
         blocks: HashMap<u64, Block>,
         enforce_l1_handler_fee: bool,
+    ) -> Self {
+        let mut versioned_constants = VersionedConstants::default_version();
+        if versioned_constants.cairo_resource_fee_weights != cairo_resource_fee_weights
+            || versioned_constants.invoke_tx_max_n_steps != invoke_tx_max_n_steps
+            || versioned_constants.validate_max_n_steps != validate_max_n_steps
+        {
+            let constants = Arc::make_mut(&mut versioned_constants);
+            constants.cairo_resource_fee_weights = cairo_resource_fee_weights;
+            constants.invoke_tx_max_n_steps = invoke_tx_max_n_steps;
+            constants.validate_max_n_steps = validate_max_n_steps;
+        }
+
+        Self::new_with_versioned_constants(
+            starknet_os_config,
+            contract_storage_commitment_tree_height,
+            global_state_commitment_tree_height,
+            versioned_constants,
+            block_info,
+            blocks,
+            enforce_l1_handler_fee,
+        )
+    }
+
+    /// Creates a new [`BlockContext`] using the given execution parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `starknet_os_config` - Starknet OS configuration.
+    /// * `contract_storage_commitment_tree_height` - Height of the contract storage commitment tree.
+    /// * `global_state_commitment_tree_height` - Height of the global state commitment tree.
+    /// * `versioned_constants` - Execution parameters of the block's Starknet version.
+    /// * `block_info` - Information about the current block.
+    /// * `blocks` - Blocks in the range [ current_block - 1024, current_block - 10 ].
+    /// * `enforce_l1_handler_fee` - Whether to enforce the L1 handler fee.
+    pub fn new_with_versioned_constants(
+        starknet_os_config: StarknetOsConfig,
+        contract_storage_commitment_tree_height: u64,
+        global_state_commitment_tree_height: u64,
+        versioned_constants: Arc<VersionedConstants>,
+        block_info: BlockInfo,
+        blocks: HashMap<u64, Block>,
+        enforce_l1_handler_fee: bool,
     ) -> Self {
         Self {
             starknet_os_config,
             contract_storage_commitment_tree_height,
             global_state_commitment_tree_height,
-            invoke_tx_max_n_steps,
-            cairo_resource_fee_weights,
-            validate_max_n_steps,
+            versioned_constants,
             block_info,
             blocks,
             enforce_l1_handler_fee,
+            allowed_libfuncs: LibfuncAllowList::allow_all(),
+            cheatcodes: Cheatcodes::default(),
+            custom_syscalls: CustomSyscalls::default(),
+            trace_syscalls: false,
         }
     }
+
+    /// Returns the weights used when calculating transaction fees.
+    pub fn cairo_resource_fee_weights(&self) -> &HashMap<String, f64> {
+        self.versioned_constants.cairo_resource_fee_weights()
+    }
+
+    /// Returns the maximum number of steps allowed when executing transactions.
+    pub fn invoke_tx_max_n_steps(&self) -> u64 {
+        self.versioned_constants.invoke_tx_max_n_steps()
+    }
+
+    /// Returns the maximum number of steps allowed when validating transactions.
+    pub fn validate_max_n_steps(&self) -> u64 {
+        self.versioned_constants.validate_max_n_steps()
+    }
 }
 
 impl Default for BlockContext {
@@ -171,12 +358,14 @@ impl Default for BlockContext {
             contract_storage_commitment_tree_height:
                 DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT,
             global_state_commitment_tree_height: DEFAULT_GLOBAL_STATE_COMMITMENT_TREE_HEIGHT,
-            invoke_tx_max_n_steps: DEFAULT_INVOKE_TX_MAX_N_STEPS,
-            cairo_resource_fee_weights: DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS.clone(),
-            validate_max_n_steps: DEFAULT_VALIDATE_MAX_N_STEPS,
+            versioned_constants: VersionedConstants::default_version(),
             block_info: BlockInfo::empty(DEFAULT_SEQUENCER_ADDRESS.clone()),
             blocks: HashMap::default(),
             enforce_l1_handler_fee: true,
+            allowed_libfuncs: LibfuncAllowList::allow_all(),
+            cheatcodes: Cheatcodes::default(),
+            custom_syscalls: CustomSyscalls::default(),
+            trace_syscalls: false,
         }
     }
 }
@@ -202,4 +391,58 @@ mod tests {
             felt_str!("393402129659245999442226"),
         );
     }
+
+    #[test]
+    fn custom_chain_ids() {
+        let app_chain = StarknetChainId::from_short_string("SN_APPCHAIN").unwrap();
+        assert_eq!(
+            app_chain,
+            StarknetChainId::from_felt(Felt252::from_bytes_be(b"SN_APPCHAIN"))
+        );
+        assert_eq!(app_chain.to_felt(), Felt252::from_bytes_be(b"SN_APPCHAIN"));
+        assert_eq!(app_chain.to_string(), "SN_APPCHAIN");
+        assert_eq!(app_chain.fee_token_address(), None);
+        assert_eq!(
+            StarknetChainId::from_felt(0x1234.into()).to_string(),
+            "0x1234"
+        );
+        assert_eq!(StarknetChainId::from_short_string(&"A".repeat(32)), None);
+        assert_eq!(StarknetChainId::from_short_string(""), None);
+
+        // Known chain IDs are mapped to the named networks.
+        for network in StarknetChainId::PUBLIC_NETWORKS {
+            assert_eq!(StarknetChainId::from_felt(network.to_felt()), network);
+            assert_eq!(
+                StarknetChainId::from_short_string(&network.to_string()),
+                Some(network)
+            );
+        }
+        assert_eq!(
+            StarknetChainId::from_felt(Felt252::from_bytes_be(b"SN_SEPOLIA")),
+            StarknetChainId::Sepolia
+        );
+    }
+
+    #[test]
+    fn public_network_presets() {
+        let config = StarknetOsConfig::for_public_network(StarknetChainId::Sepolia, 10).unwrap();
+        assert_eq!(config.chain_id(), &Felt252::from_bytes_be(b"SN_SEPOLIA"));
+        assert_eq!(
+            config.fee_token_address(),
+            &Address(felt_str!(
+                "49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7",
+                16
+            ))
+        );
+        assert_eq!(config.gas_price(), &10);
+        assert_eq!(config.starknet_chain_id(), StarknetChainId::Sepolia);
+
+        let app_chain = StarknetChainId::from_felt(0x1234.into());
+        assert!(StarknetOsConfig::for_public_network(app_chain, 10).is_none());
+        assert_eq!(
+            StarknetOsConfig::new(app_chain.to_felt(), Address(0x42.into()), 10)
+                .starknet_chain_id(),
+            app_chain
+        );
+    }
 }
@@ -46,6 +46,11 @@ lazy_static! {
         "3711666a3506c99c9d78c4d4013409a87a962b7a0880a1c24af9fe193dafc01",
         16
     ));
+    /// Address of the ETH token, used to pay fees in the public Starknet networks.
+    pub static ref ETH_FEE_TOKEN_ADDRESS: Address = Address(felt_str!(
+        "49d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7",
+        16
+    ));
     pub static ref DEFAULT_STARKNET_OS_CONFIG: StarknetOsConfig = StarknetOsConfig {
         chain_id: StarknetChainId::TestNet.to_felt(),
         fee_token_address: Address(felt_str!(
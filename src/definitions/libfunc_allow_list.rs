@@ -0,0 +1,193 @@
+//! # Sierra libfunc allow-list
+//!
+//! The sequencer only accepts the declaration of Sierra classes whose libfuncs are in its list of
+//! allowed libfuncs. This module contains the allow-list that `DeclareV2` transactions are checked
+//! against, configured through the `BlockContext`.
+
+use crate::transaction::error::TransactionError;
+use cairo_lang_starknet::{
+    allowed_libfuncs::{
+        lookup_allowed_libfuncs_list, ListSelector, BUILTIN_AUDITED_LIBFUNCS_LIST,
+        BUILTIN_EXPERIMENTAL_LIBFUNCS_LIST,
+    },
+    contract_class::ContractClass as SierraContractClass,
+};
+use lazy_static::lazy_static;
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashSet},
+    path::Path,
+    sync::Arc,
+};
+
+lazy_static! {
+    static ref AUDITED_LIBFUNCS: Arc<HashSet<String>> =
+        Arc::new(builtin_list(BUILTIN_AUDITED_LIBFUNCS_LIST));
+    static ref TESTNET_LIBFUNCS: Arc<HashSet<String>> =
+        Arc::new(builtin_list(BUILTIN_EXPERIMENTAL_LIBFUNCS_LIST));
+}
+
+fn builtin_list(name: &str) -> HashSet<String> {
+    lookup_allowed_libfuncs_list(ListSelector::ListName(name.to_string()))
+        .expect("builtin libfunc lists are always valid")
+        .allowed_libfuncs
+        .into_iter()
+        .map(|libfunc| libfunc.0.to_string())
+        .collect()
+}
+
+/// The libfuncs which Sierra classes are allowed to use.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LibfuncAllowList {
+    name: Cow<'static, str>,
+    /// `None` allows every libfunc.
+    libfuncs: Option<Arc<HashSet<String>>>,
+}
+
+impl LibfuncAllowList {
+    /// Creates a list allowing only the given libfuncs.
+    pub fn new(
+        name: impl Into<Cow<'static, str>>,
+        libfuncs: impl IntoIterator<Item = String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            libfuncs: Some(Arc::new(libfuncs.into_iter().collect())),
+        }
+    }
+
+    /// The audited libfuncs, accepted by mainnet.
+    pub fn audited() -> Self {
+        Self {
+            name: Cow::Borrowed(BUILTIN_AUDITED_LIBFUNCS_LIST),
+            libfuncs: Some(AUDITED_LIBFUNCS.clone()),
+        }
+    }
+
+    /// The libfuncs accepted by testnet, which include the experimental ones.
+    pub fn testnet() -> Self {
+        Self {
+            name: Cow::Borrowed(BUILTIN_EXPERIMENTAL_LIBFUNCS_LIST),
+            libfuncs: Some(TESTNET_LIBFUNCS.clone()),
+        }
+    }
+
+    /// A list which allows every libfunc.
+    pub const fn allow_all() -> Self {
+        Self {
+            name: Cow::Borrowed("all"),
+            libfuncs: None,
+        }
+    }
+
+    /// Loads a list from a JSON file, in the format used by the Cairo compiler
+    /// (`{ "allowed_libfuncs": [...] }`).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TransactionError> {
+        let path = path.as_ref().display().to_string();
+        let allowed_libfuncs =
+            lookup_allowed_libfuncs_list(ListSelector::ListFile(path.clone()))
+                .map_err(|err| TransactionError::InvalidLibfuncAllowList(err.to_string()))?;
+
+        Ok(Self::new(
+            path,
+            allowed_libfuncs
+                .allowed_libfuncs
+                .into_iter()
+                .map(|libfunc| libfunc.0.to_string()),
+        ))
+    }
+
+    /// Returns the name of the list.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns whether the libfunc is allowed.
+    pub fn is_allowed(&self, libfunc: &str) -> bool {
+        self.libfuncs
+            .as_ref()
+            .map_or(true, |libfuncs| libfuncs.contains(libfunc))
+    }
+
+    /// Checks that every libfunc used by the Sierra class is allowed.
+    pub fn validate(&self, contract_class: &SierraContractClass) -> Result<(), TransactionError> {
+        if self.libfuncs.is_none() {
+            return Ok(());
+        }
+
+        let program = contract_class
+            .extract_sierra_program()
+            .map_err(|err| TransactionError::SierraCompileError(err.to_string()))?;
+        let disallowed_libfuncs = program
+            .libfunc_declarations
+            .iter()
+            .map(|declaration| declaration.long_id.generic_id.0.as_str())
+            .filter(|libfunc| !self.is_allowed(libfunc))
+            .map(ToString::to_string)
+            .collect::<BTreeSet<_>>();
+
+        if disallowed_libfuncs.is_empty() {
+            Ok(())
+        } else {
+            Err(TransactionError::DisallowedLibfuncs(
+                self.name.to_string(),
+                disallowed_libfuncs.into_iter().collect(),
+            ))
+        }
+    }
+}
+
+impl Default for LibfuncAllowList {
+    fn default() -> Self {
+        Self::allow_all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+    use std::{fs::File, io::BufReader};
+
+    fn fibonacci_class() -> SierraContractClass {
+        #[cfg(not(feature = "cairo_1_tests"))]
+        let path = "starknet_programs/cairo2/fibonacci.sierra";
+        #[cfg(feature = "cairo_1_tests")]
+        let path = "starknet_programs/cairo1/fibonacci.sierra";
+
+        serde_json::from_reader(BufReader::new(File::open(path).unwrap())).unwrap()
+    }
+
+    #[test]
+    fn audited_list_accepts_fibonacci() {
+        let contract_class = fibonacci_class();
+
+        assert!(LibfuncAllowList::audited()
+            .validate(&contract_class)
+            .is_ok());
+        assert!(LibfuncAllowList::testnet()
+            .validate(&contract_class)
+            .is_ok());
+        assert!(LibfuncAllowList::allow_all()
+            .validate(&contract_class)
+            .is_ok());
+    }
+
+    #[test]
+    fn rejection_names_disallowed_libfuncs() {
+        let contract_class = fibonacci_class();
+        let allow_list = LibfuncAllowList::new("only_felt252_add", ["felt252_add".to_string()]);
+
+        match allow_list.validate(&contract_class) {
+            Err(TransactionError::DisallowedLibfuncs(name, libfuncs)) => {
+                assert_eq!(name, "only_felt252_add");
+                assert!(!libfuncs.is_empty());
+                assert!(!libfuncs.contains(&"felt252_add".to_string()));
+                assert!(libfuncs
+                    .iter()
+                    .all(|libfunc| !allow_list.is_allowed(libfunc)));
+            }
+            other => panic!("expected a DisallowedLibfuncs error, got {other:?}"),
+        }
+    }
+}
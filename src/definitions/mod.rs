@@ -1,3 +1,5 @@
 pub mod block_context;
 pub mod constants;
+pub mod libfunc_allow_list;
 pub mod transaction_type;
+pub mod versioned_constants;
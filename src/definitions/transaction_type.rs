@@ -1,7 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 /// TransactionType is an enum that represents the type of transaction.
 ///
 /// It is used in the transaction header and in the transaction execution info.
-#[derive(Debug, PartialEq, Copy, Clone, Eq, Hash)]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionType {
     Declare,
     Deploy,
@@ -0,0 +1,400 @@
+//! # Versioned constants
+//!
+//! The execution parameters which change between Starknet versions: the step limits, the fee
+//! weights of the Cairo resources, the gas costs of the syscalls and the resources used by the OS.
+//! Presets for the supported Starknet versions are bundled with the crate, and custom ones may be
+//! loaded from JSON files in the same format.
+//!
+//! The constants of Starknet 0.12.2, which match those in `definitions::constants`, are used by
+//! default. Blocks of other versions should be executed with the constants of their version (see
+//! `VersionedConstants::for_starknet_version`).
+
+use crate::{
+    definitions::constants::INITIAL_GAS_COST, execution::os_usage::OsResources,
+    syscalls::syscall_handler_errors::SyscallHandlerError, transaction::error::TransactionError,
+};
+use getset::{CopyGetters, Getters, MutGetters};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path, sync::Arc};
+
+/// Latest Starknet version with bundled constants.
+pub const LATEST_STARKNET_VERSION: &str = "0.13.2";
+
+/// Starknet version whose constants are used by default.
+pub const DEFAULT_STARKNET_VERSION: &str = "0.12.2";
+
+/// Starknet versions with bundled constants, in ascending order, and their JSON representation.
+const PRESETS: &[(&str, &str)] = &[
+    ("0.12.2", include_str!("versioned_constants/v0_12_2.json")),
+    ("0.13.2", include_str!("versioned_constants/v0_13_2.json")),
+];
+
+lazy_static! {
+    static ref LATEST: Arc<VersionedConstants> = Arc::new(
+        VersionedConstants::for_version(LATEST_STARKNET_VERSION)
+            .expect("bundled versioned constants are always valid")
+    );
+    static ref DEFAULT: Arc<VersionedConstants> = Arc::new(
+        VersionedConstants::for_version(DEFAULT_STARKNET_VERSION)
+            .expect("bundled versioned constants are always valid")
+    );
+}
+
+/// Execution parameters of a Starknet version.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, CopyGetters, Getters, MutGetters)]
+pub struct VersionedConstants {
+    /// Maximum number of steps allowed when executing transactions.
+    #[getset(get_copy = "pub", get_mut = "pub")]
+    pub(crate) invoke_tx_max_n_steps: u64,
+    /// Maximum number of steps allowed when validating transactions.
+    #[getset(get_copy = "pub", get_mut = "pub")]
+    pub(crate) validate_max_n_steps: u64,
+    /// Weights used when calculating transaction fees.
+    #[getset(get = "pub", get_mut = "pub")]
+    pub(crate) cairo_resource_fee_weights: HashMap<String, f64>,
+    /// Gas charged by the OS on every syscall, which isn't charged by the syscall handlers.
+    #[getset(get_copy = "pub", get_mut = "pub")]
+    pub(crate) syscall_base_gas_cost: u128,
+    /// Gas charged for each round of the `keccak` syscall.
+    #[getset(get_copy = "pub", get_mut = "pub")]
+    pub(crate) keccak_round_cost_gas_cost: u128,
+    /// Gas costs of the syscalls and of the entry points, by name.
+    #[getset(get = "pub", get_mut = "pub")]
+    pub(crate) syscall_gas_costs: HashMap<String, u128>,
+    /// Cairo resources used by the OS.
+    #[getset(get = "pub", get_mut = "pub")]
+    pub(crate) os_resources: OsResources,
+    /// Limits on the events emitted by a transaction. Unlimited for versions without them.
+    #[serde(default)]
+    #[getset(get_copy = "pub", get_mut = "pub")]
+    pub(crate) event_limits: EventLimits,
+}
+
+/// Limits on the events emitted by a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventLimits {
+    /// Maximum number of keys of an event.
+    pub max_keys_length: usize,
+    /// Maximum length of the data of an event.
+    pub max_data_length: usize,
+    /// Maximum number of events emitted by a transaction.
+    pub max_n_emitted_events: u64,
+}
+
+impl EventLimits {
+    /// Limits which allow every event.
+    pub const UNLIMITED: Self = Self {
+        max_keys_length: usize::MAX,
+        max_data_length: usize::MAX,
+        max_n_emitted_events: u64::MAX,
+    };
+
+    /// Checks an event with `n_keys` keys and `data_length` data, emitted by a transaction which
+    /// already emitted `n_emitted_events` events.
+    pub(crate) fn check(
+        &self,
+        n_keys: usize,
+        data_length: usize,
+        n_emitted_events: u64,
+    ) -> Result<(), SyscallHandlerError> {
+        if n_emitted_events >= self.max_n_emitted_events {
+            return Err(SyscallHandlerError::MaxEmittedEventsExceeded(
+                self.max_n_emitted_events,
+            ));
+        }
+        if n_keys > self.max_keys_length {
+            return Err(SyscallHandlerError::MaxEventKeysExceeded(
+                n_keys,
+                self.max_keys_length,
+            ));
+        }
+        if data_length > self.max_data_length {
+            return Err(SyscallHandlerError::MaxEventDataExceeded(
+                data_length,
+                self.max_data_length,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for EventLimits {
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
+
+impl VersionedConstants {
+    /// Returns the constants of the latest supported Starknet version.
+    pub fn latest() -> Arc<Self> {
+        LATEST.clone()
+    }
+
+    /// Returns the constants of the default Starknet version (see [`DEFAULT_STARKNET_VERSION`]).
+    pub fn default_version() -> Arc<Self> {
+        DEFAULT.clone()
+    }
+
+    /// Returns the bundled constants of the given Starknet version (for example, `"0.12.2"`).
+    pub fn for_version(version: &str) -> Result<Self, TransactionError> {
+        PRESETS
+            .iter()
+            .find(|(preset_version, _)| *preset_version == version)
+            .ok_or_else(|| TransactionError::UnknownStarknetVersion(version.to_string()))
+            .and_then(|(_, json)| Self::from_json(json))
+    }
+
+    /// Returns the bundled constants in effect at the given Starknet version, such as the
+    /// `starknet_version` of a block: those of the latest bundled version which isn't newer than
+    /// it, or those of the oldest bundled version for older ones.
+    pub fn for_starknet_version(version: &str) -> Result<Self, TransactionError> {
+        let parse = |version: &str| {
+            version
+                .split('.')
+                .map(str::parse::<u64>)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| TransactionError::UnknownStarknetVersion(version.to_string()))
+        };
+        let version = parse(version)?;
+
+        let mut json = PRESETS[0].1;
+        for (preset_version, preset_json) in PRESETS {
+            if parse(preset_version)? <= version {
+                json = preset_json;
+            }
+        }
+        Self::from_json(json)
+    }
+
+    /// Returns the Starknet versions with bundled constants.
+    pub fn bundled_versions() -> impl Iterator<Item = &'static str> {
+        PRESETS.iter().map(|(version, _)| *version)
+    }
+
+    /// Parses the constants from their JSON representation.
+    pub fn from_json(json: &str) -> Result<Self, TransactionError> {
+        serde_json::from_str(json)
+            .map_err(|err| TransactionError::InvalidVersionedConstants(err.to_string()))
+    }
+
+    /// Loads the constants from a JSON file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, TransactionError> {
+        let file = File::open(path)
+            .map_err(|err| TransactionError::InvalidVersionedConstants(err.to_string()))?;
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|err| TransactionError::InvalidVersionedConstants(err.to_string()))
+    }
+
+    /// Returns the gas cost of the syscall or entry point, including the syscall base cost.
+    pub fn syscall_gas_cost(&self, name: &str) -> Option<u128> {
+        self.syscall_gas_costs.get(name).copied()
+    }
+
+    /// Returns the maximum gas available to an entry point called by a transaction.
+    pub fn initial_gas(&self) -> u128 {
+        self.syscall_gas_cost("initial").unwrap_or(INITIAL_GAS_COST)
+    }
+
+    /// Returns the gas the syscall handlers charge for the syscall, which excludes the base cost
+    /// already charged by the OS.
+    pub(crate) fn required_syscall_gas(&self, syscall_name: &str) -> Option<u128> {
+        self.syscall_gas_cost(syscall_name)
+            .map(|gas_cost| gas_cost.saturating_sub(self.syscall_base_gas_cost))
+    }
+}
+
+impl Default for VersionedConstants {
+    fn default() -> Self {
+        Self::default_version().as_ref().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definitions::{
+        constants::{
+            DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS, DEFAULT_INVOKE_TX_MAX_N_STEPS,
+            DEFAULT_VALIDATE_MAX_N_STEPS,
+        },
+        transaction_type::TransactionType,
+    };
+    use coverage_helper::test;
+
+    #[test]
+    fn bundled_presets_are_valid() {
+        for version in VersionedConstants::bundled_versions() {
+            assert!(VersionedConstants::for_version(version).is_ok());
+        }
+        assert!(matches!(
+            VersionedConstants::for_version("0.1.0"),
+            Err(TransactionError::UnknownStarknetVersion(_))
+        ));
+    }
+
+    #[test]
+    fn latest_version_is_bundled() {
+        assert!(VersionedConstants::bundled_versions().any(|v| v == LATEST_STARKNET_VERSION));
+        assert_eq!(
+            VersionedConstants::for_version(LATEST_STARKNET_VERSION).unwrap(),
+            *VersionedConstants::latest()
+        );
+    }
+
+    #[test]
+    fn default_preset_matches_defaults() {
+        let constants = VersionedConstants::default_version();
+
+        assert_eq!(
+            constants.invoke_tx_max_n_steps(),
+            DEFAULT_INVOKE_TX_MAX_N_STEPS
+        );
+        assert_eq!(
+            constants.validate_max_n_steps(),
+            DEFAULT_VALIDATE_MAX_N_STEPS
+        );
+        assert_eq!(
+            constants.cairo_resource_fee_weights(),
+            &*DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS
+        );
+        assert_eq!(constants.syscall_gas_cost("storage_read"), Some(15_000));
+        assert_eq!(constants.required_syscall_gas("storage_read"), Some(5_000));
+        assert_eq!(
+            constants
+                .os_resources()
+                .estimated_tx_steps(TransactionType::InvokeFunction),
+            3363
+        );
+        assert!(constants
+            .os_resources()
+            .syscall_resources("keccak")
+            .is_some());
+        assert_eq!(*constants, VersionedConstants::default());
+    }
+
+    #[test]
+    fn latest_preset_matches_starknet_0_13_2() {
+        let constants = VersionedConstants::latest();
+
+        assert_eq!(constants.invoke_tx_max_n_steps(), 10_000_000);
+        assert_eq!(constants.validate_max_n_steps(), 1_000_000);
+        assert_eq!(constants.cairo_resource_fee_weights()["n_steps"], 0.0025);
+        assert_eq!(
+            constants.cairo_resource_fee_weights()["pedersen_builtin"],
+            0.08
+        );
+        assert_eq!(constants.syscall_gas_cost("keccak"), Some(10_000));
+        assert_eq!(constants.syscall_gas_cost("secp256r1_add"), Some(62_890));
+        assert_eq!(
+            constants
+                .os_resources()
+                .estimated_tx_steps(TransactionType::InvokeFunction),
+            3763
+        );
+        assert_eq!(
+            constants
+                .os_resources()
+                .syscall_resources("call_contract")
+                .map(|resources| resources.n_steps),
+            Some(827)
+        );
+    }
+
+    #[test]
+    fn constants_for_starknet_version() {
+        let for_starknet_version =
+            |version| VersionedConstants::for_starknet_version(version).unwrap();
+        let v0_12_2 = VersionedConstants::for_version("0.12.2").unwrap();
+        let v0_13_2 = VersionedConstants::for_version("0.13.2").unwrap();
+
+        assert_eq!(for_starknet_version("0.11.0"), v0_12_2);
+        assert_eq!(for_starknet_version("0.12.2"), v0_12_2);
+        assert_eq!(for_starknet_version("0.13.1.1"), v0_12_2);
+        assert_eq!(for_starknet_version("0.13.2"), v0_13_2);
+        assert_eq!(for_starknet_version("0.13.2.1"), v0_13_2);
+        assert!(matches!(
+            VersionedConstants::for_starknet_version("latest"),
+            Err(TransactionError::UnknownStarknetVersion(_))
+        ));
+    }
+
+    #[test]
+    fn sha256_is_only_available_from_0_13_2() {
+        let v0_12_2 = VersionedConstants::for_version("0.12.2").unwrap();
+        assert_eq!(v0_12_2.syscall_gas_cost("sha256_process_block"), None);
+        assert!(v0_12_2
+            .os_resources()
+            .syscall_resources("sha256_process_block")
+            .is_none());
+
+        let v0_13_2 = VersionedConstants::for_version("0.13.2").unwrap();
+        assert_eq!(
+            v0_13_2.syscall_gas_cost("sha256_process_block"),
+            Some(862_060)
+        );
+        assert!(v0_13_2
+            .os_resources()
+            .syscall_resources("sha256_process_block")
+            .is_some());
+    }
+
+    #[test]
+    fn event_limits_are_only_enforced_from_0_13_2() {
+        assert_eq!(
+            VersionedConstants::for_version("0.12.2")
+                .unwrap()
+                .event_limits(),
+            EventLimits::UNLIMITED
+        );
+        assert_eq!(
+            VersionedConstants::for_version("0.13.2")
+                .unwrap()
+                .event_limits(),
+            EventLimits {
+                max_keys_length: 50,
+                max_data_length: 300,
+                max_n_emitted_events: 1000,
+            }
+        );
+    }
+
+    #[test]
+    fn event_limits() {
+        let limits = EventLimits {
+            max_keys_length: 2,
+            max_data_length: 3,
+            max_n_emitted_events: 4,
+        };
+
+        assert!(limits.check(2, 3, 3).is_ok());
+        assert!(matches!(
+            limits.check(3, 0, 0),
+            Err(SyscallHandlerError::MaxEventKeysExceeded(3, 2))
+        ));
+        assert!(matches!(
+            limits.check(0, 4, 0),
+            Err(SyscallHandlerError::MaxEventDataExceeded(4, 3))
+        ));
+        assert!(matches!(
+            limits.check(0, 0, 4),
+            Err(SyscallHandlerError::MaxEmittedEventsExceeded(4))
+        ));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let constants = VersionedConstants::latest();
+        let json = serde_json::to_string(constants.as_ref()).unwrap();
+
+        assert_eq!(
+            &VersionedConstants::from_json(&json).unwrap(),
+            constants.as_ref()
+        );
+        assert!(matches!(
+            VersionedConstants::from_json("{}"),
+            Err(TransactionError::InvalidVersionedConstants(_))
+        ));
+    }
+}
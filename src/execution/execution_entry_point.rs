@@ -1,5 +1,6 @@
 use super::{
-    CallInfo, CallResult, CallType, OrderedEvent, OrderedL2ToL1Message, TransactionExecutionContext,
+    prepared_program::PreparedProgram, CallInfo, CallResult, CallType, OrderedEvent,
+    OrderedL2ToL1Message, TransactionExecutionContext,
 };
 #[cfg(feature = "cairo-native")]
 use crate::state::StateDiff;
@@ -21,7 +22,7 @@ use crate::{
         business_logic_syscall_handler::BusinessLogicSyscallHandler,
         deprecated_business_logic_syscall_handler::DeprecatedBLSyscallHandler,
         deprecated_syscall_handler::DeprecatedSyscallHintProcessor,
-        syscall_handler::SyscallHintProcessor,
+        syscall_handler::SyscallHintProcessor, syscall_trace::SyscallInvocation,
     },
     transaction::error::TransactionError,
     utils::{
@@ -36,10 +37,7 @@ use cairo_lang_starknet::contract_class::ContractEntryPoints;
 use cairo_native::cache::ProgramCache;
 use cairo_vm::{
     felt::Felt252,
-    types::{
-        program::Program,
-        relocatable::{MaybeRelocatable, Relocatable},
-    },
+    types::relocatable::{MaybeRelocatable, Relocatable},
     vm::{
         errors::runner_errors::RunnerError,
         runners::cairo_runner::{CairoArg, CairoRunner, ExecutionResources, RunResources},
@@ -50,8 +48,10 @@ use std::sync::Arc;
 
 #[cfg(feature = "cairo-native")]
 use {
-    crate::syscalls::native_syscall_handler::NativeSyscallHandler,
-    cairo_native::metadata::syscall_handler::SyscallHandlerMeta, core::cell::RefCell, std::rc::Rc,
+    crate::syscalls::native_syscall_handler::{NativeSyscallHandler, TracingNativeSyscallHandler},
+    cairo_native::metadata::syscall_handler::SyscallHandlerMeta,
+    core::cell::RefCell,
+    std::rc::Rc,
 };
 
 #[derive(Debug, Default)]
@@ -104,6 +104,7 @@ impl ExecutionEntryPoint {
     /// Executes the selected entry point with the given calldata in the specified contract.
     /// The information collected from this run (number of steps required, modifications to the
     /// contract storage, etc.) is saved on the resources manager.
+    /// The Cairo VM stops once it runs `max_steps` steps.
     /// Returns a CallInfo object that represents the execution.
     pub fn execute<T, C>(
         &self,
@@ -135,6 +136,7 @@ impl ExecutionEntryPoint {
                     tx_execution_context,
                     contract_class,
                     class_hash,
+                    max_steps,
                 )?;
                 Ok(ExecutionResult {
                     call_info: Some(call_info),
@@ -151,6 +153,7 @@ impl ExecutionEntryPoint {
                     contract_class,
                     class_hash,
                     support_reverted,
+                    max_steps,
                 ) {
                     Ok(call_info) => Ok(ExecutionResult {
                         call_info: Some(call_info),
@@ -300,6 +303,7 @@ impl ExecutionEntryPoint {
         l2_to_l1_messages: Vec<OrderedL2ToL1Message>,
         internal_calls: Vec<CallInfo>,
         retdata: Vec<Felt252>,
+        syscall_trace: Option<Vec<SyscallInvocation>>,
     ) -> Result<CallInfo, TransactionError> {
         let execution_resources = &resources_manager.cairo_usage - &previous_cairo_usage;
 
@@ -321,6 +325,7 @@ impl ExecutionEntryPoint {
             internal_calls,
             failure_flag: false,
             gas_consumed: 0,
+            syscall_trace,
         })
     }
 
@@ -333,6 +338,7 @@ impl ExecutionEntryPoint {
         l2_to_l1_messages: Vec<OrderedL2ToL1Message>,
         internal_calls: Vec<CallInfo>,
         call_result: CallResult,
+        syscall_trace: Option<Vec<SyscallInvocation>>,
     ) -> Result<CallInfo, TransactionError> {
         let execution_resources = &resources_manager.cairo_usage - &previous_cairo_usage;
 
@@ -358,9 +364,32 @@ impl ExecutionEntryPoint {
             internal_calls,
             failure_flag: !call_result.is_success,
             gas_consumed: call_result.gas_consumed,
+            syscall_trace,
         })
     }
 
+    /// Returns the prepared program of a contract class, preparing it with `prepare` (and storing
+    /// it into the contract class cache) if needed.
+    ///
+    /// The prepared program is kept under the same hash as the class in the contract class cache
+    /// (see `CachedState::contract_class_cache_key`).
+    fn get_prepared_program<S: StateReader, C: ContractClassCache>(
+        state: &CachedState<S, C>,
+        class_hash: ClassHash,
+        prepare: impl FnOnce() -> Result<PreparedProgram, TransactionError>,
+    ) -> Result<Arc<PreparedProgram>, TransactionError> {
+        let contract_class_cache = state.contract_class_cache();
+        let cache_key = state.contract_class_cache_key(class_hash);
+        if let Some(prepared_program) = contract_class_cache.get_prepared_program(cache_key) {
+            return Ok(prepared_program);
+        }
+
+        let prepared_program = Arc::new(prepare()?);
+        contract_class_cache.set_prepared_program(cache_key, prepared_program.clone());
+
+        Ok(prepared_program)
+    }
+
     /// Returns the hash of the executed contract class.
     fn get_class_hash<S: State>(&self, state: &mut S) -> Result<ClassHash, TransactionError> {
         if let Some(class_hash) = self.class_hash {
@@ -391,6 +420,7 @@ impl ExecutionEntryPoint {
         tx_execution_context: &mut TransactionExecutionContext,
         contract_class: Arc<ContractClass>,
         class_hash: ClassHash,
+        max_steps: u64,
     ) -> Result<CallInfo, TransactionError> {
         let previous_cairo_usage = resources_manager.cairo_usage.clone();
         // fetch selected entry point
@@ -398,7 +428,15 @@ impl ExecutionEntryPoint {
 
         // create starknet runner
         let mut vm = VirtualMachine::new(false);
-        let mut cairo_runner = CairoRunner::new(&contract_class.program, "starknet", false)?;
+        // get the (cached) program and compiled hints of the contract class. The hints are compiled
+        // by the first run, so the program is only cached once it has run.
+        let contract_class_cache = state.contract_class_cache().clone();
+        let cache_key = state.contract_class_cache_key(class_hash);
+        let cached_program = contract_class_cache.get_prepared_program(cache_key);
+        let prepared_program = cached_program
+            .clone()
+            .unwrap_or_else(|| Arc::new(PreparedProgram::from_deprecated(&contract_class)));
+        let mut cairo_runner = CairoRunner::new(prepared_program.program(), "starknet", false)?;
         cairo_runner.initialize_function_runner(&mut vm)?;
 
         validate_contract_deployed(state, &self.contract_address)?;
@@ -426,8 +464,11 @@ impl ExecutionEntryPoint {
             block_context.clone(),
             initial_syscall_ptr,
         );
-        let hint_processor =
-            DeprecatedSyscallHintProcessor::new(syscall_handler, RunResources::default());
+        let hint_processor = DeprecatedSyscallHintProcessor::new(
+            syscall_handler,
+            RunResources::new(max_steps as usize),
+        )
+        .with_compiled_hints(prepared_program.deprecated_hints().clone());
         let mut runner = StarknetRunner::new(cairo_runner, vm, hint_processor);
 
         // Positional arguments are passed to *args in the 'run_from_entrypoint' function.
@@ -449,6 +490,17 @@ impl ExecutionEntryPoint {
         runner.run_from_entrypoint(entry_point.offset(), &entry_point_args, None)?;
         runner.validate_and_process_os_context_for_version0_class(os_context)?;
 
+        if cached_program.is_none() {
+            let compiled_hints = runner.hint_processor.take_new_compiled_hints();
+            contract_class_cache.set_prepared_program(
+                cache_key,
+                Arc::new(
+                    PreparedProgram::from_deprecated(&contract_class)
+                        .with_deprecated_hints(compiled_hints),
+                ),
+            );
+        }
+
         // When execution starts the stack holds entry_points_args + [ret_fp, ret_pc].
         let args_ptr = (runner
             .cairo_runner
@@ -485,6 +537,11 @@ impl ExecutionEntryPoint {
             runner.hint_processor.syscall_handler.l2_to_l1_messages,
             runner.hint_processor.syscall_handler.internal_calls,
             retdata,
+            runner
+                .hint_processor
+                .syscall_handler
+                .syscall_tracer
+                .into_trace(),
         )
     }
 
@@ -497,6 +554,7 @@ impl ExecutionEntryPoint {
         contract_class: Arc<CasmContractClass>,
         class_hash: ClassHash,
         support_reverted: bool,
+        max_steps: u64,
     ) -> Result<CallInfo, TransactionError> {
         let previous_cairo_usage = resources_manager.cairo_usage.clone();
 
@@ -505,15 +563,19 @@ impl ExecutionEntryPoint {
 
         // create starknet runner
         let mut vm = VirtualMachine::new(false);
-        // get a program from the casm contract class
-        let program: Program = contract_class.as_ref().clone().try_into()?;
+        // get the (cached) program, hints and builtins from the casm contract class
+        let prepared_program = Self::get_prepared_program(state, class_hash, || {
+            PreparedProgram::from_casm(&contract_class)
+        })?;
+        let program = prepared_program.program();
         // create and initialize a cairo runner for running cairo 1 programs.
-        let mut cairo_runner = CairoRunner::new(&program, "starknet", false)?;
+        let mut cairo_runner = CairoRunner::new(program, "starknet", false)?;
 
-        cairo_runner.initialize_function_runner_cairo_1(
-            &mut vm,
-            &parse_builtin_names(&entry_point.builtins)?,
-        )?;
+        let builtins = match prepared_program.builtins(entry_point.offset) {
+            Some(builtins) => builtins.to_vec(),
+            None => parse_builtin_names(&entry_point.builtins)?,
+        };
+        cairo_runner.initialize_function_runner_cairo_1(&mut vm, &builtins)?;
         validate_contract_deployed(state, &self.contract_address)?;
         // prepare OS context
         let os_context = StarknetRunner::<SyscallHintProcessor<S, C>>::prepare_os_context_cairo1(
@@ -542,8 +604,8 @@ impl ExecutionEntryPoint {
         // create and attach a syscall hint processor to the starknet runner.
         let hint_processor = SyscallHintProcessor::new(
             syscall_handler,
-            &contract_class.hints,
-            RunResources::default(),
+            prepared_program.hints().clone(),
+            RunResources::new(max_steps as usize),
         );
         let mut runner = StarknetRunner::new(cairo_runner, vm, hint_processor);
 
@@ -636,6 +698,11 @@ impl ExecutionEntryPoint {
             runner.hint_processor.syscall_handler.l2_to_l1_messages,
             runner.hint_processor.syscall_handler.internal_calls,
             call_result,
+            runner
+                .hint_processor
+                .syscall_handler
+                .syscall_tracer
+                .into_trace(),
         )
     }
 
@@ -667,9 +734,7 @@ impl ExecutionEntryPoint {
     ) -> Result<CallInfo, TransactionError> {
         use cairo_native::values::JITValue;
 
-        use crate::{
-            syscalls::business_logic_syscall_handler::SYSCALL_BASE, utils::NATIVE_CONTEXT,
-        };
+        use crate::utils::NATIVE_CONTEXT;
 
         // Ensure we're using the global context, if initialized.
         if let Some(native_context) = NATIVE_CONTEXT.get() {
@@ -721,16 +786,19 @@ impl ExecutionEntryPoint {
             block_context: block_context.clone(),
             program_cache: program_cache.clone(),
             resources_manager: Default::default(),
+            unrecoverable_error: None,
         };
 
         native_executor
             .borrow_mut()
             .get_module_mut()
             .remove_metadata::<SyscallHandlerMeta>();
+        let mut tracing_syscall_handler =
+            TracingNativeSyscallHandler::new(&mut syscall_handler, block_context.trace_syscalls());
         native_executor
             .borrow_mut()
             .get_module_mut()
-            .insert_metadata(SyscallHandlerMeta::new(&mut syscall_handler));
+            .insert_metadata(SyscallHandlerMeta::new(&mut tracing_syscall_handler));
 
         let entry_point_fn = &sierra_program
             .funcs
@@ -751,6 +819,10 @@ impl ExecutionEntryPoint {
             .borrow()
             .execute_contract(entry_point_id, &calldata, self.initial_gas)
             .map_err(|e| TransactionError::CustomError(format!("cairo-native error: {:?}", e)))?;
+        let syscall_trace = tracing_syscall_handler.into_trace();
+        if let Some(err) = syscall_handler.unrecoverable_error.take() {
+            return Err(err);
+        }
 
         Ok(CallInfo {
             caller_address: self.caller_address.clone(),
@@ -771,8 +843,9 @@ impl ExecutionEntryPoint {
             internal_calls: syscall_handler.internal_calls,
             gas_consumed: self
                 .initial_gas
-                .saturating_sub(SYSCALL_BASE)
+                .saturating_sub(block_context.versioned_constants().syscall_base_gas_cost())
                 .saturating_sub(value.remaining_gas),
+            syscall_trace,
         })
     }
 }
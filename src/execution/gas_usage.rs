@@ -17,12 +17,38 @@ use crate::state::state_api::StateChangesCount;
 ///
 /// # Returns:
 ///
-/// The estimation of L1 gas usage as a `usize` value.
+/// The estimation of L1 gas usage as a `usize` value, with the state diff posted as calldata.
 pub fn calculate_tx_gas_usage(
     l2_to_l1_messages: Vec<L2toL1MessageInfo>,
     state_changes: &StateChangesCount,
     l1_handler_payload_size: Option<usize>,
 ) -> usize {
+    calculate_tx_gas_vector(
+        l2_to_l1_messages,
+        state_changes,
+        l1_handler_payload_size,
+        false,
+    )
+    .l1_gas
+}
+
+/// L1 gas and L1 data (blob) gas used by a transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GasVector {
+    pub l1_gas: usize,
+    pub l1_data_gas: usize,
+}
+
+/// Estimates the L1 gas and the L1 data gas used by Starknet's update state and the verifier.
+///
+/// When `use_kzg_da` is set, the state diff is posted as blobs and charged as data gas. Otherwise,
+/// it's posted as calldata and charged as L1 gas, as in [`calculate_tx_gas_usage`].
+pub fn calculate_tx_gas_vector(
+    l2_to_l1_messages: Vec<L2toL1MessageInfo>,
+    state_changes: &StateChangesCount,
+    l1_handler_payload_size: Option<usize>,
+    use_kzg_da: bool,
+) -> GasVector {
     let residual_message_segment_length =
         get_message_segment_lenght(&l2_to_l1_messages, l1_handler_payload_size);
 
@@ -42,10 +68,21 @@ pub fn calculate_tx_gas_usage(
         &l2_to_l1_messages,
     );
 
-    let sharp_gas_usage = (residual_message_segment_length * SHARP_GAS_PER_MEMORY_WORD)
-        + (residual_onchain_data_segment_length * SHARP_GAS_PER_MEMORY_WORD);
+    let sharp_gas_usage = residual_message_segment_length * SHARP_GAS_PER_MEMORY_WORD;
 
-    starknet_gas_usage + sharp_gas_usage
+    if use_kzg_da {
+        GasVector {
+            l1_gas: starknet_gas_usage + sharp_gas_usage,
+            l1_data_gas: residual_onchain_data_segment_length * DATA_GAS_PER_FIELD_ELEMENT,
+        }
+    } else {
+        GasVector {
+            l1_gas: starknet_gas_usage
+                + sharp_gas_usage
+                + residual_onchain_data_segment_length * SHARP_GAS_PER_MEMORY_WORD,
+            l1_data_gas: 0,
+        }
+    }
 }
 
 // ~~~~~~~~~~~~~~~~
@@ -268,4 +305,31 @@ mod test {
             76439
         )
     }
+
+    #[test]
+    fn transaction_gas_vector_with_blobs() {
+        let ord_ev = OrderedL2ToL1Message::new(1, Address(1235.into()), vec![4.into()]);
+        let message = L2toL1MessageInfo::new(ord_ev, Address(1234.into()));
+        let state_changes = StateChangesCount {
+            n_storage_updates: 2,
+            n_class_hash_updates: 1,
+            n_compiled_class_hash_updates: 0,
+            n_modified_contracts: 2,
+        };
+        let onchain_data_segment_length = get_onchain_data_segment_length(&state_changes);
+
+        let calldata_gas =
+            calculate_tx_gas_vector(vec![message.clone()], &state_changes, None, false);
+        let blob_gas = calculate_tx_gas_vector(vec![message], &state_changes, None, true);
+
+        assert_eq!(calldata_gas.l1_data_gas, 0);
+        assert_eq!(
+            blob_gas,
+            GasVector {
+                l1_gas: calldata_gas.l1_gas
+                    - onchain_data_segment_length * SHARP_GAS_PER_MEMORY_WORD,
+                l1_data_gas: onchain_data_segment_length * DATA_GAS_PER_FIELD_ELEMENT,
+            }
+        );
+    }
 }
@@ -1,12 +1,13 @@
 pub mod execution_entry_point;
 pub mod gas_usage;
 pub mod os_usage;
+pub mod prepared_program;
 use crate::services::api::contract_classes::deprecated_contract_class::EntryPointType;
 use crate::utils::parse_felt_array;
 use crate::{
     definitions::{constants::CONSTRUCTOR_ENTRY_POINT_SELECTOR, transaction_type::TransactionType},
     state::state_cache::StorageEntry,
-    syscalls::syscall_handler_errors::SyscallHandlerError,
+    syscalls::{syscall_handler_errors::SyscallHandlerError, syscall_trace::SyscallInvocation},
     transaction::error::TransactionError,
     utils::{get_big_int, get_integer, get_relocatable, Address, ClassHash},
 };
@@ -16,7 +17,7 @@ use cairo_vm::{
     vm::{runners::cairo_runner::ExecutionResources, vm_core::VirtualMachine},
 };
 use getset::Getters;
-use num_traits::{ToPrimitive, Zero};
+use num_traits::{One, ToPrimitive, Zero};
 use serde::{Deserialize, Deserializer};
 use std::collections::{HashMap, HashSet};
 
@@ -49,6 +50,8 @@ pub struct CallInfo {
     pub internal_calls: Vec<CallInfo>,
     pub gas_consumed: u128,
     pub failure_flag: bool,
+    /// Syscalls made by the call, in order, if syscall tracing is enabled.
+    pub syscall_trace: Option<Vec<SyscallInvocation>>,
 }
 
 impl CallInfo {
@@ -83,6 +86,7 @@ impl CallInfo {
             internal_calls: Vec::new(),
             gas_consumed: 0,
             failure_flag: false,
+            syscall_trace: None,
         }
     }
 
@@ -244,6 +248,7 @@ impl Default for CallInfo {
             events: Vec::new(),
             gas_consumed: 0,
             failure_flag: false,
+            syscall_trace: None,
         }
     }
 }
@@ -368,6 +373,9 @@ pub struct TransactionExecutionContext {
     pub(crate) nonce: Felt252,
     pub(crate) n_sent_messages: usize,
     pub(crate) _n_steps: u64,
+    /// Fields of the transaction only exposed to contracts reading the extended tx info.
+    #[get = "pub"]
+    pub(crate) extended_tx_info: ExtendedTxInfo,
     // pub(crate) use_cairo_native: bool,
 }
 
@@ -397,9 +405,16 @@ impl TransactionExecutionContext {
             version,
             n_sent_messages: 0,
             _n_steps: n_steps,
+            extended_tx_info: ExtendedTxInfo::default(),
         }
     }
 
+    /// Sets the fields of the transaction only exposed to contracts reading the extended tx info.
+    pub fn with_extended_tx_info(mut self, extended_tx_info: ExtendedTxInfo) -> Self {
+        self.extended_tx_info = extended_tx_info;
+        self
+    }
+
     pub fn create_for_testing(
         account_contract_address: Address,
         _max_fee: u128,
@@ -417,10 +432,65 @@ impl TransactionExecutionContext {
             nonce,
             n_sent_messages: 0,
             _n_steps: n_steps,
+            extended_tx_info: ExtendedTxInfo::default(),
         }
     }
 }
 
+/// A resource whose usage is bounded by a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Resource {
+    L1Gas,
+    L2Gas,
+}
+
+impl Resource {
+    /// Returns the short string identifying the resource in the tx info.
+    pub fn to_felt(self) -> Felt252 {
+        match self {
+            Resource::L1Gas => Felt252::from_bytes_be(b"L1_GAS"),
+            Resource::L2Gas => Felt252::from_bytes_be(b"L2_GAS"),
+        }
+    }
+}
+
+/// Maximum amount and price per unit of a resource a transaction may consume.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResourceBounds {
+    pub resource: Resource,
+    pub max_amount: u64,
+    pub max_price_per_unit: u128,
+}
+
+/// Where the data of a transaction's nonce or fee is published.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DataAvailabilityMode {
+    #[default]
+    L1,
+    L2,
+}
+
+impl From<DataAvailabilityMode> for Felt252 {
+    fn from(mode: DataAvailabilityMode) -> Self {
+        match mode {
+            DataAvailabilityMode::L1 => Felt252::zero(),
+            DataAvailabilityMode::L2 => Felt252::one(),
+        }
+    }
+}
+
+/// Fields of the tx info added by the extended layout, which are empty for transactions that
+/// don't set them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExtendedTxInfo {
+    pub resource_bounds: Vec<ResourceBounds>,
+    pub tip: u128,
+    pub paymaster_data: Vec<Felt252>,
+    pub nonce_data_availability_mode: DataAvailabilityMode,
+    pub fee_data_availability_mode: DataAvailabilityMode,
+    pub account_deployment_data: Vec<Felt252>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct TxInfoStruct {
     pub(crate) version: Felt252,
@@ -2,261 +2,114 @@ use std::collections::HashMap;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 
-use crate::{definitions::transaction_type::TransactionType, transaction::error::TransactionError};
+use serde::{Deserialize, Serialize};
 
-pub(crate) const ESTIMATED_INVOKE_FUNCTION_STEPS: usize = 3363;
-pub(crate) const ESTIMATED_DECLARE_STEPS: usize = 2703;
-pub(crate) const ESTIMATED_DEPLOY_STEPS: usize = 0;
-pub(crate) const ESTIMATED_DEPLOY_ACCOUNT_STEPS: usize = 3612;
-pub(crate) const ESTIMATED_L1_HANDLER_STEPS: usize = 1068;
+use crate::{
+    definitions::{transaction_type::TransactionType, versioned_constants::VersionedConstants},
+    transaction::error::TransactionError,
+};
 
-#[derive(Debug, Clone)]
+/// Cairo resources used by the OS to run each syscall and the code wrapping each transaction type,
+/// which are added to the resources used by the transaction's own execution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "OsResourcesRepr", into = "OsResourcesRepr")]
 pub struct OsResources {
     execute_syscalls: HashMap<String, ExecutionResources>,
     execute_txs_inner: HashMap<TransactionType, ExecutionResources>,
 }
 
+impl OsResources {
+    /// Returns the resources used by the OS to run the syscall.
+    pub fn syscall_resources(&self, syscall_name: &str) -> Option<&ExecutionResources> {
+        self.execute_syscalls.get(syscall_name)
+    }
+
+    /// Returns the resources used by the OS to run a transaction of the given type.
+    pub fn tx_resources(&self, tx_type: TransactionType) -> Option<&ExecutionResources> {
+        self.execute_txs_inner.get(&tx_type)
+    }
+
+    /// Returns the number of steps the OS is estimated to run for a transaction of the given
+    /// type, used to calculate its minimal fee.
+    pub(crate) fn estimated_tx_steps(&self, tx_type: TransactionType) -> usize {
+        self.tx_resources(tx_type)
+            .map(|resources| resources.n_steps)
+            .unwrap_or_default()
+    }
+}
+
 impl Default for OsResources {
+    /// Returns the resources of the default Starknet version.
     fn default() -> Self {
-        let execute_txs_inner: HashMap<TransactionType, ExecutionResources> = HashMap::from([
-            (
-                TransactionType::InvokeFunction,
-                ExecutionResources {
-                    n_steps: ESTIMATED_INVOKE_FUNCTION_STEPS,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([
-                        ("pedersen_builtin".to_string(), 16),
-                        ("range_check_builtin".to_string(), 80),
-                    ]),
-                },
-            ),
-            (
-                TransactionType::Declare,
-                ExecutionResources {
-                    n_steps: ESTIMATED_DECLARE_STEPS,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([
-                        ("pedersen_builtin".to_string(), 15),
-                        ("range_check_builtin".to_string(), 63),
-                    ]),
-                },
-            ),
-            (
-                TransactionType::Deploy,
-                ExecutionResources {
-                    n_steps: ESTIMATED_DEPLOY_STEPS,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                TransactionType::DeployAccount,
-                ExecutionResources {
-                    n_steps: ESTIMATED_DEPLOY_ACCOUNT_STEPS,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([
-                        ("pedersen_builtin".to_string(), 23),
-                        ("range_check_builtin".to_string(), 83),
-                    ]),
-                },
-            ),
-            (
-                TransactionType::L1Handler,
-                ExecutionResources {
-                    n_steps: ESTIMATED_L1_HANDLER_STEPS,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([
-                        ("pedersen_builtin".to_string(), 11),
-                        ("range_check_builtin".to_string(), 17),
-                    ]),
-                },
-            ),
-        ]);
+        VersionedConstants::default_version().os_resources().clone()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct OsResourcesRepr {
+    execute_syscalls: HashMap<String, ExecutionResourcesRepr>,
+    execute_txs_inner: HashMap<TransactionType, ExecutionResourcesRepr>,
+}
 
-        let execute_syscalls = HashMap::from([
-            (
-                "call_contract".to_string(),
-                ExecutionResources {
-                    n_steps: 690,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        19,
-                    )]),
-                },
-            ),
-            (
-                "delegate_call".to_string(),
-                ExecutionResources {
-                    n_steps: 712,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        19,
-                    )]),
-                },
-            ),
-            (
-                "delegate_l1_handler".to_string(),
-                ExecutionResources {
-                    n_steps: 691,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        15,
-                    )]),
-                },
-            ),
-            (
-                "deploy".to_string(),
-                ExecutionResources {
-                    n_steps: 936,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([
-                        ("range_check_builtin".to_string(), 18),
-                        ("pedersen_builtin".to_string(), 7),
-                    ]),
-                },
-            ),
-            (
-                "library_call".to_string(),
-                ExecutionResources {
-                    n_steps: 679,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        19,
-                    )]),
-                },
-            ),
-            (
-                "emit_event".to_string(),
-                ExecutionResources {
-                    n_steps: 19,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_block_hash".to_string(),
-                ExecutionResources {
-                    n_steps: 44,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_block_number".to_string(),
-                ExecutionResources {
-                    n_steps: 40,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_block_timestamp".to_string(),
-                ExecutionResources {
-                    n_steps: 38,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_caller_address".to_string(),
-                ExecutionResources {
-                    n_steps: 32,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_contract_address".to_string(),
-                ExecutionResources {
-                    n_steps: 36,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_execution_info".to_string(),
-                ExecutionResources {
-                    n_steps: 29,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_sequencer_address".to_string(),
-                ExecutionResources {
-                    n_steps: 34,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_tx_info".to_string(),
-                ExecutionResources {
-                    n_steps: 29,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_tx_signature".to_string(),
-                ExecutionResources {
-                    n_steps: 44,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "library_call_l1_handler".to_string(),
-                ExecutionResources {
-                    n_steps: 658,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        15,
-                    )]),
-                },
-            ),
-            (
-                "replace_class".to_string(),
-                ExecutionResources {
-                    n_steps: 73,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "send_message_to_l1".to_string(),
-                ExecutionResources {
-                    n_steps: 84,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "storage_read".to_string(),
-                ExecutionResources {
-                    n_steps: 44,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "storage_write".to_string(),
-                ExecutionResources {
-                    n_steps: 46,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-        ]);
+#[derive(Clone, Serialize, Deserialize)]
+struct ExecutionResourcesRepr {
+    n_steps: usize,
+    #[serde(default)]
+    n_memory_holes: usize,
+    #[serde(default)]
+    builtin_instance_counter: HashMap<String, usize>,
+}
 
-        OsResources {
-            execute_syscalls,
-            execute_txs_inner,
+impl From<OsResourcesRepr> for OsResources {
+    fn from(repr: OsResourcesRepr) -> Self {
+        Self {
+            execute_syscalls: repr
+                .execute_syscalls
+                .into_iter()
+                .map(|(syscall, resources)| (syscall, resources.into()))
+                .collect(),
+            execute_txs_inner: repr
+                .execute_txs_inner
+                .into_iter()
+                .map(|(tx_type, resources)| (tx_type, resources.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<OsResources> for OsResourcesRepr {
+    fn from(os_resources: OsResources) -> Self {
+        Self {
+            execute_syscalls: os_resources
+                .execute_syscalls
+                .into_iter()
+                .map(|(syscall, resources)| (syscall, resources.into()))
+                .collect(),
+            execute_txs_inner: os_resources
+                .execute_txs_inner
+                .into_iter()
+                .map(|(tx_type, resources)| (tx_type, resources.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<ExecutionResourcesRepr> for ExecutionResources {
+    fn from(repr: ExecutionResourcesRepr) -> Self {
+        Self {
+            n_steps: repr.n_steps,
+            n_memory_holes: repr.n_memory_holes,
+            builtin_instance_counter: repr.builtin_instance_counter,
+        }
+    }
+}
+
+impl From<ExecutionResources> for ExecutionResourcesRepr {
+    fn from(resources: ExecutionResources) -> Self {
+        Self {
+            n_steps: resources.n_steps,
+            n_memory_holes: resources.n_memory_holes,
+            builtin_instance_counter: resources.builtin_instance_counter,
         }
     }
 }
@@ -264,9 +117,8 @@ impl Default for OsResources {
 pub fn get_additional_os_resources(
     syscall_counter: HashMap<String, u64>,
     tx_type: &TransactionType,
+    os_resources: &OsResources,
 ) -> Result<ExecutionResources, TransactionError> {
-    let os_resources = OsResources::default();
-
     let mut additional_os_resources = ExecutionResources::default();
 
     for (syscall, count) in syscall_counter {
@@ -295,7 +147,8 @@ fn get_additional_os_resources_test() {
 
     let tx_type = TransactionType::InvokeFunction;
 
-    let additional_os_resources = get_additional_os_resources(syscall_counter, &tx_type).unwrap();
+    let additional_os_resources =
+        get_additional_os_resources(syscall_counter, &tx_type, &OsResources::default()).unwrap();
     let expected_additional_os_resources = ExecutionResources {
         n_steps: 3589,
         n_memory_holes: 0,
@@ -0,0 +1,265 @@
+//! # Prepared programs
+//!
+//! Running a Cairo 1 (CASM) contract class in the Cairo VM requires converting it into a VM
+//! `Program`, compiling its hints (indexing them by pc) and resolving the builtins of every entry
+//! point. None of that depends on the call being executed, so it is done once per class and kept
+//! alongside the class in the `ContractClassCache` (see `ContractClassCache::get_prepared_program`).
+//!
+//! Deprecated (Cairo 0) classes already hold a parsed `Program`, which their prepared program
+//! shares. Their hints are compiled (resolving the variables they access from the program's
+//! references) the first time the class is run, and the compiled hints are cached along with the
+//! program so later runs don't compile them again.
+
+use crate::{
+    services::api::contract_classes::deprecated_contract_class::ContractClass,
+    transaction::error::TransactionError, utils::parse_builtin_names,
+};
+use cairo_lang_casm::hints::Hint;
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+use cairo_vm::{
+    hint_processor::{
+        builtin_hint_processor::builtin_hint_processor_definition::HintProcessorData,
+        hint_processor_definition::HintReference,
+    },
+    serde::deserialize_program::{ApTracking, BuiltinName},
+    types::program::Program,
+    types::relocatable::MaybeRelocatable,
+};
+use std::{
+    collections::HashMap,
+    mem::{size_of, size_of_val},
+    sync::Arc,
+};
+
+/// The compiled hints of a CASM program: the hints at every pc, as handed to the VM when it runs
+/// the program. Sharing them makes compiling the hints of a prepared program a lookup.
+pub type CompiledHints = Arc<HashMap<usize, Arc<Vec<Hint>>>>;
+
+/// The compiled hints of a deprecated (Cairo 0) program, in the order in which the VM compiles
+/// them.
+pub type DeprecatedCompiledHints = Arc<Vec<DeprecatedCompiledHint>>;
+
+/// A compiled deprecated (Cairo 0) hint: its code, along with the variables it accesses.
+#[derive(Debug)]
+pub struct DeprecatedCompiledHint {
+    code: String,
+    ap_tracking: ApTracking,
+    reference_ids: HashMap<String, usize>,
+    ids_data: HashMap<String, HintReference>,
+}
+
+impl DeprecatedCompiledHint {
+    /// Keeps the data of a hint compiled from the given reference ids.
+    pub(crate) fn new(
+        hint_data: &HintProcessorData,
+        reference_ids: &HashMap<String, usize>,
+    ) -> Self {
+        Self {
+            code: hint_data.code.clone(),
+            ap_tracking: hint_data.ap_tracking.clone(),
+            reference_ids: reference_ids.clone(),
+            ids_data: hint_data.ids_data.clone(),
+        }
+    }
+
+    /// Returns whether this is the compiled form of the given hint.
+    pub(crate) fn matches(
+        &self,
+        code: &str,
+        ap_tracking: &ApTracking,
+        reference_ids: &HashMap<String, usize>,
+    ) -> bool {
+        self.code == code
+            && &self.ap_tracking == ap_tracking
+            && &self.reference_ids == reference_ids
+    }
+
+    /// Returns the hint data handed to the VM when it runs the hint.
+    pub(crate) fn to_hint_processor_data(&self) -> HintProcessorData {
+        HintProcessorData {
+            code: self.code.clone(),
+            ap_tracking: self.ap_tracking.clone(),
+            ids_data: self.ids_data.clone(),
+        }
+    }
+}
+
+/// The call-independent data required to run a contract class in the Cairo VM.
+#[derive(Debug)]
+pub struct PreparedProgram {
+    program: Program,
+    /// Whether the program data is shared with the contract class, as for deprecated classes.
+    shares_program_data: bool,
+    hints: CompiledHints,
+    deprecated_hints: DeprecatedCompiledHints,
+    builtins: HashMap<usize, Vec<BuiltinName>>,
+}
+
+impl PreparedProgram {
+    /// Prepares a CASM contract class to be run in the Cairo VM.
+    pub fn from_casm(contract_class: &CasmContractClass) -> Result<Self, TransactionError> {
+        let builtins = contract_class
+            .entry_points_by_type
+            .external
+            .iter()
+            .chain(contract_class.entry_points_by_type.l1_handler.iter())
+            .chain(contract_class.entry_points_by_type.constructor.iter())
+            .map(|entry_point| {
+                Ok((
+                    entry_point.offset,
+                    parse_builtin_names(&entry_point.builtins)?,
+                ))
+            })
+            .collect::<Result<_, TransactionError>>()?;
+
+        Ok(Self {
+            program: contract_class.clone().try_into()?,
+            shares_program_data: false,
+            hints: Arc::new(
+                contract_class
+                    .hints
+                    .iter()
+                    .map(|(pc, hints)| (*pc, Arc::new(hints.clone())))
+                    .collect(),
+            ),
+            deprecated_hints: Default::default(),
+            builtins,
+        })
+    }
+
+    /// Prepares a deprecated (Cairo 0) contract class to be run in the Cairo VM. Its hints are
+    /// compiled by the first run of the class (see `with_deprecated_hints`).
+    pub fn from_deprecated(contract_class: &ContractClass) -> Self {
+        Self {
+            program: contract_class.program.clone(),
+            shares_program_data: true,
+            hints: Default::default(),
+            deprecated_hints: Default::default(),
+            builtins: HashMap::new(),
+        }
+    }
+
+    /// Sets the compiled hints of a deprecated (Cairo 0) program.
+    pub fn with_deprecated_hints(self, deprecated_hints: Vec<DeprecatedCompiledHint>) -> Self {
+        Self {
+            deprecated_hints: Arc::new(deprecated_hints),
+            ..self
+        }
+    }
+
+    /// Returns the program to be loaded into the VM.
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    /// Returns the compiled hints of a CASM program, indexed by pc.
+    pub fn hints(&self) -> &CompiledHints {
+        &self.hints
+    }
+
+    /// Returns the compiled hints of a deprecated (Cairo 0) program, in compilation order.
+    pub fn deprecated_hints(&self) -> &DeprecatedCompiledHints {
+        &self.deprecated_hints
+    }
+
+    /// Returns the builtins used by the entry point at the given offset.
+    pub fn builtins(&self, entry_point_offset: usize) -> Option<&[BuiltinName]> {
+        self.builtins.get(&entry_point_offset).map(Vec::as_slice)
+    }
+
+    /// Estimates the memory usage of the prepared program, in bytes.
+    pub fn estimated_size(&self) -> usize {
+        // Felts are stored on the heap, using (at most) 32 bytes.
+        const FELT_SIZE: usize = 32;
+
+        let program_data_size = if self.shares_program_data {
+            0
+        } else {
+            self.program.data_len() * (size_of::<MaybeRelocatable>() + FELT_SIZE)
+        };
+
+        size_of::<Self>()
+            + program_data_size
+            + self
+                .hints
+                .values()
+                .map(|hints| {
+                    size_of::<(usize, Arc<Vec<Hint>>)>()
+                        + size_of::<Vec<Hint>>()
+                        + size_of_val(hints.as_slice())
+                })
+                .sum::<usize>()
+            + self
+                .deprecated_hints
+                .iter()
+                .map(|hint| {
+                    size_of::<DeprecatedCompiledHint>()
+                        + hint.code.len()
+                        + hint
+                            .reference_ids
+                            .keys()
+                            .map(|name| size_of::<(String, usize)>() + name.len())
+                            .sum::<usize>()
+                        + hint
+                            .ids_data
+                            .keys()
+                            .map(|name| size_of::<(String, HintReference)>() + name.len())
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+            + self
+                .builtins
+                .values()
+                .map(|builtins| {
+                    size_of::<(usize, Vec<BuiltinName>)>() + size_of_val(builtins.as_slice())
+                })
+                .sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prepare_casm_class() {
+        let contract_class: CasmContractClass = serde_json::from_slice(include_bytes!(
+            "../../starknet_programs/cairo2/fibonacci.casm"
+        ))
+        .unwrap();
+        let prepared_program = PreparedProgram::from_casm(&contract_class).unwrap();
+
+        assert_eq!(
+            prepared_program.program().data_len(),
+            contract_class.bytecode.len()
+        );
+        assert_eq!(prepared_program.hints().len(), contract_class.hints.len());
+
+        let entry_point = &contract_class.entry_points_by_type.external[0];
+        assert_eq!(
+            prepared_program.builtins(entry_point.offset).unwrap(),
+            parse_builtin_names(&entry_point.builtins)
+                .unwrap()
+                .as_slice()
+        );
+        assert_eq!(prepared_program.builtins(usize::MAX), None);
+    }
+
+    #[test]
+    fn prepare_deprecated_class() {
+        let contract_class = ContractClass::from_path("starknet_programs/fibonacci.json").unwrap();
+        let prepared_program = PreparedProgram::from_deprecated(&contract_class);
+
+        assert_eq!(
+            prepared_program.program().data_len(),
+            contract_class.program.data_len()
+        );
+        assert!(prepared_program.hints().is_empty());
+        assert!(prepared_program.deprecated_hints().is_empty());
+        // The program data is shared with the class, so it isn't accounted for twice.
+        assert_eq!(
+            prepared_program.estimated_size(),
+            size_of::<PreparedProgram>()
+        );
+    }
+}
@@ -3,6 +3,7 @@
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
 use crate::{
+    core::errors::validation_errors::ValidationError,
     definitions::block_context::BlockContext,
     execution::{
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
@@ -14,7 +15,11 @@ use crate::{
         state_api::{State, StateReader},
         ExecutionResourcesManager,
     },
-    transaction::{error::TransactionError, fee::calculate_tx_fee, L1Handler, Transaction},
+    transaction::{
+        error::TransactionError,
+        fee::{calculate_tx_fee, calculate_tx_fee_breakdown, FeeBreakdown},
+        L1Handler, Transaction, ValidationInfo,
+    },
     utils::Address,
 };
 use cairo_vm::felt::Felt252;
@@ -41,26 +46,32 @@ use {
     std::{cell::RefCell, rc::Rc},
 };
 
+pub mod bouncer;
 pub mod core;
 pub mod definitions;
 pub mod execution;
 pub mod hash_utils;
+pub mod mempool;
+pub mod metrics;
 pub mod parser_errors;
 pub mod runner;
 pub mod serde_structs;
 pub mod services;
+pub mod signer;
 pub mod state;
 pub mod syscalls;
 pub mod transaction;
 pub mod utils;
 
+/// Simulates the execution of the transactions. If `remaining_gas` is `None`, the gas budget of
+/// each transaction is derived from its max fee, see [`Transaction::execute_with_gas_budget`].
 #[allow(clippy::too_many_arguments)]
 pub fn simulate_transaction<S: StateReader, C: ContractClassCache>(
     transactions: &[&Transaction],
     state: S,
     contract_class_cache: Arc<C>,
     block_context: &BlockContext,
-    remaining_gas: u128,
+    remaining_gas: Option<u128>,
     skip_validate: bool,
     skip_execute: bool,
     skip_fee_transfer: bool,
@@ -80,7 +91,7 @@ pub fn simulate_transaction<S: StateReader, C: ContractClassCache>(
             ignore_max_fee,
             skip_nonce_check,
         );
-        let tx_result = tx_for_simulation.execute(
+        let tx_result = tx_for_simulation.execute_with_gas_budget(
             &mut cache_state,
             block_context,
             remaining_gas,
@@ -96,12 +107,74 @@ pub fn simulate_transaction<S: StateReader, C: ContractClassCache>(
 /// Estimate the fee associated with transaction
 pub fn estimate_fee<T, C>(
     transactions: &[Transaction],
-    mut cached_state: CachedState<T, C>,
+    cached_state: CachedState<T, C>,
     block_context: &BlockContext,
     #[cfg(feature = "cairo-native")] program_cache: Option<
         Rc<RefCell<ProgramCache<'_, ClassHash>>>,
     >,
 ) -> Result<Vec<(u128, usize)>, TransactionError>
+where
+    T: StateReader,
+    C: ContractClassCache,
+{
+    simulate_for_fee_estimation(
+        transactions,
+        cached_state,
+        block_context,
+        #[cfg(feature = "cairo-native")]
+        program_cache,
+    )?
+    .into_iter()
+    .map(
+        |transaction_result| match transaction_result.actual_resources.get("l1_gas_usage") {
+            Some(gas_usage) => Ok((transaction_result.actual_fee, *gas_usage)),
+            None => Err(TransactionError::ResourcesError),
+        },
+    )
+    .collect()
+}
+
+/// Estimate the fee associated with transaction, split by the L1 gas and the L1 data gas it pays
+/// for
+pub fn estimate_fee_breakdown<T, C>(
+    transactions: &[Transaction],
+    cached_state: CachedState<T, C>,
+    block_context: &BlockContext,
+    #[cfg(feature = "cairo-native")] program_cache: Option<
+        Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+    >,
+) -> Result<Vec<FeeBreakdown>, TransactionError>
+where
+    T: StateReader,
+    C: ContractClassCache,
+{
+    simulate_for_fee_estimation(
+        transactions,
+        cached_state,
+        block_context,
+        #[cfg(feature = "cairo-native")]
+        program_cache,
+    )?
+    .iter()
+    .map(|transaction_result| {
+        calculate_tx_fee_breakdown(
+            &transaction_result.actual_resources,
+            block_context.starknet_os_config.gas_price,
+            block_context,
+        )
+    })
+    .collect()
+}
+
+/// Executes the transactions one after the other on a fake state, ignoring the fee checks.
+fn simulate_for_fee_estimation<T, C>(
+    transactions: &[Transaction],
+    mut cached_state: CachedState<T, C>,
+    block_context: &BlockContext,
+    #[cfg(feature = "cairo-native")] program_cache: Option<
+        Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+    >,
+) -> Result<Vec<TransactionExecutionInfo>, TransactionError>
 where
     T: StateReader,
     C: ContractClassCache,
@@ -122,11 +195,7 @@ where
             #[cfg(feature = "cairo-native")]
             program_cache.clone(),
         )?;
-        if let Some(gas_usage) = transaction_result.actual_resources.get("l1_gas_usage") {
-            result.push((transaction_result.actual_fee, *gas_usage));
-        } else {
-            return Err(TransactionError::ResourcesError);
-        };
+        result.push(transaction_result);
 
         cached_state.cache.update_initial_values();
     }
@@ -183,7 +252,7 @@ pub fn call_contract<T: StateReader, C: ContractClassCache>(
         &mut ExecutionResourcesManager::default(),
         &mut tx_execution_context,
         false,
-        block_context.invoke_tx_max_n_steps,
+        block_context.invoke_tx_max_n_steps(),
         #[cfg(feature = "cairo-native")]
         program_cache,
     )?;
@@ -228,16 +297,18 @@ where
     }
 }
 
+/// Executes the transaction. If `remaining_gas` is `None`, the gas budget is derived from its max
+/// fee, see [`Transaction::execute_with_gas_budget`].
 pub fn execute_transaction<S: StateReader, C: ContractClassCache>(
     tx: Transaction,
     state: &mut CachedState<S, C>,
     block_context: BlockContext,
-    remaining_gas: u128,
+    remaining_gas: Option<u128>,
     #[cfg(feature = "cairo-native")] program_cache: Option<
         Rc<RefCell<ProgramCache<'_, ClassHash>>>,
     >,
 ) -> Result<TransactionExecutionInfo, TransactionError> {
-    tx.execute(
+    tx.execute_with_gas_budget(
         state,
         &block_context,
         remaining_gas,
@@ -246,6 +317,24 @@ pub fn execute_transaction<S: StateReader, C: ContractClassCache>(
     )
 }
 
+/// Validates the transaction without executing it, see [`Transaction::validate`]. The state isn't
+/// modified.
+pub fn validate_transaction<S: StateReader, C: ContractClassCache>(
+    tx: &Transaction,
+    state: &CachedState<S, C>,
+    block_context: &BlockContext,
+    #[cfg(feature = "cairo-native")] program_cache: Option<
+        Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+    >,
+) -> Result<ValidationInfo, ValidationError> {
+    tx.validate(
+        state,
+        block_context,
+        #[cfg(feature = "cairo-native")]
+        program_cache,
+    )
+}
+
 #[cfg(test)]
 mod test {
     use crate::{
@@ -258,7 +347,7 @@ mod test {
                 VALIDATE_DECLARE_ENTRY_POINT_SELECTOR, VALIDATE_ENTRY_POINT_SELECTOR,
             },
         },
-        estimate_fee, estimate_message_fee,
+        estimate_fee, estimate_message_fee, execute_transaction,
         hash_utils::calculate_contract_address,
         services::api::contract_classes::{
             compiled_class::CompiledClass,
@@ -273,7 +362,8 @@ mod test {
             ExecutionResourcesManager,
         },
         transaction::{
-            Declare, DeclareV2, Deploy, DeployAccount, InvokeFunction, L1Handler, Transaction,
+            fee::calculate_initial_gas, Declare, DeclareV2, Deploy, DeployAccount, InvokeFunction,
+            L1Handler, Transaction,
         },
         utils::{
             felt_to_hash,
@@ -627,7 +717,7 @@ mod test {
             state_reader,
             Arc::new(PermanentContractClassCache::default()),
             &block_context,
-            1000,
+            Some(1000),
             false,
             true,
             true,
@@ -732,7 +822,7 @@ mod test {
             state_reader,
             Arc::new(PermanentContractClassCache::default()),
             &block_context,
-            1000,
+            Some(1000),
             true,
             true,
             true,
@@ -784,7 +874,7 @@ mod test {
             state.clone_for_testing(),
             state.contract_class_cache().clone(),
             block_context,
-            100_000_000,
+            Some(100_000_000),
             false,
             false,
             false,
@@ -827,7 +917,7 @@ mod test {
             state.clone_for_testing(),
             state.contract_class_cache().clone(),
             block_context,
-            100_000_000,
+            Some(100_000_000),
             false,
             false,
             false,
@@ -904,7 +994,7 @@ mod test {
             state.clone_for_testing(),
             state.contract_class_cache().clone(),
             &block_context,
-            100_000_000,
+            Some(100_000_000),
             false,
             false,
             false,
@@ -916,6 +1006,91 @@ mod test {
         .unwrap();
     }
 
+    #[test]
+    fn test_simulate_invoke_derives_the_gas_budget_from_the_max_fee() {
+        #[cfg(not(feature = "cairo_1_tests"))]
+        let program_data = include_bytes!("../starknet_programs/cairo2/fibonacci.casm");
+        #[cfg(feature = "cairo_1_tests")]
+        let program_data = include_bytes!("../starknet_programs/cairo1/fibonacci.casm");
+        let contract_class: CasmContractClass = serde_json::from_slice(program_data).unwrap();
+        let entrypoint_selector =
+            Felt252::from(&contract_class.entry_points_by_type.external[0].selector);
+
+        let address = Address(1111.into());
+        let class_hash: ClassHash = ClassHash([1; 32]);
+        let mut state_reader = InMemoryStateReader::default();
+        state_reader
+            .address_to_class_hash_mut()
+            .insert(address.clone(), class_hash);
+        state_reader
+            .class_hash_to_compiled_class_mut()
+            .insert(class_hash, CompiledClass::Casm(Arc::new(contract_class)));
+
+        let invoke = Transaction::InvokeFunction(
+            InvokeFunction::new(
+                address,
+                entrypoint_selector,
+                1,
+                Felt252::zero(),
+                vec![1.into(), 1.into(), 10.into()],
+                vec![],
+                StarknetChainId::TestNet.to_felt(),
+                None,
+            )
+            .unwrap(),
+        );
+
+        // A max fee of 1 can't pay for a single unit of L1 gas, so there's no gas left to execute.
+        let mut block_context = BlockContext::default();
+        block_context.starknet_os_config.gas_price = 2;
+        assert_eq!(calculate_initial_gas(1, 0, &block_context), 0);
+
+        let simulate = |remaining_gas| {
+            simulate_transaction(
+                &[&invoke],
+                state_reader.clone(),
+                Arc::new(PermanentContractClassCache::default()),
+                &block_context,
+                remaining_gas,
+                true,
+                false,
+                true,
+                false,
+                true,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap()
+            .remove(0)
+            .call_info
+            .unwrap()
+        };
+
+        let call_info = simulate(None);
+        assert!(call_info.failure_flag);
+        assert_eq!(
+            call_info.retdata,
+            vec![Felt252::from_bytes_be(b"Out of gas")]
+        );
+        assert_eq!(call_info, simulate(Some(0)));
+        assert!(!simulate(Some(INITIAL_GAS_COST)).failure_flag);
+
+        let mut state = CachedState::new(
+            Arc::new(state_reader),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let execution_info = execute_transaction(
+            invoke.create_for_simulation(true, false, true, false, true),
+            &mut state,
+            block_context,
+            None,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+        assert_eq!(execution_info.call_info, Some(call_info));
+    }
+
     #[test]
     fn test_simulate_deploy_account() {
         let state_reader = Arc::new(InMemoryStateReader::default());
@@ -953,7 +1128,7 @@ mod test {
             state.clone_for_testing(),
             state.contract_class_cache().clone(),
             block_context,
-            100_000_000,
+            Some(100_000_000),
             false,
             false,
             false,
@@ -984,6 +1159,7 @@ mod test {
             sierra_contract_class: Some(sierra_contract_class),
             sierra_class_hash,
             casm_class: Default::default(),
+            extended_tx_info: Default::default(),
             skip_execute: false,
             skip_fee_transfer: false,
             skip_validate: false,
@@ -1001,7 +1177,7 @@ mod test {
             state.clone_for_testing(),
             state.contract_class_cache().clone(),
             &block_context,
-            100_000_000,
+            Some(100_000_000),
             false,
             false,
             true,
@@ -1072,7 +1248,7 @@ mod test {
             state.clone_for_testing(),
             state.contract_class_cache().clone(),
             &block_context,
-            100_000_000,
+            Some(100_000_000),
             false,
             false,
             false,
@@ -1135,7 +1311,7 @@ mod test {
             state.clone_for_testing(),
             state.contract_class_cache().clone(),
             block_context,
-            100_000_000,
+            Some(100_000_000),
             false,
             false,
             false,
@@ -1274,7 +1450,7 @@ mod test {
             state.clone_for_testing(),
             state.clone_for_testing().contract_class_cache().clone(),
             &block_context,
-            100_000_000,
+            Some(100_000_000),
             true,
             false,
             true,
@@ -1291,7 +1467,7 @@ mod test {
             state.clone_for_testing(),
             state.contract_class_cache().clone(),
             &block_context,
-            100_000_000,
+            Some(100_000_000),
             false,
             false,
             true,
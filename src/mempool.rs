@@ -0,0 +1,739 @@
+//! # Mempool
+//!
+//! A pool of transactions waiting to be included in a block. Transactions are admitted by running
+//! only their validation (see [`Transaction::validate`]) on a snapshot of the state, so the pool
+//! can be filled without executing anything.
+//!
+//! Transactions are queued per account and ordered by nonce. Those whose nonce is ahead of the
+//! account's next nonce wait in the queue until the gap is filled. The ready transactions are
+//! handed out in batches for block execution, by highest max fee across accounts and in nonce
+//! order within each account. Transactions which were handed out but not executed can be put back
+//! with [`Mempool::requeue`].
+//!
+//! ```ignore
+//! let mut mempool = Mempool::new(MempoolConfig::default());
+//! mempool.add_transaction(tx, &state, &block_context)?;
+//! let mut batch = mempool.pop_batch(100).into_iter();
+//! while let Some(tx) = batch.next() {
+//!     if block_is_full {
+//!         mempool.requeue(std::iter::once(tx).chain(batch).collect());
+//!         break;
+//!     }
+//!     tx.execute(&mut state, &block_context, remaining_gas)?;
+//! }
+//! ```
+
+use crate::{
+    core::errors::mempool_errors::MempoolError,
+    definitions::{block_context::BlockContext, transaction_type::TransactionType},
+    state::{
+        cached_state::CachedState, contract_class_cache::ContractClassCache, state_api::StateReader,
+    },
+    transaction::Transaction,
+    utils::Address,
+};
+use cairo_vm::felt::Felt252;
+use num_traits::{One, ToPrimitive};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[cfg(feature = "cairo-native")]
+use {
+    crate::utils::ClassHash,
+    cairo_native::cache::ProgramCache,
+    std::{cell::RefCell, rc::Rc},
+};
+
+/// Admission, replacement and eviction rules of a [`Mempool`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MempoolConfig {
+    /// Maximum number of transactions in the pool.
+    pub max_size: usize,
+    /// Whether a full pool evicts its cheapest transaction to admit one with a higher max fee.
+    pub evict_cheaper_transactions: bool,
+    /// Minimum increase of the max fee, in percent, for a transaction to replace the one in the
+    /// pool with the same sender and nonce. If `None`, transactions can't be replaced.
+    pub replacement_fee_bump_percent: Option<u128>,
+    /// How far ahead of the account's next nonce the nonce of a queued transaction may be.
+    pub max_nonce_gap: u64,
+}
+
+impl Default for MempoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 10_000,
+            evict_cheaper_transactions: true,
+            replacement_fee_bump_percent: Some(10),
+            max_nonce_gap: 64,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PooledTransaction {
+    transaction: Transaction,
+    /// Order in which the transaction was admitted, used to break ties between equal fees.
+    arrival: u64,
+}
+
+/// Transactions of an account, by nonce.
+#[derive(Debug)]
+struct AccountQueue {
+    /// Nonce of the next transaction of the account to be handed out.
+    next_nonce: Felt252,
+    transactions: BTreeMap<Felt252, PooledTransaction>,
+}
+
+impl AccountQueue {
+    fn new(next_nonce: Felt252) -> Self {
+        Self {
+            next_nonce,
+            transactions: BTreeMap::new(),
+        }
+    }
+
+    fn head(&self) -> Option<&PooledTransaction> {
+        self.transactions.get(&self.next_nonce)
+    }
+
+    fn n_ready(&self) -> usize {
+        let mut nonce = self.next_nonce.clone();
+        let mut n_ready = 0;
+        while self.transactions.contains_key(&nonce) {
+            nonce += Felt252::one();
+            n_ready += 1;
+        }
+        n_ready
+    }
+}
+
+/// A pool of validated transactions, see the [module docs](self).
+#[derive(Debug, Default)]
+pub struct Mempool {
+    config: MempoolConfig,
+    accounts: HashMap<Address, AccountQueue>,
+    hashes: HashSet<Felt252>,
+    n_arrivals: u64,
+}
+
+impl Mempool {
+    pub fn new(config: MempoolConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the number of transactions in the pool, both ready and queued.
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns whether the pool has no transactions.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Returns the number of transactions which can be handed out for execution, as there's no
+    /// nonce gap before them.
+    pub fn n_ready(&self) -> usize {
+        self.accounts.values().map(AccountQueue::n_ready).sum()
+    }
+
+    /// Returns whether the transaction with the given hash is in the pool.
+    pub fn contains(&self, hash_value: &Felt252) -> bool {
+        self.hashes.contains(hash_value)
+    }
+
+    /// Validates the transaction on a snapshot of `state` and adds it to the pool.
+    ///
+    /// The transaction replaces the one with the same sender and nonce, if its max fee is high
+    /// enough. If the pool is full, the cheapest transaction may be evicted to make room for it.
+    /// Validation runs against the current state, so it doesn't see the effects of the earlier
+    /// transactions of the account which are still in the pool.
+    pub fn add_transaction<S: StateReader, C: ContractClassCache>(
+        &mut self,
+        transaction: Transaction,
+        state: &CachedState<S, C>,
+        block_context: &BlockContext,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<(), MempoolError> {
+        let hash_value = transaction.hash_value().clone();
+        if self.hashes.contains(&hash_value) {
+            return Err(MempoolError::DuplicateTransaction(hash_value));
+        }
+        let nonce = match (transaction.tx_type(), transaction.nonce()) {
+            (TransactionType::L1Handler, _) | (_, None) => {
+                return Err(MempoolError::UnsupportedTransaction(hash_value))
+            }
+            (_, Some(nonce)) => nonce.clone(),
+        };
+
+        let sender = transaction.contract_address();
+        // The pool is only updated with the account nonce once the transaction is admitted.
+        let account_nonce = StateReader::get_nonce_at(state, &sender)?;
+        let next_nonce = match self.accounts.get(&sender) {
+            Some(queue) if queue.next_nonce > account_nonce => queue.next_nonce.clone(),
+            _ => account_nonce.clone(),
+        };
+        if nonce < next_nonce {
+            return Err(MempoolError::NonceTooOld(sender, next_nonce, nonce));
+        }
+        if (nonce.clone() - next_nonce.clone())
+            .to_u64()
+            .map_or(true, |gap| gap > self.config.max_nonce_gap)
+        {
+            return Err(MempoolError::NonceTooFarAhead(sender, next_nonce, nonce));
+        }
+
+        let max_fee = transaction.max_fee();
+        let replaced = self
+            .accounts
+            .get(&sender)
+            .and_then(|queue| queue.transactions.get(&nonce));
+        let needs_eviction = match replaced {
+            Some(replaced) => {
+                let fee_bump_percent =
+                    self.config.replacement_fee_bump_percent.ok_or_else(|| {
+                        MempoolError::ReplacementDisabled(sender.clone(), nonce.clone())
+                    })?;
+                let replaced_fee = replaced.transaction.max_fee();
+                let required_fee = replaced_fee
+                    .saturating_add(replaced_fee.saturating_mul(fee_bump_percent) / 100)
+                    .max(replaced_fee.saturating_add(1));
+                if max_fee < required_fee {
+                    return Err(MempoolError::ReplacementUnderpriced(required_fee, max_fee));
+                }
+                false
+            }
+            None if self.len() >= self.config.max_size => {
+                let can_evict = self.config.evict_cheaper_transactions
+                    && self
+                        .eviction_candidate(&sender)
+                        .map_or(false, |(_, fee)| fee < max_fee);
+                if !can_evict {
+                    return Err(MempoolError::MempoolFull);
+                }
+                true
+            }
+            None => false,
+        };
+
+        // The nonce was checked above, as it may be ahead of the account's.
+        transaction.run_validation(
+            state,
+            block_context,
+            true,
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )?;
+
+        self.update_account_nonce(&sender, account_nonce);
+        if needs_eviction && self.len() >= self.config.max_size {
+            if let Some((evicted_sender, _)) = self.eviction_candidate(&sender) {
+                self.evict(&evicted_sender);
+            }
+        }
+
+        let arrival = self.n_arrivals;
+        self.n_arrivals += 1;
+        self.hashes.insert(hash_value);
+        let queue = self
+            .accounts
+            .entry(sender)
+            .or_insert_with(|| AccountQueue::new(next_nonce));
+        if let Some(replaced) = queue.transactions.insert(
+            nonce,
+            PooledTransaction {
+                transaction,
+                arrival,
+            },
+        ) {
+            self.hashes.remove(replaced.transaction.hash_value());
+        }
+
+        Ok(())
+    }
+
+    /// Removes up to `max_n` ready transactions from the pool, in the order they should be
+    /// executed: by highest max fee across accounts (earliest admitted first on ties), and in nonce
+    /// order within each account.
+    /// The next nonce of the accounts advances past the transactions handed out. Accounts left
+    /// without transactions are forgotten, so the pool goes back to their nonce in the state.
+    pub fn pop_batch(&mut self, max_n: usize) -> Vec<Transaction> {
+        let mut batch = Vec::new();
+        while batch.len() < max_n {
+            let sender = self
+                .accounts
+                .iter()
+                .filter_map(|(sender, queue)| queue.head().map(|head| (sender, head)))
+                .max_by(|(_, a), (_, b)| {
+                    a.transaction
+                        .max_fee()
+                        .cmp(&b.transaction.max_fee())
+                        .then(b.arrival.cmp(&a.arrival))
+                })
+                .map(|(sender, _)| sender.clone());
+            let Some(sender) = sender else {
+                break;
+            };
+            let Some(queue) = self.accounts.get_mut(&sender) else {
+                break;
+            };
+
+            let nonce = queue.next_nonce.clone();
+            queue.next_nonce += Felt252::one();
+            if let Some(pooled) = queue.transactions.remove(&nonce) {
+                self.hashes.remove(pooled.transaction.hash_value());
+                batch.push(pooled.transaction);
+            }
+            if queue.transactions.is_empty() {
+                self.accounts.remove(&sender);
+            }
+        }
+        batch
+    }
+
+    /// Puts back transactions handed out by [`Mempool::pop_batch`] which weren't executed, e.g.
+    /// because the block was full. The next nonce of their accounts goes back to the lowest nonce
+    /// put back, so they're handed out again. They aren't validated again, and they're put back
+    /// even if the pool is full.
+    pub fn requeue(&mut self, transactions: Vec<Transaction>) {
+        for transaction in transactions {
+            let Some(nonce) = transaction.nonce().cloned() else {
+                continue;
+            };
+            if !self.hashes.insert(transaction.hash_value().clone()) {
+                continue;
+            }
+            let arrival = self.n_arrivals;
+            self.n_arrivals += 1;
+            let queue = self
+                .accounts
+                .entry(transaction.contract_address())
+                .or_insert_with(|| AccountQueue::new(nonce.clone()));
+            if nonce < queue.next_nonce {
+                queue.next_nonce = nonce.clone();
+            }
+            if let Some(replaced) = queue.transactions.insert(
+                nonce,
+                PooledTransaction {
+                    transaction,
+                    arrival,
+                },
+            ) {
+                self.hashes.remove(replaced.transaction.hash_value());
+            }
+        }
+    }
+
+    /// Updates the pool with the nonce of the account in the state, e.g. after executing a block.
+    /// Transactions with lower nonces are dropped. Nonces behind the next nonce known by the pool
+    /// are ignored, as the transactions up to it were already handed out; see
+    /// [`Mempool::reset_account_nonce`] for when they won't be executed.
+    pub fn update_account_nonce(&mut self, sender: &Address, nonce: Felt252) {
+        if self
+            .accounts
+            .get(sender)
+            .map_or(false, |queue| nonce >= queue.next_nonce)
+        {
+            self.reset_account_nonce(sender, nonce);
+        }
+    }
+
+    /// Sets the next nonce of the account to its nonce in the state, even if it's behind the next
+    /// nonce known by the pool, e.g. after the transactions handed out for the account failed to
+    /// execute. Transactions with lower nonces are dropped.
+    pub fn reset_account_nonce(&mut self, sender: &Address, nonce: Felt252) {
+        let Some(queue) = self.accounts.get_mut(sender) else {
+            return;
+        };
+        let pending = queue.transactions.split_off(&nonce);
+        for stale in std::mem::replace(&mut queue.transactions, pending).into_values() {
+            self.hashes.remove(stale.transaction.hash_value());
+        }
+        queue.next_nonce = nonce;
+        // Once the state caught up with the account, there's nothing left to remember about it.
+        if queue.transactions.is_empty() {
+            self.accounts.remove(sender);
+        }
+    }
+
+    /// Returns the account whose last transaction is the cheapest one in the pool, along with its
+    /// max fee. Only the last transactions of the accounts are evicted, so as not to open nonce
+    /// gaps. The transactions of `incoming_sender` aren't evicted to admit another one of theirs,
+    /// as its nonce may depend on them.
+    fn eviction_candidate(&self, incoming_sender: &Address) -> Option<(Address, u128)> {
+        self.accounts
+            .iter()
+            .filter(|(sender, _)| *sender != incoming_sender)
+            .filter_map(|(sender, queue)| {
+                queue
+                    .transactions
+                    .values()
+                    .next_back()
+                    .map(|last| (sender, last))
+            })
+            .min_by(|(_, a), (_, b)| {
+                a.transaction
+                    .max_fee()
+                    .cmp(&b.transaction.max_fee())
+                    .then(b.arrival.cmp(&a.arrival))
+            })
+            .map(|(sender, last)| (sender.clone(), last.transaction.max_fee()))
+    }
+
+    fn evict(&mut self, sender: &Address) {
+        if let Some(queue) = self.accounts.get_mut(sender) {
+            if let Some((_, evicted)) = queue.transactions.pop_last() {
+                self.hashes.remove(evicted.transaction.hash_value());
+            }
+            if queue.transactions.is_empty() {
+                self.accounts.remove(sender);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        definitions::{block_context::StarknetChainId, constants::EXECUTE_ENTRY_POINT_SELECTOR},
+        services::api::contract_classes::compiled_class::CompiledClass,
+        state::{
+            contract_class_cache::PermanentContractClassCache,
+            in_memory_state_reader::InMemoryStateReader,
+        },
+        transaction::InvokeFunction,
+        utils::{get_erc20_balance_var_addresses, ClassHash},
+    };
+    use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+    use coverage_helper::test;
+    use std::sync::Arc;
+
+    fn invoke(sender: u64, nonce: u64, max_fee: u128) -> Transaction {
+        // Only calls to `__execute__` are validated.
+        invoke_with_selector(sender, nonce, max_fee, 1.into())
+    }
+
+    fn invoke_with_selector(
+        sender: u64,
+        nonce: u64,
+        max_fee: u128,
+        entry_point_selector: Felt252,
+    ) -> Transaction {
+        Transaction::InvokeFunction(
+            InvokeFunction::new(
+                Address(sender.into()),
+                entry_point_selector,
+                max_fee,
+                1.into(),
+                vec![],
+                vec![],
+                StarknetChainId::TestNet.to_felt(),
+                Some(nonce.into()),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn state() -> CachedState<InMemoryStateReader, PermanentContractClassCache> {
+        state_with_nonce(5)
+    }
+
+    fn state_with_nonce(
+        nonce: u64,
+    ) -> CachedState<InMemoryStateReader, PermanentContractClassCache> {
+        CachedState::new(
+            Arc::new(state_reader_with_nonce(nonce)),
+            Arc::new(PermanentContractClassCache::default()),
+        )
+    }
+
+    fn state_reader_with_nonce(nonce: u64) -> InMemoryStateReader {
+        let mut state_reader = InMemoryStateReader::default();
+        state_reader
+            .address_to_nonce
+            .insert(Address(1.into()), nonce.into());
+        // The senders can pay for the max fees used in the tests.
+        let block_context = BlockContext::default();
+        let fee_token_address = block_context.starknet_os_config().fee_token_address();
+        for sender in 1..=3u64 {
+            let (balance_key, _) =
+                get_erc20_balance_var_addresses(&Address(sender.into())).unwrap();
+            state_reader
+                .address_to_storage
+                .insert((fee_token_address.clone(), balance_key), 1000.into());
+        }
+        state_reader
+    }
+
+    fn add(
+        mempool: &mut Mempool,
+        state: &CachedState<InMemoryStateReader, PermanentContractClassCache>,
+        transaction: Transaction,
+    ) -> Result<(), MempoolError> {
+        mempool.add_transaction(
+            transaction,
+            state,
+            &BlockContext::default(),
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+    }
+
+    fn nonces(batch: &[Transaction]) -> Vec<(Address, Felt252)> {
+        batch
+            .iter()
+            .map(|tx| (tx.contract_address(), tx.nonce().unwrap().clone()))
+            .collect()
+    }
+
+    #[test]
+    fn future_nonces_are_queued_until_the_gap_is_filled() {
+        let state = state();
+        let mut mempool = Mempool::default();
+
+        add(&mut mempool, &state, invoke(1, 7, 10)).unwrap();
+        add(&mut mempool, &state, invoke(1, 6, 10)).unwrap();
+        assert_eq!(mempool.n_ready(), 0);
+        assert!(mempool.pop_batch(10).is_empty());
+
+        add(&mut mempool, &state, invoke(1, 5, 10)).unwrap();
+        add(&mut mempool, &state, invoke(2, 0, 20)).unwrap();
+        assert_eq!(mempool.len(), 4);
+        assert_eq!(mempool.n_ready(), 4);
+
+        // The best paying account goes first, and the nonce order is kept within each account.
+        assert_eq!(
+            nonces(&mempool.pop_batch(3)),
+            vec![
+                (Address(2.into()), 0.into()),
+                (Address(1.into()), 5.into()),
+                (Address(1.into()), 6.into()),
+            ]
+        );
+        assert!(matches!(
+            add(&mut mempool, &state, invoke(1, 6, 10)),
+            Err(MempoolError::NonceTooOld(..))
+        ));
+        assert_eq!(
+            nonces(&mempool.pop_batch(3)),
+            vec![(Address(1.into()), 7.into())]
+        );
+        assert!(mempool.is_empty());
+        // Accounts without transactions are forgotten.
+        assert!(mempool.accounts.is_empty());
+    }
+
+    #[test]
+    fn admission_rules() {
+        let state = state();
+        let mut mempool = Mempool::new(MempoolConfig {
+            max_size: 2,
+            max_nonce_gap: 2,
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            add(&mut mempool, &state, invoke(1, 4, 10)),
+            Err(MempoolError::NonceTooOld(..))
+        ));
+        assert!(matches!(
+            add(&mut mempool, &state, invoke(1, 8, 10)),
+            Err(MempoolError::NonceTooFarAhead(..))
+        ));
+        // The account has no contract to run `__validate__`.
+        assert!(matches!(
+            add(
+                &mut mempool,
+                &state,
+                invoke_with_selector(1, 5, 10, EXECUTE_ENTRY_POINT_SELECTOR.clone())
+            ),
+            Err(MempoolError::Validation(_))
+        ));
+
+        add(&mut mempool, &state, invoke(1, 5, 100)).unwrap();
+        assert!(matches!(
+            add(&mut mempool, &state, invoke(1, 5, 100)),
+            Err(MempoolError::DuplicateTransaction(_))
+        ));
+        assert!(matches!(
+            add(&mut mempool, &state, invoke(1, 5, 105)),
+            Err(MempoolError::ReplacementUnderpriced(110, 105))
+        ));
+        add(&mut mempool, &state, invoke(1, 5, 110)).unwrap();
+        assert_eq!(mempool.len(), 1);
+
+        // When full, the cheapest transaction is evicted for a better paying one.
+        add(&mut mempool, &state, invoke(2, 0, 20)).unwrap();
+        assert!(matches!(
+            add(&mut mempool, &state, invoke(3, 0, 20)),
+            Err(MempoolError::MempoolFull)
+        ));
+        let better_paying = invoke(3, 0, 30);
+        let hash_value = better_paying.hash_value().clone();
+        add(&mut mempool, &state, better_paying).unwrap();
+        assert!(mempool.contains(&hash_value));
+        assert_eq!(
+            nonces(&mempool.pop_batch(10)),
+            vec![(Address(1.into()), 5.into()), (Address(3.into()), 0.into())]
+        );
+    }
+
+    #[test]
+    fn failed_admission_leaves_the_pool_unchanged() {
+        let mut mempool = Mempool::default();
+        add(&mut mempool, &state(), invoke(1, 5, 10)).unwrap();
+        add(&mut mempool, &state(), invoke(1, 6, 10)).unwrap();
+
+        // The first transaction was executed, but the pool wasn't updated yet.
+        let state = state_with_nonce(6);
+        assert!(matches!(
+            add(
+                &mut mempool,
+                &state,
+                invoke_with_selector(1, 7, 10, EXECUTE_ENTRY_POINT_SELECTOR.clone())
+            ),
+            Err(MempoolError::Validation(_))
+        ));
+        assert_eq!(mempool.len(), 2);
+
+        // An admitted transaction brings the account up to date.
+        add(&mut mempool, &state, invoke(1, 7, 10)).unwrap();
+        assert_eq!(mempool.len(), 2);
+        assert_eq!(
+            nonces(&mempool.pop_batch(10)),
+            vec![(Address(1.into()), 6.into()), (Address(1.into()), 7.into())]
+        );
+    }
+
+    #[test]
+    fn unexecuted_transactions_are_handed_out_again() {
+        let state = state();
+        let mut mempool = Mempool::default();
+        add(&mut mempool, &state, invoke(1, 5, 10)).unwrap();
+        add(&mut mempool, &state, invoke(1, 6, 10)).unwrap();
+        add(&mut mempool, &state, invoke(2, 0, 20)).unwrap();
+
+        // The block only had room for the first transaction.
+        let mut batch = mempool.pop_batch(10);
+        let leftover = batch.split_off(1);
+        assert_eq!(nonces(&batch), vec![(Address(2.into()), 0.into())]);
+        mempool.requeue(leftover);
+        assert_eq!(mempool.len(), 2);
+        assert_eq!(
+            nonces(&mempool.pop_batch(10)),
+            vec![(Address(1.into()), 5.into()), (Address(1.into()), 6.into())]
+        );
+
+        // All the transactions of the account were handed out, so the pool is back at its nonce in
+        // the state and they can be added again if they failed to execute.
+        add(&mut mempool, &state, invoke(1, 5, 10)).unwrap();
+        assert_eq!(mempool.n_ready(), 1);
+    }
+
+    #[test]
+    fn reset_account_nonce_hands_out_the_failed_transactions_again() {
+        let state = state();
+        let mut mempool = Mempool::default();
+        add(&mut mempool, &state, invoke(1, 5, 10)).unwrap();
+        add(&mut mempool, &state, invoke(1, 6, 10)).unwrap();
+        add(&mut mempool, &state, invoke(1, 7, 10)).unwrap();
+        assert_eq!(mempool.pop_batch(2).len(), 2);
+        assert!(matches!(
+            add(&mut mempool, &state, invoke(1, 5, 10)),
+            Err(MempoolError::NonceTooOld(..))
+        ));
+
+        // The transactions handed out failed to execute, so the account is back at its nonce in
+        // the state, and the one left waits for them.
+        mempool.reset_account_nonce(&Address(1.into()), 5.into());
+        assert_eq!(mempool.n_ready(), 0);
+        add(&mut mempool, &state, invoke(1, 5, 10)).unwrap();
+        add(&mut mempool, &state, invoke(1, 6, 10)).unwrap();
+        assert_eq!(mempool.n_ready(), 3);
+    }
+
+    #[test]
+    fn eviction_spares_the_incoming_sender() {
+        let state = state();
+        let mut mempool = Mempool::new(MempoolConfig {
+            max_size: 2,
+            ..Default::default()
+        });
+        add(&mut mempool, &state, invoke(1, 5, 10)).unwrap();
+        add(&mut mempool, &state, invoke(2, 0, 20)).unwrap();
+
+        // Evicting the cheaper transaction of the sender would open a nonce gap before the new one.
+        add(&mut mempool, &state, invoke(1, 6, 30)).unwrap();
+        assert_eq!(
+            nonces(&mempool.pop_batch(10)),
+            vec![(Address(1.into()), 5.into()), (Address(1.into()), 6.into())]
+        );
+
+        let mut mempool = Mempool::new(MempoolConfig {
+            max_size: 1,
+            ..Default::default()
+        });
+        add(&mut mempool, &state, invoke(1, 5, 10)).unwrap();
+        assert!(matches!(
+            add(&mut mempool, &state, invoke(1, 6, 30)),
+            Err(MempoolError::MempoolFull)
+        ));
+    }
+
+    #[test]
+    fn admission_runs_the_account_validation() {
+        #[cfg(not(feature = "cairo_1_tests"))]
+        let program_data = include_bytes!("../starknet_programs/cairo2/hello_world_account.casm");
+        #[cfg(feature = "cairo_1_tests")]
+        let program_data = include_bytes!("../starknet_programs/cairo1/hello_world_account.casm");
+        let contract_class: CasmContractClass = serde_json::from_slice(program_data).unwrap();
+        let class_hash = ClassHash([1; 32]);
+        let mut state_reader = state_reader_with_nonce(5);
+        state_reader
+            .address_to_class_hash
+            .insert(Address(1.into()), class_hash);
+        state_reader
+            .class_hash_to_compiled_class
+            .insert(class_hash, CompiledClass::Casm(Arc::new(contract_class)));
+        let state = CachedState::new(
+            Arc::new(state_reader),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let mut mempool = Mempool::default();
+
+        // The account's `__validate__` requires a signature of two elements.
+        let invoke_with_signature = |signature: Vec<Felt252>| {
+            Transaction::InvokeFunction(
+                InvokeFunction::new(
+                    Address(1.into()),
+                    EXECUTE_ENTRY_POINT_SELECTOR.clone(),
+                    10,
+                    1.into(),
+                    vec![2.into(), 3.into(), 0.into()],
+                    signature,
+                    StarknetChainId::TestNet.to_felt(),
+                    Some(5.into()),
+                )
+                .unwrap(),
+            )
+        };
+        assert!(matches!(
+            add(&mut mempool, &state, invoke_with_signature(vec![4.into()])),
+            Err(MempoolError::Validation(_))
+        ));
+        assert!(mempool.is_empty());
+
+        add(
+            &mut mempool,
+            &state,
+            invoke_with_signature(vec![4.into(), 5.into()]),
+        )
+        .unwrap();
+        assert_eq!(mempool.n_ready(), 1);
+    }
+}
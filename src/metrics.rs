@@ -0,0 +1,419 @@
+//! # Metrics
+//!
+//! When the `metrics` feature is enabled, the crate records execution and cache metrics into a
+//! global registry, which can be exported in the Prometheus text format using
+//! [`export_prometheus`]. The recorded metrics are:
+//!   - `starknet_in_rust_transaction_execution_seconds`: Execution time, by transaction type.
+//!   - `starknet_in_rust_transaction_vm_steps`: VM steps, by transaction type.
+//!   - `starknet_in_rust_syscalls_total`: Syscalls executed, by syscall name.
+//!   - `starknet_in_rust_class_cache_lookups_total`: Contract class lookups, by cache (`private`,
+//!     `shared` or `state_reader`, in case of a miss on both caches).
+//!   - `starknet_in_rust_state_reader_seconds`: State reader latency, by operation.
+//!
+//! When the feature is disabled, recording does nothing and the registry is not available.
+
+use crate::definitions::transaction_type::TransactionType;
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+pub use self::registry::*;
+
+/// Where a contract class was found.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ClassCacheLookup {
+    /// Found in the state's private cache.
+    Private,
+    /// Found in the shared cache.
+    Shared,
+    /// Not found in any cache, so it was fetched from the state reader.
+    StateReader,
+}
+
+impl ClassCacheLookup {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ClassCacheLookup::Private => "private",
+            ClassCacheLookup::Shared => "shared",
+            ClassCacheLookup::StateReader => "state_reader",
+        }
+    }
+}
+
+/// Returns the label used for a transaction type in the exported metrics.
+pub const fn transaction_type_label(tx_type: TransactionType) -> &'static str {
+    match tx_type {
+        TransactionType::Declare => "declare",
+        TransactionType::Deploy => "deploy",
+        TransactionType::DeployAccount => "deploy_account",
+        TransactionType::InitializeBlockInfo => "initialize_block_info",
+        TransactionType::InvokeFunction => "invoke_function",
+        TransactionType::L1Handler => "l1_handler",
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+mod recorders {
+    use super::*;
+
+    #[inline(always)]
+    pub(crate) fn record_transaction(
+        _tx_type: TransactionType,
+        _elapsed: Duration,
+        _n_steps: Option<usize>,
+    ) {
+        // does nothing
+    }
+
+    #[inline(always)]
+    pub(crate) fn record_syscall(_syscall_name: &str, _amount: u64) {
+        // does nothing
+    }
+
+    #[inline(always)]
+    pub(crate) fn record_class_cache_lookup(_lookup: ClassCacheLookup) {
+        // does nothing
+    }
+}
+
+#[cfg(feature = "metrics")]
+mod recorders {
+    use super::*;
+
+    pub(crate) fn record_transaction(
+        tx_type: TransactionType,
+        elapsed: Duration,
+        n_steps: Option<usize>,
+    ) {
+        let label = transaction_type_label(tx_type);
+        let registry = registry();
+
+        registry
+            .transaction_execution_seconds
+            .observe(label, elapsed.as_secs_f64());
+        if let Some(n_steps) = n_steps {
+            registry.transaction_vm_steps.observe(label, n_steps as f64);
+        }
+    }
+
+    pub(crate) fn record_syscall(syscall_name: &str, amount: u64) {
+        registry().syscalls.increment(syscall_name, amount);
+    }
+
+    pub(crate) fn record_class_cache_lookup(lookup: ClassCacheLookup) {
+        registry().class_cache_lookups.increment(lookup.as_str(), 1);
+    }
+
+    pub(crate) fn record_state_reader_call(operation: &'static str, elapsed: Duration) {
+        registry()
+            .state_reader_seconds
+            .observe(operation, elapsed.as_secs_f64());
+    }
+}
+
+pub(crate) use recorders::*;
+
+/// Runs a state reader operation, recording its latency.
+#[inline(always)]
+pub(crate) fn time_state_reader_call<T>(operation: &'static str, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "metrics")]
+    let start = std::time::Instant::now();
+    let result = f();
+    #[cfg(feature = "metrics")]
+    record_state_reader_call(operation, start.elapsed());
+    #[cfg(not(feature = "metrics"))]
+    let _ = operation;
+
+    result
+}
+
+#[cfg(feature = "metrics")]
+mod registry {
+    use lazy_static::lazy_static;
+    use std::{
+        collections::BTreeMap,
+        fmt::Write,
+        sync::{Mutex, RwLock},
+    };
+
+    lazy_static! {
+        static ref REGISTRY: MetricsRegistry = MetricsRegistry::default();
+    }
+
+    /// Bucket upper bounds (in seconds) for latency histograms.
+    const LATENCY_BUCKETS: &[f64] = &[
+        0.00001, 0.00005, 0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0,
+    ];
+    /// Bucket upper bounds for VM step histograms.
+    const STEP_BUCKETS: &[f64] = &[
+        100.0, 500.0, 1000.0, 5000.0, 10000.0, 50000.0, 100000.0, 500000.0, 1000000.0, 3000000.0,
+    ];
+
+    /// Returns the global metrics registry.
+    pub fn registry() -> &'static MetricsRegistry {
+        &REGISTRY
+    }
+
+    /// Exports the global metrics registry in the Prometheus text format.
+    pub fn export_prometheus() -> String {
+        registry().export_prometheus()
+    }
+
+    /// A set of counters sharing a name, distinguished by a label.
+    #[derive(Debug)]
+    pub struct CounterVec {
+        label: &'static str,
+        values: RwLock<BTreeMap<String, u64>>,
+    }
+
+    impl CounterVec {
+        const fn new(label: &'static str) -> Self {
+            Self {
+                label,
+                values: RwLock::new(BTreeMap::new()),
+            }
+        }
+
+        pub(crate) fn increment(&self, label_value: &str, amount: u64) {
+            *self
+                .values
+                .write()
+                .unwrap()
+                .entry(label_value.to_string())
+                .or_default() += amount;
+        }
+
+        /// Returns the value of the counter with the given label value.
+        pub fn get(&self, label_value: &str) -> u64 {
+            self.values
+                .read()
+                .unwrap()
+                .get(label_value)
+                .copied()
+                .unwrap_or_default()
+        }
+
+        fn reset(&self) {
+            self.values.write().unwrap().clear();
+        }
+
+        fn export(&self, out: &mut String, name: &str, help: &str) {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            for (label_value, value) in self.values.read().unwrap().iter() {
+                let _ = writeln!(out, "{name}{{{}=\"{label_value}\"}} {value}", self.label);
+            }
+        }
+    }
+
+    /// The observations of a single histogram.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct HistogramSnapshot {
+        /// Cumulative count of observations for each bucket upper bound.
+        pub buckets: Vec<(f64, u64)>,
+        pub sum: f64,
+        pub count: u64,
+    }
+
+    /// A set of histograms sharing a name and buckets, distinguished by a label.
+    #[derive(Debug)]
+    pub struct HistogramVec {
+        label: &'static str,
+        bounds: &'static [f64],
+        values: Mutex<BTreeMap<String, HistogramSnapshot>>,
+    }
+
+    impl HistogramVec {
+        const fn new(label: &'static str, bounds: &'static [f64]) -> Self {
+            Self {
+                label,
+                bounds,
+                values: Mutex::new(BTreeMap::new()),
+            }
+        }
+
+        pub(crate) fn observe(&self, label_value: &str, value: f64) {
+            let mut values = self.values.lock().unwrap();
+            let histogram =
+                values
+                    .entry(label_value.to_string())
+                    .or_insert_with(|| HistogramSnapshot {
+                        buckets: self.bounds.iter().map(|bound| (*bound, 0)).collect(),
+                        sum: 0.0,
+                        count: 0,
+                    });
+
+            histogram
+                .buckets
+                .iter_mut()
+                .filter(|(bound, _)| value <= *bound)
+                .for_each(|(_, count)| *count += 1);
+            histogram.sum += value;
+            histogram.count += 1;
+        }
+
+        /// Returns the histogram with the given label value.
+        pub fn get(&self, label_value: &str) -> Option<HistogramSnapshot> {
+            self.values.lock().unwrap().get(label_value).cloned()
+        }
+
+        fn reset(&self) {
+            self.values.lock().unwrap().clear();
+        }
+
+        fn export(&self, out: &mut String, name: &str, help: &str) {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} histogram");
+            for (label_value, histogram) in self.values.lock().unwrap().iter() {
+                let label = self.label;
+                for (bound, count) in &histogram.buckets {
+                    let _ = writeln!(
+                        out,
+                        "{name}_bucket{{{label}=\"{label_value}\",le=\"{bound}\"}} {count}"
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    "{name}_bucket{{{label}=\"{label_value}\",le=\"+Inf\"}} {}",
+                    histogram.count
+                );
+                let _ = writeln!(
+                    out,
+                    "{name}_sum{{{label}=\"{label_value}\"}} {}",
+                    histogram.sum
+                );
+                let _ = writeln!(
+                    out,
+                    "{name}_count{{{label}=\"{label_value}\"}} {}",
+                    histogram.count
+                );
+            }
+        }
+    }
+
+    /// The registry holding all the metrics recorded by the crate.
+    #[derive(Debug)]
+    pub struct MetricsRegistry {
+        pub transaction_execution_seconds: HistogramVec,
+        pub transaction_vm_steps: HistogramVec,
+        pub syscalls: CounterVec,
+        pub class_cache_lookups: CounterVec,
+        pub state_reader_seconds: HistogramVec,
+    }
+
+    impl Default for MetricsRegistry {
+        fn default() -> Self {
+            Self {
+                transaction_execution_seconds: HistogramVec::new("tx_type", LATENCY_BUCKETS),
+                transaction_vm_steps: HistogramVec::new("tx_type", STEP_BUCKETS),
+                syscalls: CounterVec::new("syscall"),
+                class_cache_lookups: CounterVec::new("cache"),
+                state_reader_seconds: HistogramVec::new("operation", LATENCY_BUCKETS),
+            }
+        }
+    }
+
+    impl MetricsRegistry {
+        /// Returns the ratio of class lookups served by the private or the shared cache, or `None`
+        /// if there were no lookups.
+        pub fn class_cache_hit_rate(&self) -> Option<f64> {
+            let private = self.class_cache_lookups.get("private");
+            let shared = self.class_cache_lookups.get("shared");
+            let misses = self.class_cache_lookups.get("state_reader");
+
+            let total = private + shared + misses;
+            (total != 0).then(|| (private + shared) as f64 / total as f64)
+        }
+
+        /// Clears every recorded metric.
+        pub fn reset(&self) {
+            self.transaction_execution_seconds.reset();
+            self.transaction_vm_steps.reset();
+            self.syscalls.reset();
+            self.class_cache_lookups.reset();
+            self.state_reader_seconds.reset();
+        }
+
+        /// Exports every recorded metric in the Prometheus text format.
+        pub fn export_prometheus(&self) -> String {
+            let mut out = String::new();
+
+            self.transaction_execution_seconds.export(
+                &mut out,
+                "starknet_in_rust_transaction_execution_seconds",
+                "Transaction execution time in seconds.",
+            );
+            self.transaction_vm_steps.export(
+                &mut out,
+                "starknet_in_rust_transaction_vm_steps",
+                "VM steps used by each transaction.",
+            );
+            self.syscalls.export(
+                &mut out,
+                "starknet_in_rust_syscalls_total",
+                "Number of syscalls executed.",
+            );
+            self.class_cache_lookups.export(
+                &mut out,
+                "starknet_in_rust_class_cache_lookups_total",
+                "Number of contract class lookups, by the cache which served them.",
+            );
+            self.state_reader_seconds.export(
+                &mut out,
+                "starknet_in_rust_state_reader_seconds",
+                "State reader latency in seconds.",
+            );
+
+            out
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn export_histogram_and_counter() {
+            let registry = MetricsRegistry::default();
+            registry.syscalls.increment("storage_read", 2);
+            registry.syscalls.increment("storage_read", 1);
+            registry
+                .transaction_vm_steps
+                .observe("invoke_function", 700.0);
+
+            assert_eq!(registry.syscalls.get("storage_read"), 3);
+            let histogram = registry
+                .transaction_vm_steps
+                .get("invoke_function")
+                .unwrap();
+            assert_eq!(histogram.count, 1);
+            assert_eq!(histogram.buckets[0], (100.0, 0));
+            assert_eq!(histogram.buckets[2], (1000.0, 1));
+
+            let exported = registry.export_prometheus();
+            assert!(exported.contains("# TYPE starknet_in_rust_syscalls_total counter"));
+            assert!(
+                exported.contains("starknet_in_rust_syscalls_total{syscall=\"storage_read\"} 3")
+            );
+            assert!(exported.contains(
+                "starknet_in_rust_transaction_vm_steps_bucket{tx_type=\"invoke_function\",le=\"1000\"} 1"
+            ));
+            assert!(exported.contains(
+                "starknet_in_rust_transaction_vm_steps_count{tx_type=\"invoke_function\"} 1"
+            ));
+
+            registry.reset();
+            assert_eq!(registry.syscalls.get("storage_read"), 0);
+        }
+
+        #[test]
+        fn class_cache_hit_rate() {
+            let registry = MetricsRegistry::default();
+            assert_eq!(registry.class_cache_hit_rate(), None);
+
+            registry.class_cache_lookups.increment("private", 2);
+            registry.class_cache_lookups.increment("shared", 1);
+            registry.class_cache_lookups.increment("state_reader", 1);
+            assert_eq!(registry.class_cache_hit_rate(), Some(0.75));
+        }
+    }
+}
@@ -174,7 +174,7 @@ struct SerializedContractClass<'a> {
 use flate2::bufread;
 // Uncompresses a Gz Encoded vector of bytes and returns a string or error
 // Here &[u8] implements BufRead
-fn decode_reader(bytes: Vec<u8>) -> io::Result<String> {
+pub(crate) fn decode_reader(bytes: Vec<u8>) -> io::Result<String> {
     let mut gz = bufread::GzDecoder::new(&bytes[..]);
     let mut s = String::new();
     gz.read_to_string(&mut s)?;
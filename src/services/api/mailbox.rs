@@ -0,0 +1,235 @@
+//! # L1 mailbox
+//!
+//! An in-process stand-in for the Starknet core contract, which lets tests exercise L1 messaging
+//! without an L1 node. Messages sent to L1 by executed transactions are collected and may then be
+//! consumed, and messages sent to L2 are turned into [`L1Handler`] transactions.
+
+use super::messages::{StarknetMessageToL1, StarknetMessageToL2};
+use crate::{
+    execution::TransactionExecutionInfo,
+    transaction::{error::TransactionError, L1Handler},
+    utils::Address,
+};
+use cairo_vm::felt::Felt252;
+use num_traits::{One, Zero};
+use std::collections::VecDeque;
+
+/// A message to L2 waiting to be handled, with the fee paid for it on L1.
+#[derive(Debug, Clone)]
+struct QueuedMessageToL2 {
+    message: StarknetMessageToL2,
+    paid_fee_on_l1: Option<Felt252>,
+}
+
+/// Keeps track of the messages exchanged between L1 and L2.
+#[derive(Debug, Clone)]
+pub struct L1Mailbox {
+    messages_to_l1: Vec<StarknetMessageToL1>,
+    messages_to_l2: VecDeque<QueuedMessageToL2>,
+    next_l1_to_l2_nonce: Felt252,
+}
+
+impl L1Mailbox {
+    pub fn new() -> Self {
+        Self {
+            messages_to_l1: Vec::new(),
+            messages_to_l2: VecDeque::new(),
+            next_l1_to_l2_nonce: Felt252::zero(),
+        }
+    }
+
+    /// Collects the messages sent to L1 by an executed transaction, in the order they were sent.
+    pub fn collect(&mut self, execution_info: &TransactionExecutionInfo) {
+        for call in execution_info
+            .non_optional_calls()
+            .iter()
+            .flat_map(|call| call.gen_call_topology())
+        {
+            let mut messages = call.l2_to_l1_messages.clone();
+            messages.sort_by_key(|message| message.order);
+            self.messages_to_l1
+                .extend(messages.into_iter().map(|message| {
+                    StarknetMessageToL1::new(
+                        call.contract_address.clone(),
+                        message.to_address,
+                        message.payload,
+                    )
+                }));
+        }
+    }
+
+    /// Returns the messages sent to L1 which weren't consumed yet.
+    pub fn pending_messages_to_l1(&self) -> &[StarknetMessageToL1] {
+        &self.messages_to_l1
+    }
+
+    /// Consumes a message sent to L1, as the receiving L1 contract would. Returns the hash of the
+    /// message, or an error if the message wasn't sent or was already consumed.
+    pub fn consume_message_to_l1(
+        &mut self,
+        message: &StarknetMessageToL1,
+    ) -> Result<Vec<u8>, TransactionError> {
+        let position = self
+            .messages_to_l1
+            .iter()
+            .position(|pending| pending == message)
+            .ok_or_else(|| {
+                TransactionError::MessageToL1NotPending(hex::encode(message.get_hash()))
+            })?;
+
+        Ok(self.messages_to_l1.remove(position).get_hash())
+    }
+
+    /// Sends a message to the L1 handler `selector` of the L2 contract `to_address`, as the
+    /// sending L1 contract would. Returns the queued message, whose nonce is assigned by the
+    /// mailbox.
+    pub fn send_message_to_l2(
+        &mut self,
+        from_address: Address,
+        to_address: Address,
+        selector: Felt252,
+        payload: Vec<Felt252>,
+        paid_fee_on_l1: Option<Felt252>,
+    ) -> StarknetMessageToL2 {
+        let nonce = self.next_l1_to_l2_nonce.clone();
+        self.next_l1_to_l2_nonce += Felt252::one();
+
+        let message = StarknetMessageToL2::new(from_address, to_address, selector, payload, nonce);
+        self.messages_to_l2.push_back(QueuedMessageToL2 {
+            message: message.clone(),
+            paid_fee_on_l1,
+        });
+
+        message
+    }
+
+    /// Returns the messages sent to L2 which weren't turned into transactions yet.
+    pub fn pending_messages_to_l2(&self) -> impl Iterator<Item = &StarknetMessageToL2> {
+        self.messages_to_l2.iter().map(|queued| &queued.message)
+    }
+
+    /// Drains the messages sent to L2, creating an [`L1Handler`] transaction for each of them in
+    /// the order they were sent.
+    pub fn create_l1_handler_txs(
+        &mut self,
+        chain_id: Felt252,
+    ) -> Result<Vec<L1Handler>, TransactionError> {
+        self.messages_to_l2
+            .drain(..)
+            .map(
+                |QueuedMessageToL2 {
+                     message,
+                     paid_fee_on_l1,
+                 }| {
+                    L1Handler::new(
+                        message.to_address().clone(),
+                        message.selector().clone(),
+                        message.l1_handler_calldata(),
+                        message.nonce().clone(),
+                        chain_id.clone(),
+                        paid_fee_on_l1,
+                    )
+                },
+            )
+            .collect()
+    }
+}
+
+impl Default for L1Mailbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        definitions::block_context::StarknetChainId,
+        execution::{CallInfo, OrderedL2ToL1Message},
+    };
+    use coverage_helper::test;
+
+    #[test]
+    fn collect_and_consume_messages_to_l1() {
+        let message = |order: usize, payload: u64| OrderedL2ToL1Message {
+            order,
+            to_address: Address(1729.into()),
+            payload: vec![payload.into()],
+        };
+        let inner_call = CallInfo {
+            contract_address: Address(43.into()),
+            l2_to_l1_messages: vec![message(0, 3)],
+            ..Default::default()
+        };
+        let execution_info = TransactionExecutionInfo {
+            call_info: Some(CallInfo {
+                contract_address: Address(42.into()),
+                l2_to_l1_messages: vec![message(1, 2), message(0, 1)],
+                internal_calls: vec![inner_call],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut mailbox = L1Mailbox::new();
+        mailbox.collect(&execution_info);
+
+        let sent = |from: u64, payload: u64| {
+            StarknetMessageToL1::new(
+                Address(from.into()),
+                Address(1729.into()),
+                vec![payload.into()],
+            )
+        };
+        assert_eq!(
+            mailbox.pending_messages_to_l1(),
+            &[sent(42, 1), sent(42, 2), sent(43, 3)]
+        );
+
+        assert_eq!(
+            mailbox.consume_message_to_l1(&sent(42, 2)).unwrap(),
+            sent(42, 2).get_hash()
+        );
+        assert!(matches!(
+            mailbox.consume_message_to_l1(&sent(42, 2)),
+            Err(TransactionError::MessageToL1NotPending(_))
+        ));
+        assert_eq!(
+            mailbox.pending_messages_to_l1(),
+            &[sent(42, 1), sent(43, 3)]
+        );
+    }
+
+    #[test]
+    fn create_l1_handler_txs_from_messages_to_l2() {
+        let mut mailbox = L1Mailbox::new();
+        let first = mailbox.send_message_to_l2(
+            Address(1.into()),
+            Address(2.into()),
+            3.into(),
+            vec![4.into()],
+            Some(5.into()),
+        );
+        let second = mailbox.send_message_to_l2(
+            Address(1.into()),
+            Address(2.into()),
+            3.into(),
+            vec![],
+            None,
+        );
+
+        assert_eq!(first.nonce(), &Felt252::zero());
+        assert_eq!(second.nonce(), &Felt252::one());
+        assert_eq!(mailbox.pending_messages_to_l2().count(), 2);
+
+        let txs = mailbox
+            .create_l1_handler_txs(StarknetChainId::TestNet.to_felt())
+            .unwrap();
+
+        assert_eq!(txs.len(), 2);
+        assert_eq!(txs[0].contract_address(), &Address(2.into()));
+        assert_ne!(txs[0].hash_value(), txs[1].hash_value());
+        assert_eq!(mailbox.pending_messages_to_l2().count(), 0);
+    }
+}
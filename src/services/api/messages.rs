@@ -1,11 +1,20 @@
 use cairo_vm::felt::Felt252;
-use num_traits::ToPrimitive;
 use sha3::{Digest, Keccak256};
 
 use crate::utils::Address;
 
+/// Hashes the encoding of a message the way the Starknet core contract does on L1: each felt is
+/// encoded as a 32-byte big-endian word, and the words are hashed with keccak256.
+fn keccak_encoding(encoding: &[Felt252]) -> Vec<u8> {
+    let mut hasher = Keccak256::new();
+    for elem in encoding {
+        hasher.update(elem.to_be_bytes());
+    }
+    hasher.finalize().to_vec()
+}
+
 /// A StarkNet Message from L2 to L1.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StarknetMessageToL1 {
     from_address: Address,
     to_address: Address,
@@ -31,16 +40,101 @@ impl StarknetMessageToL1 {
         encoding
     }
 
+    /// Returns the hash of the message, used by the Starknet core contract to identify it.
     pub fn get_hash(&self) -> Vec<u8> {
-        let data = self
-            .encode()
-            .iter()
-            .map(|elem| elem.to_usize().unwrap() as u8)
-            .collect::<Vec<u8>>();
-        let mut hasher = Keccak256::new();
-        hasher.update(data);
-        let finalized_hash = hasher.finalize();
-        finalized_hash.as_slice().to_vec()
+        keccak_encoding(&self.encode())
+    }
+
+    /// Returns the address of the L2 contract which sent the message.
+    pub const fn from_address(&self) -> &Address {
+        &self.from_address
+    }
+
+    /// Returns the address of the L1 contract which receives the message.
+    pub const fn to_address(&self) -> &Address {
+        &self.to_address
+    }
+
+    pub fn payload(&self) -> &[Felt252] {
+        &self.payload
+    }
+}
+
+/// A StarkNet Message from L1 to L2, which is handled by an L1 handler of the receiving contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StarknetMessageToL2 {
+    from_address: Address,
+    to_address: Address,
+    selector: Felt252,
+    payload: Vec<Felt252>,
+    nonce: Felt252,
+}
+
+impl StarknetMessageToL2 {
+    pub fn new(
+        from_address: Address,
+        to_address: Address,
+        selector: Felt252,
+        payload: Vec<Felt252>,
+        nonce: Felt252,
+    ) -> Self {
+        StarknetMessageToL2 {
+            from_address,
+            to_address,
+            selector,
+            payload,
+            nonce,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<Felt252> {
+        let mut encoding = Vec::with_capacity(self.payload.len() + 5);
+        encoding.push(self.from_address.0.clone());
+        encoding.push(self.to_address.0.clone());
+        encoding.push(self.nonce.clone());
+        encoding.push(self.selector.clone());
+        encoding.push(self.payload.len().into());
+        encoding.extend_from_slice(&self.payload);
+
+        encoding
+    }
+
+    /// Returns the hash of the message, used by the Starknet core contract to identify it.
+    pub fn get_hash(&self) -> Vec<u8> {
+        keccak_encoding(&self.encode())
+    }
+
+    /// Returns the calldata of the L1 handler which handles the message: the address of the L1
+    /// sender followed by the payload.
+    pub fn l1_handler_calldata(&self) -> Vec<Felt252> {
+        let mut calldata = Vec::with_capacity(self.payload.len() + 1);
+        calldata.push(self.from_address.0.clone());
+        calldata.extend_from_slice(&self.payload);
+
+        calldata
+    }
+
+    /// Returns the address of the L1 contract which sent the message.
+    pub const fn from_address(&self) -> &Address {
+        &self.from_address
+    }
+
+    /// Returns the address of the L2 contract which receives the message.
+    pub const fn to_address(&self) -> &Address {
+        &self.to_address
+    }
+
+    /// Returns the selector of the L1 handler which handles the message.
+    pub const fn selector(&self) -> &Felt252 {
+        &self.selector
+    }
+
+    pub fn payload(&self) -> &[Felt252] {
+        &self.payload
+    }
+
+    pub const fn nonce(&self) -> &Felt252 {
+        &self.nonce
     }
 }
 
@@ -91,8 +185,63 @@ fn get_hash_for_starknet_message_to_l1() {
     assert_eq!(
         message.get_hash(),
         Vec::from([
-            35, 146, 105, 229, 123, 197, 150, 164, 71, 161, 100, 157, 18, 54, 233, 219, 32, 150,
-            155, 238, 74, 8, 254, 114, 153, 144, 74, 32, 110, 104, 86, 42
+            167, 136, 113, 216, 253, 103, 186, 175, 18, 220, 46, 76, 223, 13, 159, 1, 39, 10, 16,
+            199, 0, 12, 66, 204, 54, 152, 75, 18, 225, 196, 153, 246
+        ])
+    )
+}
+
+#[test]
+fn get_hash_for_starknet_message_to_l1_with_large_payload() {
+    use num_traits::{One, Zero};
+
+    let message = StarknetMessageToL1::new(
+        Address(42.into()),
+        Address(1729.into()),
+        vec![Felt252::zero() - Felt252::one(), 5.into()],
+    );
+
+    assert_eq!(
+        message.get_hash(),
+        Vec::from([
+            98, 102, 213, 221, 56, 207, 139, 47, 100, 205, 64, 203, 243, 140, 85, 122, 65, 17, 0,
+            206, 230, 41, 54, 180, 252, 242, 101, 156, 93, 98, 175, 55
+        ])
+    )
+}
+
+#[test]
+fn get_hash_for_starknet_message_to_l2() {
+    use cairo_vm::felt::felt_str;
+    use num_traits::{One, Zero};
+
+    let message = StarknetMessageToL2::new(
+        Address(felt_str!("c3511006C04EF1d78af4C8E0e74Ec18A6E64Ff9e", 16)),
+        Address(felt_str!(
+            "73314940630fd6dcda0d772d4c972c4e0a9946bef9dabf4ef84eda8ef542b82",
+            16
+        )),
+        felt_str!(
+            "2d757788a8d8d6f21d1cd40bce38a8222d70654214e96ff95d8086e684fbee5",
+            16
+        ),
+        vec![1.into(), Felt252::zero() - Felt252::one()],
+        12.into(),
+    );
+
+    assert_eq!(
+        message.l1_handler_calldata(),
+        vec![
+            message.from_address().0.clone(),
+            1.into(),
+            Felt252::zero() - Felt252::one()
+        ]
+    );
+    assert_eq!(
+        message.get_hash(),
+        Vec::from([
+            245, 234, 7, 12, 175, 119, 82, 126, 95, 175, 210, 253, 72, 131, 140, 15, 58, 155, 89,
+            125, 225, 148, 168, 218, 200, 5, 117, 23, 230, 193, 183, 93
         ])
     )
 }
@@ -1,3 +1,4 @@
 pub mod contract_class_errors;
 pub mod contract_classes;
+pub mod mailbox;
 pub mod messages;
@@ -0,0 +1,309 @@
+//! # Signer
+//!
+//! Utilities to send signed transactions from accounts which validate Stark curve ECDSA
+//! signatures, like the bundled `Account.cairo`.
+//!
+//! A [`Signer`] holds a key pair. It signs transaction hashes, and the account address it
+//! controls can be calculated before the account is deployed (as deploy account transactions
+//! require):
+//!
+//! ```ignore
+//! let signer = Signer::random();
+//! let salt = Felt252::from(1);
+//! let account_address = signer.account_address(&class_hash, &salt)?;
+//! // Fund `account_address` so it can pay for its deployment.
+//!
+//! let constructor_calldata = vec![signer.public_key().clone()];
+//! let deploy_account = DeployAccount::new(
+//!     class_hash.into(), max_fee, 1.into(), 0.into(), constructor_calldata, vec![], salt, chain_id,
+//! )?;
+//! let deploy_account = signer.sign_transaction(Transaction::DeployAccount(deploy_account))?;
+//!
+//! let calldata = encode_execute_calldata(
+//!     &[Call::new(contract_address, selector, calldata)],
+//!     ExecuteEncoding::Legacy,
+//! );
+//! ```
+
+use crate::{
+    core::errors::{contract_address_errors::ContractAddressError, signer_errors::SignerError},
+    hash_utils::calculate_contract_address,
+    transaction::{Declare, DeclareV2, Transaction},
+    utils::{felt_to_field_element, field_element_to_felt, Address},
+};
+use cairo_vm::felt::Felt252;
+use getset::Getters;
+use num_traits::Zero;
+use starknet::signers::SigningKey;
+use starknet_crypto::{get_public_key, rfc6979_generate_k, sign, verify};
+use std::fmt;
+
+/// A Stark curve key pair.
+#[derive(Clone, PartialEq, Eq, Getters)]
+pub struct Signer {
+    #[getset(get = "pub")]
+    private_key: Felt252,
+    #[getset(get = "pub")]
+    public_key: Felt252,
+}
+
+impl Signer {
+    /// Creates a signer from a private key.
+    pub fn new(private_key: Felt252) -> Result<Self, SignerError> {
+        if private_key.is_zero() {
+            return Err(SignerError::InvalidPrivateKey);
+        }
+        let public_key =
+            field_element_to_felt(&get_public_key(&felt_to_field_element(&private_key)?));
+
+        Ok(Self {
+            private_key,
+            public_key,
+        })
+    }
+
+    /// Creates a signer with a randomly generated private key.
+    pub fn random() -> Self {
+        let private_key = SigningKey::from_random().secret_scalar();
+        Self {
+            private_key: field_element_to_felt(&private_key),
+            public_key: field_element_to_felt(&get_public_key(&private_key)),
+        }
+    }
+
+    /// Signs a hash, returning the `[r, s]` signature. The signature is deterministic (RFC 6979).
+    pub fn sign_hash(&self, hash: &Felt252) -> Result<Vec<Felt252>, SignerError> {
+        let private_key = felt_to_field_element(&self.private_key)?;
+        let hash = felt_to_field_element(hash)?;
+        let k = rfc6979_generate_k(&hash, &private_key, None);
+        let signature =
+            sign(&private_key, &hash, &k).map_err(|e| SignerError::Sign(format!("{e:?}")))?;
+
+        Ok(vec![
+            field_element_to_felt(&signature.r),
+            field_element_to_felt(&signature.s),
+        ])
+    }
+
+    /// Returns whether the `[r, s]` signature of the hash was made by this signer.
+    pub fn verify(&self, hash: &Felt252, signature: &[Felt252]) -> Result<bool, SignerError> {
+        let [r, s] = signature else {
+            return Ok(false);
+        };
+        Ok(verify(
+            &felt_to_field_element(&self.public_key)?,
+            &felt_to_field_element(hash)?,
+            &felt_to_field_element(r)?,
+            &felt_to_field_element(s)?,
+        )
+        .unwrap_or(false))
+    }
+
+    /// Signs an invoke, declare or deploy account transaction, returning it with the signature
+    /// of its hash.
+    pub fn sign_transaction(&self, transaction: Transaction) -> Result<Transaction, SignerError> {
+        let signature = self.sign_hash(transaction.hash_value())?;
+
+        Ok(match transaction {
+            Transaction::Declare(tx) => Transaction::Declare(Declare { signature, ..tx }),
+            Transaction::DeclareV2(tx) => {
+                Transaction::DeclareV2(Box::new(DeclareV2 { signature, ..*tx }))
+            }
+            Transaction::DeployAccount(tx) => {
+                Transaction::DeployAccount(tx.with_signature(signature))
+            }
+            Transaction::InvokeFunction(tx) => {
+                Transaction::InvokeFunction(tx.with_signature(signature))
+            }
+            Transaction::Deploy(_) | Transaction::L1Handler(_) => {
+                return Err(SignerError::UnsignedTransaction(transaction.tx_type()))
+            }
+        })
+    }
+
+    /// Returns the address of an account controlled by this signer, given the class and salt it
+    /// will be deployed with. The account's constructor must take the public key as its only
+    /// argument, as `Account.cairo`'s does.
+    pub fn account_address(
+        &self,
+        class_hash: &Felt252,
+        salt: &Felt252,
+    ) -> Result<Address, ContractAddressError> {
+        counterfactual_address(class_hash, salt, &[self.public_key.clone()])
+    }
+}
+
+impl fmt::Debug for Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signer")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the address a contract will have when deployed by a deploy account transaction, which
+/// is known before the deployment so the account can be funded.
+pub fn counterfactual_address(
+    class_hash: &Felt252,
+    salt: &Felt252,
+    constructor_calldata: &[Felt252],
+) -> Result<Address, ContractAddressError> {
+    Ok(Address(calculate_contract_address(
+        salt,
+        class_hash,
+        constructor_calldata,
+        Address(Felt252::zero()),
+    )?))
+}
+
+/// A call made by an account's `__execute__` entry point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Call {
+    pub to: Address,
+    pub selector: Felt252,
+    pub calldata: Vec<Felt252>,
+}
+
+impl Call {
+    pub fn new(to: Address, selector: Felt252, calldata: Vec<Felt252>) -> Self {
+        Self {
+            to,
+            selector,
+            calldata,
+        }
+    }
+}
+
+/// The ABI of an account's `__execute__` entry point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExecuteEncoding {
+    /// Cairo 0 accounts (like `Account.cairo`): an array of `(to, selector, data_offset,
+    /// data_len)` followed by the calldata of all the calls.
+    Legacy,
+    /// Cairo 1 accounts: an `Array<Call>`, each call followed by its own calldata.
+    Cairo1,
+}
+
+/// Encodes the calldata of a multicall to an account's `__execute__` entry point.
+pub fn encode_execute_calldata(calls: &[Call], encoding: ExecuteEncoding) -> Vec<Felt252> {
+    let mut calldata = vec![calls.len().into()];
+
+    match encoding {
+        ExecuteEncoding::Legacy => {
+            let mut data_offset = 0;
+            for call in calls {
+                calldata.push(call.to.0.clone());
+                calldata.push(call.selector.clone());
+                calldata.push(data_offset.into());
+                calldata.push(call.calldata.len().into());
+                data_offset += call.calldata.len();
+            }
+            calldata.push(data_offset.into());
+            for call in calls {
+                calldata.extend_from_slice(&call.calldata);
+            }
+        }
+        ExecuteEncoding::Cairo1 => {
+            for call in calls {
+                calldata.push(call.to.0.clone());
+                calldata.push(call.selector.clone());
+                calldata.push(call.calldata.len().into());
+                calldata.extend_from_slice(&call.calldata);
+            }
+        }
+    }
+
+    calldata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{InvokeFunction, L1Handler};
+    use assert_matches::assert_matches;
+    use cairo_vm::felt::felt_str;
+    use coverage_helper::test;
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn sign_and_verify() {
+        // The public key of the private key 1 is the x coordinate of the curve's generator.
+        let signer = Signer::new(1.into()).unwrap();
+        assert_eq!(
+            signer.public_key(),
+            &felt_str!(
+                "1ef15c18599971b7beced415a40f0c7deacfd9b0d1819e03d723d8bc943cfca",
+                16
+            )
+        );
+        assert_matches!(Signer::new(0.into()), Err(SignerError::InvalidPrivateKey));
+
+        let signer = Signer::random();
+        let hash = Felt252::from(0x1234);
+        let signature = signer.sign_hash(&hash).unwrap();
+        assert_eq!(signature, signer.sign_hash(&hash).unwrap());
+        assert!(signer.verify(&hash, &signature).unwrap());
+        assert!(!signer.verify(&(hash + 1), &signature).unwrap());
+        assert!(!Signer::random().verify(&0x1234.into(), &signature).unwrap());
+    }
+
+    #[test]
+    fn sign_transactions() {
+        let signer = Signer::new(0x5678.into()).unwrap();
+        let invoke = InvokeFunction::new_with_tx_hash(
+            Address(0x42.into()),
+            0x43.into(),
+            0,
+            1.into(),
+            vec![],
+            vec![],
+            Some(0.into()),
+            0x1234.into(),
+        )
+        .unwrap();
+
+        let Transaction::InvokeFunction(invoke) = signer
+            .sign_transaction(Transaction::InvokeFunction(invoke))
+            .unwrap()
+        else {
+            panic!("expected an invoke transaction");
+        };
+        assert!(signer
+            .verify(invoke.hash_value(), invoke.signature())
+            .unwrap());
+
+        let l1_handler = L1Handler::new_with_tx_hash(
+            Address(0x42.into()),
+            0x43.into(),
+            vec![],
+            0.into(),
+            None,
+            0x1234.into(),
+        )
+        .unwrap();
+        assert_matches!(
+            signer.sign_transaction(Transaction::L1Handler(l1_handler)),
+            Err(SignerError::UnsignedTransaction(_))
+        );
+    }
+
+    #[test]
+    fn execute_calldata_encodings() {
+        let calls = [
+            Call::new(Address(0x10.into()), 0x20.into(), vec![1.into(), 2.into()]),
+            Call::new(Address(0x11.into()), 0x21.into(), vec![3.into()]),
+        ];
+        let felts = |values: &[u32]| -> Vec<Felt252> {
+            values.iter().map(|value| Felt252::from(*value)).collect()
+        };
+
+        assert_eq!(
+            encode_execute_calldata(&calls, ExecuteEncoding::Legacy),
+            felts(&[2, 0x10, 0x20, 0, 2, 0x11, 0x21, 2, 1, 3, 1, 2, 3])
+        );
+        assert_eq!(
+            encode_execute_calldata(&calls, ExecuteEncoding::Cairo1),
+            felts(&[2, 0x10, 0x20, 2, 1, 2, 0x11, 0x21, 1, 3])
+        );
+    }
+}
@@ -5,6 +5,7 @@ use super::{
 };
 use crate::{
     core::errors::state_errors::StateError,
+    metrics::{self, ClassCacheLookup},
     services::api::contract_classes::compiled_class::CompiledClass,
     state::StateDiff,
     utils::{
@@ -115,6 +116,28 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
         }
     }
 
+    /// Returns the hash under which the contract class is stored in the contract class caches,
+    /// following `get_contract_class`: the compiled class hash of Cairo 1 classes declared in this
+    /// state (unless the class is cached under its class hash), and the class hash otherwise, as
+    /// classes fetched from the state reader are cached under the hash they were requested with.
+    pub(crate) fn contract_class_cache_key(&self, class_hash: ClassHash) -> ClassHash {
+        match self
+            .cache
+            .class_hash_to_compiled_class_hash
+            .get(&class_hash)
+        {
+            Some(compiled_class_hash)
+                if self
+                    .contract_class_cache
+                    .get_contract_class(class_hash)
+                    .is_none() =>
+            {
+                *compiled_class_hash
+            }
+            _ => class_hash,
+        }
+    }
+
     pub fn drain_private_contract_class_cache(
         &self,
     ) -> Result<impl Iterator<Item = (ClassHash, CompiledClass)>, StateError> {
@@ -149,13 +172,19 @@ impl<T: StateReader, C: ContractClassCache> StateReader for CachedState<T, C> {
         self.cache
             .get_class_hash(contract_address)
             .map(|a| Ok(*a))
-            .unwrap_or_else(|| self.state_reader.get_class_hash_at(contract_address))
+            .unwrap_or_else(|| {
+                metrics::time_state_reader_call("get_class_hash_at", || {
+                    self.state_reader.get_class_hash_at(contract_address)
+                })
+            })
     }
 
     /// Returns the nonce for a given contract address.
     fn get_nonce_at(&self, contract_address: &Address) -> Result<Felt252, StateError> {
         if self.cache.get_nonce(contract_address).is_none() {
-            return self.state_reader.get_nonce_at(contract_address);
+            return metrics::time_state_reader_call("get_nonce_at", || {
+                self.state_reader.get_nonce_at(contract_address)
+            });
         }
         self.cache
             .get_nonce(contract_address)
@@ -169,7 +198,11 @@ impl<T: StateReader, C: ContractClassCache> StateReader for CachedState<T, C> {
         self.cache
             .get_storage(storage_entry)
             .map(|v| Ok(v.clone()))
-            .unwrap_or_else(|| self.state_reader.get_storage_at(storage_entry))
+            .unwrap_or_else(|| {
+                metrics::time_state_reader_call("get_storage_at", || {
+                    self.state_reader.get_storage_at(storage_entry)
+                })
+            })
     }
 
     // TODO: check if that the proper way to store it (converting hash to address)
@@ -180,7 +213,9 @@ impl<T: StateReader, C: ContractClassCache> StateReader for CachedState<T, C> {
         {
             Ok(*compiled_class_hash)
         } else {
-            self.state_reader.get_compiled_class_hash(class_hash)
+            metrics::time_state_reader_call("get_compiled_class_hash", || {
+                self.state_reader.get_compiled_class_hash(class_hash)
+            })
         }
     }
 
@@ -198,10 +233,12 @@ impl<T: StateReader, C: ContractClassCache> StateReader for CachedState<T, C> {
             .write()
             .map_err(|_| StateError::FailedToReadContractClassCache)?;
         if let Some(compiled_class) = private_cache.get(class_hash) {
+            metrics::record_class_cache_lookup(ClassCacheLookup::Private);
             return Ok(compiled_class.clone());
         } else if let Some(compiled_class) =
             self.contract_class_cache().get_contract_class(*class_hash)
         {
+            metrics::record_class_cache_lookup(ClassCacheLookup::Shared);
             private_cache.insert(*class_hash, compiled_class.clone());
             return Ok(compiled_class);
         }
@@ -211,18 +248,23 @@ impl<T: StateReader, C: ContractClassCache> StateReader for CachedState<T, C> {
             self.cache.class_hash_to_compiled_class_hash.get(class_hash)
         {
             if let Some(casm_class) = private_cache.get(compiled_class_hash) {
+                metrics::record_class_cache_lookup(ClassCacheLookup::Private);
                 return Ok(casm_class.clone());
             } else if let Some(casm_class) = self
                 .contract_class_cache()
                 .get_contract_class(*compiled_class_hash)
             {
+                metrics::record_class_cache_lookup(ClassCacheLookup::Shared);
                 private_cache.insert(*class_hash, casm_class.clone());
                 return Ok(casm_class);
             }
         }
 
         // II: FETCHING FROM STATE_READER
-        let contract_class = self.state_reader.get_contract_class(class_hash)?;
+        metrics::record_class_cache_lookup(ClassCacheLookup::StateReader);
+        let contract_class = metrics::time_state_reader_call("get_contract_class", || {
+            self.state_reader.get_contract_class(class_hash)
+        })?;
         private_cache.insert(*class_hash, contract_class.clone());
 
         Ok(contract_class)
@@ -392,7 +434,9 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
             }
             None => {
                 self.add_miss();
-                let class_hash = self.state_reader.get_class_hash_at(contract_address)?;
+                let class_hash = metrics::time_state_reader_call("get_class_hash_at", || {
+                    self.state_reader.get_class_hash_at(contract_address)
+                })?;
                 self.cache
                     .class_hash_initial_values
                     .insert(contract_address.clone(), class_hash);
@@ -404,7 +448,9 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
     fn get_nonce_at(&mut self, contract_address: &Address) -> Result<Felt252, StateError> {
         if self.cache.get_nonce(contract_address).is_none() {
             self.add_miss();
-            let nonce = self.state_reader.get_nonce_at(contract_address)?;
+            let nonce = metrics::time_state_reader_call("get_nonce_at", || {
+                self.state_reader.get_nonce_at(contract_address)
+            })?;
             self.cache
                 .nonce_initial_values
                 .insert(contract_address.clone(), nonce);
@@ -429,7 +475,9 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
             }
             None => {
                 self.add_miss();
-                let value = self.state_reader.get_storage_at(storage_entry)?;
+                let value = metrics::time_state_reader_call("get_storage_at", || {
+                    self.state_reader.get_storage_at(storage_entry)
+                })?;
                 self.cache
                     .storage_initial_values
                     .insert(storage_entry.clone(), value.clone());
@@ -452,7 +500,10 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
             }
             None => {
                 self.add_miss();
-                let compiled_class_hash = self.state_reader.get_compiled_class_hash(class_hash)?;
+                let compiled_class_hash =
+                    metrics::time_state_reader_call("get_compiled_class_hash", || {
+                        self.state_reader.get_compiled_class_hash(class_hash)
+                    })?;
                 let address = Address(Felt252::from_bytes_be(compiled_class_hash.to_bytes_be()));
                 self.cache
                     .class_hash_initial_values
@@ -479,11 +530,13 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
             .cloned();
         if let Some(compiled_class) = compiled_class_op {
             self.add_hit();
+            metrics::record_class_cache_lookup(ClassCacheLookup::Private);
             return Ok(compiled_class);
         } else if let Some(compiled_class) =
             self.contract_class_cache().get_contract_class(*class_hash)
         {
             self.add_hit();
+            metrics::record_class_cache_lookup(ClassCacheLookup::Shared);
             self.contract_class_cache_private
                 .write()
                 .map_err(|_| StateError::FailedToReadContractClassCache)?
@@ -503,12 +556,14 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
                 .cloned();
             if let Some(casm_class) = casm_class_op {
                 self.add_hit();
+                metrics::record_class_cache_lookup(ClassCacheLookup::Private);
                 return Ok(casm_class);
             } else if let Some(casm_class) = self
                 .contract_class_cache()
                 .get_contract_class(*compiled_class_hash)
             {
                 self.add_hit();
+                metrics::record_class_cache_lookup(ClassCacheLookup::Shared);
                 self.contract_class_cache_private
                     .write()
                     .map_err(|_| StateError::FailedToReadContractClassCache)?
@@ -527,7 +582,10 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
         //     )));
         // }
         // II: FETCHING FROM STATE_READER
-        let contract = self.state_reader.get_contract_class(class_hash)?;
+        metrics::record_class_cache_lookup(ClassCacheLookup::StateReader);
+        let contract = metrics::time_state_reader_call("get_contract_class", || {
+            self.state_reader.get_contract_class(class_hash)
+        })?;
         match contract {
             CompiledClass::Casm(ref casm_class) => {
                 // We call this method instead of state_reader's in order to update the cache's class_hash_initial_values map
@@ -587,7 +645,9 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
                 // This key was first accessed via write, so we need to cache its initial value
                 self.cache.storage_initial_values.insert(
                     storage_entry.clone(),
-                    self.state_reader.get_storage_at(storage_entry)?,
+                    metrics::time_state_reader_call("get_storage_at", || {
+                        self.state_reader.get_storage_at(storage_entry)
+                    })?,
                 );
             }
         }
@@ -596,7 +656,9 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
                 // This key was first accessed via write, so we need to cache its initial value
                 self.cache.class_hash_initial_values.insert(
                     address.clone(),
-                    self.state_reader.get_class_hash_at(address)?,
+                    metrics::time_state_reader_call("get_class_hash_at", || {
+                        self.state_reader.get_class_hash_at(address)
+                    })?,
                 );
             }
         }
@@ -609,7 +671,9 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
                 // This key was first accessed via write, so we need to cache its initial value
                 self.cache.nonce_initial_values.insert(
                     contract_address.clone(),
-                    self.state_reader.get_nonce_at(contract_address)?,
+                    metrics::time_state_reader_call("get_nonce_at", || {
+                        self.state_reader.get_nonce_at(contract_address)
+                    })?,
                 );
             }
         }
@@ -710,6 +774,50 @@ mod tests {
         );
     }
 
+    /// This test checks that the contract class cache key is the hash under which
+    /// `get_contract_class` caches the class.
+    #[test]
+    fn contract_class_cache_key_follows_the_cached_class() {
+        let mut state_reader = InMemoryStateReader::default();
+        state_reader
+            .class_hash_to_compiled_class_hash
+            .insert(ClassHash([1; 32]), ClassHash([2; 32]));
+
+        let mut cached_state = CachedState::new(
+            Arc::new(state_reader),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+
+        // Classes fetched from the state reader are cached under the requested hash.
+        assert_eq!(
+            cached_state.contract_class_cache_key(ClassHash([1; 32])),
+            ClassHash([1; 32])
+        );
+
+        // Classes declared in the state are cached under their compiled class hash.
+        cached_state
+            .cache
+            .class_hash_to_compiled_class_hash
+            .insert(ClassHash([1; 32]), ClassHash([2; 32]));
+        assert_eq!(
+            cached_state.contract_class_cache_key(ClassHash([1; 32])),
+            ClassHash([2; 32])
+        );
+
+        // Unless the class is already cached under its class hash.
+        let contract_class =
+            ContractClass::from_path("starknet_programs/raw_contract_classes/class_with_abi.json")
+                .unwrap();
+        cached_state.contract_class_cache.set_contract_class(
+            ClassHash([1; 32]),
+            CompiledClass::Deprecated(Arc::new(contract_class)),
+        );
+        assert_eq!(
+            cached_state.contract_class_cache_key(ClassHash([1; 32])),
+            ClassHash([1; 32])
+        );
+    }
+
     /// This test verifies the correct handling of storage in the cached state.
     #[test]
     fn cached_state_storage_test() {
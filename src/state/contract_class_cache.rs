@@ -6,9 +6,36 @@
 //! The trait `ContractClassCache` provides methods for retrieving and inserting elements into the
 //! cache. It also contains a method to extend the shared cache from an iterator so that it can be
 //! used with the private caches.
+//!
+//! Caches may also keep the prepared program of every stored class (see `PreparedProgram`), so
+//! that it doesn't have to be rebuilt every time the class is executed. The prepared program is
+//! dropped along with its class.
+//!
+//! Three policies are provided out of the box:
+//!   - `NullContractClassCache`: Stores nothing.
+//!   - `PermanentContractClassCache`: Stores everything, forever.
+//!   - `LruContractClassCache`: Stores the most recently used classes, bounded by an estimation of
+//!     their memory usage (and optionally by their number).
 
-use crate::{services::api::contract_classes::compiled_class::CompiledClass, utils::ClassHash};
-use std::{collections::HashMap, sync::RwLock};
+use crate::{
+    execution::prepared_program::PreparedProgram,
+    services::api::contract_classes::compiled_class::CompiledClass, utils::ClassHash,
+    ContractEntryPoint,
+};
+use cairo_lang_casm::hints::Hint;
+use cairo_lang_starknet::contract_class::ContractEntryPoint as SierraContractEntryPoint;
+use cairo_lang_utils::bigint::BigUintAsHex;
+use cairo_vm::types::relocatable::MaybeRelocatable;
+use lru::LruCache;
+use std::{
+    collections::HashMap,
+    mem::{size_of, size_of_val},
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+};
 
 /// The contract class cache trait, which must be implemented by all caches.
 pub trait ContractClassCache {
@@ -17,6 +44,21 @@ pub trait ContractClassCache {
     fn get_contract_class(&self, class_hash: ClassHash) -> Option<CompiledClass>;
     /// Inserts or replaces a contract class associated with a specific class hash.
     fn set_contract_class(&self, class_hash: ClassHash, compiled_class: CompiledClass);
+
+    /// Provides the prepared program of the contract class associated with a specific class hash,
+    /// or `None` if not present.
+    fn get_prepared_program(&self, _class_hash: ClassHash) -> Option<Arc<PreparedProgram>> {
+        None
+    }
+    /// Stores the prepared program of the contract class associated with a specific class hash.
+    /// Caches may ignore it, for example if the contract class itself is not stored.
+    fn set_prepared_program(
+        &self,
+        _class_hash: ClassHash,
+        _prepared_program: Arc<PreparedProgram>,
+    ) {
+        // Nothing needs to be done here.
+    }
 }
 
 /// A contract class cache which stores nothing. In other words, using this as a cache means there's
@@ -39,6 +81,7 @@ impl ContractClassCache for NullContractClassCache {
 #[derive(Debug, Default)]
 pub struct PermanentContractClassCache {
     storage: RwLock<HashMap<ClassHash, CompiledClass>>,
+    prepared_programs: RwLock<HashMap<ClassHash, Arc<PreparedProgram>>>,
 }
 
 impl PermanentContractClassCache {
@@ -46,7 +89,13 @@ impl PermanentContractClassCache {
     where
         I: IntoIterator<Item = (ClassHash, CompiledClass)>,
     {
-        self.storage.write().unwrap().extend(other);
+        let mut storage = self.storage.write().unwrap();
+        let mut prepared_programs = self.prepared_programs.write().unwrap();
+        for (class_hash, compiled_class) in other {
+            if storage.insert(class_hash, compiled_class).is_some() {
+                prepared_programs.remove(&class_hash);
+            }
+        }
     }
 }
 
@@ -60,6 +109,22 @@ impl ContractClassCache for PermanentContractClassCache {
             .write()
             .unwrap()
             .insert(class_hash, compiled_class);
+        self.prepared_programs.write().unwrap().remove(&class_hash);
+    }
+
+    fn get_prepared_program(&self, class_hash: ClassHash) -> Option<Arc<PreparedProgram>> {
+        self.prepared_programs
+            .read()
+            .unwrap()
+            .get(&class_hash)
+            .cloned()
+    }
+
+    fn set_prepared_program(&self, class_hash: ClassHash, prepared_program: Arc<PreparedProgram>) {
+        self.prepared_programs
+            .write()
+            .unwrap()
+            .insert(class_hash, prepared_program);
     }
 }
 
@@ -67,6 +132,7 @@ impl Clone for PermanentContractClassCache {
     fn clone(&self) -> Self {
         Self {
             storage: RwLock::new(self.storage.read().unwrap().clone()),
+            prepared_programs: RwLock::new(self.prepared_programs.read().unwrap().clone()),
         }
     }
 }
@@ -88,3 +154,376 @@ impl IntoIterator for &PermanentContractClassCache {
         self.storage.read().unwrap().clone().into_iter()
     }
 }
+
+/// Hit, miss and eviction counters of a contract class cache.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ContractClassCacheStats {
+    /// Number of lookups which found the requested class.
+    pub hits: u64,
+    /// Number of lookups which did not find the requested class.
+    pub misses: u64,
+    /// Number of classes removed from the cache to make room for others.
+    pub evictions: u64,
+}
+
+/// A contract class cache which keeps the most recently used contract classes, evicting the least
+/// recently used ones once the estimated memory usage (see [`estimate_compiled_class_size`])
+/// exceeds the configured limit. Optionally, the number of entries can be bounded too.
+///
+/// This cache is suited for long running applications, like RPC servers, since its memory usage
+/// is bounded.
+#[derive(Debug)]
+pub struct LruContractClassCache {
+    storage: Mutex<LruStorage>,
+
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+#[derive(Debug)]
+struct LruStorage {
+    entries: LruCache<ClassHash, LruEntry>,
+    size: usize,
+    max_size: usize,
+    max_entries: Option<NonZeroUsize>,
+}
+
+#[derive(Debug)]
+struct LruEntry {
+    compiled_class: CompiledClass,
+    prepared_program: Option<Arc<PreparedProgram>>,
+    size: usize,
+}
+
+impl LruContractClassCache {
+    /// Creates a new cache which will keep at most `max_size` bytes (estimated) of contract
+    /// classes.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            storage: Mutex::new(LruStorage {
+                entries: LruCache::unbounded(),
+                size: 0,
+                max_size,
+                max_entries: None,
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Creates a new cache which will keep at most `max_size` bytes (estimated) of contract
+    /// classes and at most `max_entries` contract classes.
+    pub fn with_max_entries(max_size: usize, max_entries: NonZeroUsize) -> Self {
+        let cache = Self::new(max_size);
+        cache.storage.lock().unwrap().max_entries = Some(max_entries);
+        cache
+    }
+
+    pub fn extend<I>(&self, other: I)
+    where
+        I: IntoIterator<Item = (ClassHash, CompiledClass)>,
+    {
+        let mut storage = self.storage.lock().unwrap();
+        let evictions: u64 = other
+            .into_iter()
+            .map(|(class_hash, compiled_class)| storage.insert(class_hash, compiled_class))
+            .sum();
+        self.evictions.fetch_add(evictions, Ordering::Relaxed);
+    }
+
+    /// Returns the number of contract classes currently stored.
+    pub fn len(&self) -> usize {
+        self.storage.lock().unwrap().entries.len()
+    }
+
+    /// Returns whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the estimated memory usage, in bytes, of the stored contract classes.
+    pub fn size(&self) -> usize {
+        self.storage.lock().unwrap().size
+    }
+
+    /// Returns the hit, miss and eviction counters since the cache was created.
+    pub fn stats(&self) -> ContractClassCacheStats {
+        ContractClassCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl LruStorage {
+    /// Inserts a contract class, evicting the least recently used ones if needed. Returns the
+    /// number of evicted contract classes.
+    fn insert(&mut self, class_hash: ClassHash, compiled_class: CompiledClass) -> u64 {
+        let entry_size = estimate_compiled_class_size(&compiled_class);
+
+        if let Some(old_entry) = self.entries.pop(&class_hash) {
+            self.size -= old_entry.size;
+        }
+
+        // Classes which wouldn't fit even in an empty cache are not stored.
+        if entry_size > self.max_size {
+            return 0;
+        }
+
+        let mut evictions = 0;
+        while self.size + entry_size > self.max_size
+            || self
+                .max_entries
+                .is_some_and(|max_entries| self.entries.len() >= max_entries.get())
+        {
+            match self.entries.pop_lru() {
+                Some((_, evicted_entry)) => {
+                    self.size -= evicted_entry.size;
+                    evictions += 1;
+                }
+                None => break,
+            }
+        }
+
+        self.entries.put(
+            class_hash,
+            LruEntry {
+                compiled_class,
+                prepared_program: None,
+                size: entry_size,
+            },
+        );
+        self.size += entry_size;
+
+        evictions
+    }
+}
+
+impl ContractClassCache for LruContractClassCache {
+    fn get_contract_class(&self, class_hash: ClassHash) -> Option<CompiledClass> {
+        let compiled_class = self
+            .storage
+            .lock()
+            .unwrap()
+            .entries
+            .get(&class_hash)
+            .map(|entry| entry.compiled_class.clone());
+
+        match compiled_class {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+
+        compiled_class
+    }
+
+    fn set_contract_class(&self, class_hash: ClassHash, compiled_class: CompiledClass) {
+        let evictions = self
+            .storage
+            .lock()
+            .unwrap()
+            .insert(class_hash, compiled_class);
+        self.evictions.fetch_add(evictions, Ordering::Relaxed);
+    }
+
+    fn get_prepared_program(&self, class_hash: ClassHash) -> Option<Arc<PreparedProgram>> {
+        self.storage
+            .lock()
+            .unwrap()
+            .entries
+            .peek(&class_hash)
+            .and_then(|entry| entry.prepared_program.clone())
+    }
+
+    /// Stores the prepared program along with its contract class, if present. Its memory usage is
+    /// accounted for, but it never causes other classes to be evicted: if it doesn't fit, it's
+    /// simply not stored.
+    fn set_prepared_program(&self, class_hash: ClassHash, prepared_program: Arc<PreparedProgram>) {
+        let mut storage = self.storage.lock().unwrap();
+        let (size, max_size) = (storage.size, storage.max_size);
+
+        if let Some(entry) = storage.entries.peek_mut(&class_hash) {
+            let old_size = entry
+                .prepared_program
+                .as_ref()
+                .map_or(0, |x| x.estimated_size());
+            let new_size = prepared_program.estimated_size();
+            if size - old_size + new_size > max_size {
+                return;
+            }
+
+            entry.prepared_program = Some(prepared_program);
+            entry.size = entry.size - old_size + new_size;
+            storage.size = size - old_size + new_size;
+        }
+    }
+}
+
+/// Estimates the memory usage, in bytes, of a compiled class.
+///
+/// The estimation only takes into account the biggest parts of each class (the program data, the
+/// hints and the entry points), so it should be used as an approximation only.
+pub fn estimate_compiled_class_size(compiled_class: &CompiledClass) -> usize {
+    // Felts and big integers are stored on the heap, using (at most) 32 bytes.
+    const BIG_INT_SIZE: usize = 32;
+
+    size_of::<CompiledClass>()
+        + match compiled_class {
+            CompiledClass::Deprecated(contract_class) => {
+                let program = contract_class.program();
+                let n_entry_points: usize = contract_class
+                    .entry_points_by_type()
+                    .values()
+                    .map(Vec::len)
+                    .sum();
+
+                size_of_val(contract_class.as_ref())
+                    + program.data_len() * (size_of::<MaybeRelocatable>() + BIG_INT_SIZE)
+                    + program.iter_builtins().count() * size_of::<usize>()
+                    + n_entry_points * (size_of::<ContractEntryPoint>() + BIG_INT_SIZE)
+            }
+            CompiledClass::Casm(casm_class) => {
+                let entry_points = casm_class
+                    .entry_points_by_type
+                    .external
+                    .iter()
+                    .chain(casm_class.entry_points_by_type.l1_handler.iter())
+                    .chain(casm_class.entry_points_by_type.constructor.iter());
+                let entry_points_size: usize = entry_points
+                    .map(|entry_point| {
+                        size_of_val(entry_point)
+                            + BIG_INT_SIZE
+                            + entry_point.builtins.iter().map(String::len).sum::<usize>()
+                    })
+                    .sum();
+                let hints_size: usize = casm_class
+                    .hints
+                    .iter()
+                    .map(|(_, hints)| {
+                        size_of::<(usize, Vec<Hint>)>() + size_of_val(hints.as_slice())
+                    })
+                    .sum();
+
+                size_of_val(casm_class.as_ref())
+                    + casm_class.bytecode.len() * (size_of::<BigUintAsHex>() + BIG_INT_SIZE)
+                    + hints_size
+                    + entry_points_size
+            }
+            CompiledClass::Sierra(sierra_class) => {
+                let (program, entry_points) = sierra_class.as_ref();
+
+                size_of_val(sierra_class.as_ref())
+                    + size_of_val(program.type_declarations.as_slice())
+                    + size_of_val(program.libfunc_declarations.as_slice())
+                    + size_of_val(program.statements.as_slice())
+                    + size_of_val(program.funcs.as_slice())
+                    + (entry_points.external.len()
+                        + entry_points.l1_handler.len()
+                        + entry_points.constructor.len())
+                        * (size_of::<SierraContractEntryPoint>() + BIG_INT_SIZE)
+            }
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::api::contract_classes::deprecated_contract_class::ContractClass;
+    use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+
+    fn fibonacci_class() -> CompiledClass {
+        CompiledClass::Deprecated(Arc::new(
+            ContractClass::from_path("starknet_programs/fibonacci.json").unwrap(),
+        ))
+    }
+
+    #[test]
+    fn lru_cache_evicts_by_size() {
+        let compiled_class = fibonacci_class();
+        let entry_size = estimate_compiled_class_size(&compiled_class);
+
+        // Room for two classes, but not for three.
+        let cache = LruContractClassCache::new(2 * entry_size + entry_size / 2);
+        cache.set_contract_class(ClassHash([1; 32]), compiled_class.clone());
+        cache.set_contract_class(ClassHash([2; 32]), compiled_class.clone());
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.size(), 2 * entry_size);
+
+        // Mark the first class as the most recently used one.
+        assert!(cache.get_contract_class(ClassHash([1; 32])).is_some());
+
+        cache.set_contract_class(ClassHash([3; 32]), compiled_class);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get_contract_class(ClassHash([1; 32])).is_some());
+        assert!(cache.get_contract_class(ClassHash([2; 32])).is_none());
+        assert!(cache.get_contract_class(ClassHash([3; 32])).is_some());
+
+        assert_eq!(
+            cache.stats(),
+            ContractClassCacheStats {
+                hits: 3,
+                misses: 1,
+                evictions: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn lru_cache_evicts_by_entry_count() {
+        let compiled_class = fibonacci_class();
+
+        let cache =
+            LruContractClassCache::with_max_entries(usize::MAX, NonZeroUsize::new(1).unwrap());
+        cache.extend([
+            (ClassHash([1; 32]), compiled_class.clone()),
+            (ClassHash([2; 32]), compiled_class),
+        ]);
+
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get_contract_class(ClassHash([2; 32])).is_some());
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn lru_cache_drops_prepared_programs_with_their_class() {
+        let casm_class: CasmContractClass = serde_json::from_slice(include_bytes!(
+            "../../starknet_programs/cairo2/fibonacci.casm"
+        ))
+        .unwrap();
+        let prepared_program = Arc::new(PreparedProgram::from_casm(&casm_class).unwrap());
+
+        let cache =
+            LruContractClassCache::with_max_entries(usize::MAX, NonZeroUsize::new(1).unwrap());
+
+        // Prepared programs are not stored without their class.
+        cache.set_prepared_program(ClassHash([1; 32]), prepared_program.clone());
+        assert!(cache.get_prepared_program(ClassHash([1; 32])).is_none());
+
+        cache.set_contract_class(
+            ClassHash([1; 32]),
+            CompiledClass::Casm(Arc::new(casm_class)),
+        );
+        let class_size = cache.size();
+        cache.set_prepared_program(ClassHash([1; 32]), prepared_program.clone());
+        assert!(cache.get_prepared_program(ClassHash([1; 32])).is_some());
+        assert_eq!(cache.size(), class_size + prepared_program.estimated_size());
+
+        cache.set_contract_class(ClassHash([2; 32]), fibonacci_class());
+        assert!(cache.get_prepared_program(ClassHash([1; 32])).is_none());
+    }
+
+    #[test]
+    fn lru_cache_skips_oversized_classes() {
+        let compiled_class = fibonacci_class();
+        let entry_size = estimate_compiled_class_size(&compiled_class);
+
+        let cache = LruContractClassCache::new(entry_size - 1);
+        cache.set_contract_class(ClassHash([1; 32]), compiled_class);
+
+        assert!(cache.is_empty());
+        assert_eq!(cache.size(), 0);
+    }
+}
@@ -0,0 +1,424 @@
+//! # Persistent contract class cache
+//!
+//! The `DiskContractClassCache` stores compiled classes in a local directory, one file per class
+//! hash, so that they survive process restarts and can be shared between processes using the same
+//! directory. Classes are only read from disk when they are first requested, and the most recently
+//! used ones are then kept in memory by an `LruContractClassCache`.
+//!
+//! Every file starts with the Keccak-256 digest of its contents, which is verified (along with the
+//! class hash stored within) before loading the class. Files failing the check are ignored and
+//! overwritten the next time the class is stored.
+//!
+//! Sierra programs (`CompiledClass::Sierra`) are only kept in memory since they are not
+//! serializable, so they're lost once evicted.
+
+use super::contract_class_cache::{ContractClassCache, LruContractClassCache};
+use crate::{
+    core::errors::state_errors::StateError,
+    execution::prepared_program::PreparedProgram,
+    services::api::contract_classes::{
+        compiled_class::CompiledClass,
+        deprecated_contract_class::{AbiType, ContractClass, ContractEntryPoint, EntryPointType},
+    },
+    utils::ClassHash,
+};
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+use cairo_vm::{felt::Felt252, types::program::Program};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+const DIGEST_LEN: usize = 32;
+
+/// Estimated memory usage, in bytes, of the classes kept in memory by default.
+pub const DEFAULT_DISK_CACHE_MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+/// A contract class cache which persists the compiled classes into a local directory.
+#[derive(Debug)]
+pub struct DiskContractClassCache {
+    path: PathBuf,
+    loaded: LruContractClassCache,
+}
+
+impl DiskContractClassCache {
+    /// Creates a new cache backed by the directory at `path`, creating it if it doesn't exist.
+    /// Classes already present in the directory are loaded lazily, and at most
+    /// [`DEFAULT_DISK_CACHE_MEMORY_LIMIT`] bytes (estimated) of them are kept in memory.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, StateError> {
+        Self::with_memory_limit(path, DEFAULT_DISK_CACHE_MEMORY_LIMIT)
+    }
+
+    /// Creates a new cache backed by the directory at `path`, which keeps at most `max_size`
+    /// bytes (estimated) of the loaded classes in memory.
+    pub fn with_memory_limit(
+        path: impl Into<PathBuf>,
+        max_size: usize,
+    ) -> Result<Self, StateError> {
+        let path = path.into();
+        fs::create_dir_all(&path)?;
+
+        Ok(Self {
+            path,
+            loaded: LruContractClassCache::new(max_size),
+        })
+    }
+
+    /// Returns the directory where the classes are stored.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn extend<I>(&self, other: I)
+    where
+        I: IntoIterator<Item = (ClassHash, CompiledClass)>,
+    {
+        for (class_hash, compiled_class) in other {
+            self.set_contract_class(class_hash, compiled_class);
+        }
+    }
+
+    fn class_path(&self, class_hash: ClassHash) -> PathBuf {
+        self.path
+            .join(format!("{}.json", hex::encode(class_hash.0)))
+    }
+
+    /// Reads a class from disk. Returns `Ok(None)` if the class has never been stored.
+    fn load(&self, class_hash: ClassHash) -> Result<Option<CompiledClass>, StateError> {
+        let data = match fs::read(self.class_path(class_hash)) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        if data.len() < DIGEST_LEN
+            || Keccak256::digest(&data[DIGEST_LEN..]).as_slice() != &data[..DIGEST_LEN]
+        {
+            return Err(StateError::CustomError(format!(
+                "Integrity check failed for stored class {class_hash}"
+            )));
+        }
+
+        let stored: StoredClass = serde_json::from_slice(&data[DIGEST_LEN..])
+            .map_err(|e| StateError::CustomError(e.to_string()))?;
+        if stored.class_hash != class_hash {
+            return Err(StateError::CustomError(format!(
+                "Stored class hash mismatch: expected {class_hash}, found {}",
+                stored.class_hash
+            )));
+        }
+
+        stored.class.try_into().map(Some)
+    }
+
+    /// Writes a class to disk. The file is written under a temporary name and then renamed so that
+    /// other processes never observe partially written files.
+    fn store(
+        &self,
+        class_hash: ClassHash,
+        compiled_class: &CompiledClass,
+    ) -> Result<(), StateError> {
+        let class = match StoredCompiledClass::from_compiled_class(compiled_class)? {
+            Some(class) => class,
+            None => return Ok(()),
+        };
+        let payload = serde_json::to_vec(&StoredClass { class_hash, class })
+            .map_err(|e| StateError::CustomError(e.to_string()))?;
+
+        let mut data = Keccak256::digest(&payload).to_vec();
+        data.extend(payload);
+
+        let class_path = self.class_path(class_hash);
+        let tmp_path = class_path.with_extension(format!("tmp.{}", std::process::id()));
+        fs::write(&tmp_path, data)?;
+        fs::rename(&tmp_path, &class_path)?;
+
+        Ok(())
+    }
+}
+
+impl ContractClassCache for DiskContractClassCache {
+    fn get_contract_class(&self, class_hash: ClassHash) -> Option<CompiledClass> {
+        if let Some(compiled_class) = self.loaded.get_contract_class(class_hash) {
+            return Some(compiled_class);
+        }
+
+        match self.load(class_hash) {
+            Ok(Some(compiled_class)) => {
+                self.loaded
+                    .set_contract_class(class_hash, compiled_class.clone());
+                Some(compiled_class)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Ignoring stored class {class_hash}: {e}");
+                None
+            }
+        }
+    }
+
+    fn set_contract_class(&self, class_hash: ClassHash, compiled_class: CompiledClass) {
+        if let Err(e) = self.store(class_hash, &compiled_class) {
+            tracing::warn!("Failed to store class {class_hash}: {e}");
+        }
+
+        // Replacing the class also drops its prepared program.
+        self.loaded.set_contract_class(class_hash, compiled_class);
+    }
+
+    /// Prepared programs are only kept in memory, along with their class.
+    fn get_prepared_program(&self, class_hash: ClassHash) -> Option<Arc<PreparedProgram>> {
+        self.loaded.get_prepared_program(class_hash)
+    }
+
+    fn set_prepared_program(&self, class_hash: ClassHash, prepared_program: Arc<PreparedProgram>) {
+        self.loaded
+            .set_prepared_program(class_hash, prepared_program);
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct StoredClass {
+    class_hash: ClassHash,
+    class: StoredCompiledClass,
+}
+
+#[derive(Deserialize, Serialize)]
+enum StoredCompiledClass {
+    Deprecated {
+        /// The program, as serialized by the Cairo VM.
+        program: String,
+        hinted_class_hash: String,
+        entry_points_by_type: Vec<(StoredEntryPointType, Vec<(String, usize)>)>,
+        abi: Option<AbiType>,
+    },
+    Casm(CasmContractClass),
+}
+
+#[derive(Deserialize, Serialize)]
+enum StoredEntryPointType {
+    External,
+    L1Handler,
+    Constructor,
+}
+
+impl StoredCompiledClass {
+    /// Converts a compiled class into its stored representation, or `None` if it can't be stored.
+    fn from_compiled_class(compiled_class: &CompiledClass) -> Result<Option<Self>, StateError> {
+        Ok(match compiled_class {
+            CompiledClass::Deprecated(contract_class) => {
+                let program = contract_class
+                    .program()
+                    .serialize()
+                    .map_err(|e| StateError::CustomError(e.to_string()))?;
+
+                Some(StoredCompiledClass::Deprecated {
+                    program: String::from_utf8(program)
+                        .map_err(|e| StateError::CustomError(e.to_string()))?,
+                    hinted_class_hash: contract_class.hinted_class_hash().to_str_radix(16),
+                    entry_points_by_type: contract_class
+                        .entry_points_by_type()
+                        .iter()
+                        .map(|(entry_point_type, entry_points)| {
+                            (
+                                (*entry_point_type).into(),
+                                entry_points
+                                    .iter()
+                                    .map(|x| (x.selector().to_str_radix(16), x.offset()))
+                                    .collect(),
+                            )
+                        })
+                        .collect(),
+                    abi: contract_class.abi().clone(),
+                })
+            }
+            CompiledClass::Casm(casm_class) => {
+                Some(StoredCompiledClass::Casm(casm_class.as_ref().clone()))
+            }
+            CompiledClass::Sierra(_) => None,
+        })
+    }
+}
+
+impl TryFrom<StoredCompiledClass> for CompiledClass {
+    type Error = StateError;
+
+    fn try_from(stored_class: StoredCompiledClass) -> Result<Self, Self::Error> {
+        let parse_felt = |value: &str| {
+            Felt252::parse_bytes(value.as_bytes(), 16)
+                .ok_or_else(|| StateError::CustomError(format!("Invalid felt: {value}")))
+        };
+
+        Ok(match stored_class {
+            StoredCompiledClass::Deprecated {
+                program,
+                hinted_class_hash,
+                entry_points_by_type,
+                abi,
+            } => {
+                let program = Program::deserialize(program.as_bytes(), None)
+                    .map_err(|e| StateError::CustomError(e.to_string()))?;
+                let entry_points_by_type = entry_points_by_type
+                    .into_iter()
+                    .map(|(entry_point_type, entry_points)| {
+                        Ok((
+                            entry_point_type.into(),
+                            entry_points
+                                .into_iter()
+                                .map(|(selector, offset)| {
+                                    Ok(ContractEntryPoint::new(parse_felt(&selector)?, offset))
+                                })
+                                .collect::<Result<_, StateError>>()?,
+                        ))
+                    })
+                    .collect::<Result<_, StateError>>()?;
+
+                CompiledClass::Deprecated(Arc::new(ContractClass::new_with_hinted_class_hash(
+                    parse_felt(&hinted_class_hash)?,
+                    program,
+                    entry_points_by_type,
+                    abi,
+                )?))
+            }
+            StoredCompiledClass::Casm(casm_class) => CompiledClass::Casm(Arc::new(casm_class)),
+        })
+    }
+}
+
+impl From<EntryPointType> for StoredEntryPointType {
+    fn from(value: EntryPointType) -> Self {
+        match value {
+            EntryPointType::External => Self::External,
+            EntryPointType::L1Handler => Self::L1Handler,
+            EntryPointType::Constructor => Self::Constructor,
+        }
+    }
+}
+
+impl From<StoredEntryPointType> for EntryPointType {
+    fn from(value: StoredEntryPointType) -> Self {
+        match value {
+            StoredEntryPointType::External => Self::External,
+            StoredEntryPointType::L1Handler => Self::L1Handler,
+            StoredEntryPointType::Constructor => Self::Constructor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::contract_class_cache::estimate_compiled_class_size;
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("starknet_in_rust_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        path
+    }
+
+    #[test]
+    fn disk_cache_persists_classes() {
+        let path = temp_cache_dir("disk_cache_persists_classes");
+
+        let deprecated_class = CompiledClass::Deprecated(Arc::new(
+            ContractClass::from_path("starknet_programs/fibonacci.json").unwrap(),
+        ));
+        let casm_class = CompiledClass::Casm(Arc::new(
+            serde_json::from_slice(include_bytes!(
+                "../../starknet_programs/cairo2/fibonacci.casm"
+            ))
+            .unwrap(),
+        ));
+
+        let cache = DiskContractClassCache::new(&path).unwrap();
+        cache.set_contract_class(ClassHash([1; 32]), deprecated_class.clone());
+        cache.set_contract_class(ClassHash([2; 32]), casm_class.clone());
+
+        // A new cache (for example, from another process) sees the stored classes.
+        let cache = DiskContractClassCache::new(&path).unwrap();
+        let (expected, loaded) = match (
+            deprecated_class,
+            cache.get_contract_class(ClassHash([1; 32])),
+        ) {
+            (CompiledClass::Deprecated(expected), Some(CompiledClass::Deprecated(loaded))) => {
+                (expected, loaded)
+            }
+            _ => panic!("expected a deprecated class"),
+        };
+        assert_eq!(loaded.hinted_class_hash(), expected.hinted_class_hash());
+        assert_eq!(
+            loaded.entry_points_by_type(),
+            expected.entry_points_by_type()
+        );
+        assert_eq!(loaded.abi(), expected.abi());
+        assert_eq!(loaded.program().data_len(), expected.program().data_len());
+
+        assert_eq!(
+            cache.get_contract_class(ClassHash([2; 32])),
+            Some(casm_class)
+        );
+        assert_eq!(cache.get_contract_class(ClassHash([3; 32])), None);
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn disk_cache_ignores_corrupted_classes() {
+        let path = temp_cache_dir("disk_cache_ignores_corrupted_classes");
+
+        let casm_class = CompiledClass::Casm(Arc::new(
+            serde_json::from_slice(include_bytes!(
+                "../../starknet_programs/cairo2/fibonacci.casm"
+            ))
+            .unwrap(),
+        ));
+
+        let cache = DiskContractClassCache::new(&path).unwrap();
+        cache.set_contract_class(ClassHash([1; 32]), casm_class);
+
+        let class_path = cache.class_path(ClassHash([1; 32]));
+        let mut data = fs::read(&class_path).unwrap();
+        *data.last_mut().unwrap() ^= 0xFF;
+        fs::write(&class_path, data).unwrap();
+
+        let cache = DiskContractClassCache::new(&path).unwrap();
+        assert_eq!(cache.get_contract_class(ClassHash([1; 32])), None);
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn disk_cache_bounds_the_loaded_classes() {
+        let path = temp_cache_dir("disk_cache_bounds_the_loaded_classes");
+
+        let casm_class = CompiledClass::Casm(Arc::new(
+            serde_json::from_slice(include_bytes!(
+                "../../starknet_programs/cairo2/fibonacci.casm"
+            ))
+            .unwrap(),
+        ));
+        let class_size = estimate_compiled_class_size(&casm_class);
+
+        // Only one class fits in memory, but both are still served from disk.
+        let cache = DiskContractClassCache::with_memory_limit(&path, class_size).unwrap();
+        cache.set_contract_class(ClassHash([1; 32]), casm_class.clone());
+        cache.set_contract_class(ClassHash([2; 32]), casm_class.clone());
+        assert_eq!(cache.loaded.len(), 1);
+
+        assert_eq!(
+            cache.get_contract_class(ClassHash([1; 32])),
+            Some(casm_class.clone())
+        );
+        assert_eq!(
+            cache.get_contract_class(ClassHash([2; 32])),
+            Some(casm_class)
+        );
+        assert_eq!(cache.loaded.len(), 1);
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -4,6 +4,7 @@ use self::{
 };
 use crate::{
     core::errors::state_errors::StateError,
+    metrics,
     transaction::error::TransactionError,
     utils::{
         get_keys, to_cache_state_storage_mapping, to_state_diff_storage_mapping, Address,
@@ -17,6 +18,7 @@ use std::{collections::HashMap, sync::Arc};
 pub mod cached_state;
 pub mod contract_class_cache;
 pub(crate) mod contract_storage_state;
+pub mod disk_contract_class_cache;
 pub mod in_memory_state_reader;
 pub mod state_api;
 pub mod state_cache;
@@ -29,6 +31,12 @@ pub struct BlockInfo {
     pub block_timestamp: u64,
     /// L1 gas price (in Wei) measured at the beginning of the last block creation attempt.
     pub gas_price: u128,
+    /// L1 data (blob) gas price (in Wei) measured at the beginning of the last block creation
+    /// attempt.
+    pub l1_data_gas_price: u128,
+    /// Whether the block posts its state diff as blobs, which is charged as L1 data gas, instead
+    /// of as calldata.
+    pub use_kzg_da: bool,
     /// The sequencer address of this block.
     pub sequencer_address: Address,
 }
@@ -39,6 +47,8 @@ impl BlockInfo {
             block_number: 0, // To do: In cairo-lang, this value is set to -1
             block_timestamp: 0,
             gas_price: 0,
+            l1_data_gas_price: 0,
+            use_kzg_da: false,
             sequencer_address,
         }
     }
@@ -65,6 +75,8 @@ impl Default for BlockInfo {
             block_number: 0,
             block_timestamp: 0,
             gas_price: 0,
+            l1_data_gas_price: 0,
+            use_kzg_da: false,
             sequencer_address: Address(0.into()),
         }
     }
@@ -89,6 +101,7 @@ impl ExecutionResourcesManager {
     }
 
     pub fn increment_syscall_counter(&mut self, syscall_name: &str, amount: u64) {
+        metrics::record_syscall(syscall_name, amount);
         *self
             .syscall_counter
             .entry(syscall_name.to_string())
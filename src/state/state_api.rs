@@ -51,7 +51,7 @@ pub trait StateReader {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub struct StateChangesCount {
     pub n_storage_updates: usize,
     pub n_class_hash_updates: usize,
@@ -3,15 +3,20 @@
 use std::collections::HashMap;
 use std::ops::Add;
 
+use super::custom_syscalls::{CustomSyscallContext, CustomSyscallResponse};
+use super::secp::{self, SecpPointStore, INVALID_ARGUMENT};
 use super::syscall_handler_errors::SyscallHandlerError;
 use super::syscall_request::{
-    EmitEventRequest, FromPtr, GetBlockHashRequest, GetBlockTimestampRequest, KeccakRequest,
-    StorageReadRequest, StorageWriteRequest,
+    CustomSyscallRequest, EmitEventRequest, FromPtr, GetBlockHashRequest, GetBlockTimestampRequest,
+    KeccakRequest, Secp256AddRequest, Secp256GetPointFromXRequest, Secp256GetXyRequest,
+    Secp256MulRequest, Secp256NewRequest, Sha256ProcessBlockRequest, StorageReadRequest,
+    StorageWriteRequest,
 };
 use super::syscall_response::{
     DeployResponse, GetBlockHashResponse, GetBlockTimestampResponse, KeccakResponse,
-    SyscallResponse,
+    Secp256GetXyResponse, SyscallResponse,
 };
+use super::syscall_trace::{request as trace_request, SyscallOutcome, SyscallTracer};
 use super::{
     syscall_info::get_syscall_size_from_name,
     syscall_request::{
@@ -42,8 +47,13 @@ use crate::{
         contract_storage_state::ContractStorageState,
         state_api::{State, StateReader},
     },
-    utils::{felt_to_hash, get_big_int, get_felt_range, Address, ClassHash},
+    utils::{
+        felt_to_hash, get_big_int, get_felt_range, sha256_compress, Address, ClassHash,
+        SHA256_BLOCK_SIZE, SHA256_STATE_SIZE,
+    },
 };
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::PrimeField;
 use cairo_vm::felt::Felt252;
 use cairo_vm::{
     types::{
@@ -64,10 +74,6 @@ use {
     std::{cell::RefCell, rc::Rc},
 };
 
-pub(crate) const STEP: u128 = 100;
-pub(crate) const SYSCALL_BASE: u128 = 100 * STEP;
-pub(crate) const KECCAK_ROUND_COST: u128 = 180000;
-
 lazy_static! {
     /// Felt->syscall map that was extracted from new_syscalls.json (Cairo 1.0 syscalls)
     static ref SELECTOR_TO_SYSCALL: HashMap<Felt252, &'static str> = {
@@ -92,39 +98,20 @@ lazy_static! {
             map.insert(Felt252::from_bytes_be(&calculate_sn_keccak("get_block_timestamp".as_bytes())), "get_block_timestamp");
             map.insert(Felt252::from_bytes_be(&calculate_sn_keccak("get_block_number".as_bytes())), "get_block_number");
             map.insert(Felt252::from_bytes_be("Keccak".as_bytes()), "keccak");
+            map.insert(Felt252::from_bytes_be("Sha256ProcessBlock".as_bytes()), "sha256_process_block");
+            map.insert(Felt252::from_bytes_be("Secp256k1Add".as_bytes()), "secp256k1_add");
+            map.insert(Felt252::from_bytes_be("Secp256k1GetPointFromX".as_bytes()), "secp256k1_get_point_from_x");
+            map.insert(Felt252::from_bytes_be("Secp256k1GetXy".as_bytes()), "secp256k1_get_xy");
+            map.insert(Felt252::from_bytes_be("Secp256k1Mul".as_bytes()), "secp256k1_mul");
+            map.insert(Felt252::from_bytes_be("Secp256k1New".as_bytes()), "secp256k1_new");
+            map.insert(Felt252::from_bytes_be("Secp256r1Add".as_bytes()), "secp256r1_add");
+            map.insert(Felt252::from_bytes_be("Secp256r1GetPointFromX".as_bytes()), "secp256r1_get_point_from_x");
+            map.insert(Felt252::from_bytes_be("Secp256r1GetXy".as_bytes()), "secp256r1_get_xy");
+            map.insert(Felt252::from_bytes_be("Secp256r1Mul".as_bytes()), "secp256r1_mul");
+            map.insert(Felt252::from_bytes_be("Secp256r1New".as_bytes()), "secp256r1_new");
 
             map
     };
-
-    // TODO: There is no reason why this could not be in the syscall enum itself AFAICT
-    // Taken from starkware/starknet/constants.py in cairo-lang
-    // See further documentation on cairo_programs/constants.cairo
-    /// Maps syscall name to gas costs
-    pub(crate) static ref SYSCALL_GAS_COST: HashMap<&'static str, u128> = {
-        let mut map = HashMap::new();
-
-        map.insert("initial", 100_000_000 * STEP);
-        map.insert("entry_point_initial_budget", 100 * STEP);
-
-        map.insert("entry_point", map["entry_point_initial_budget"] + 500 * STEP);
-        map.insert("fee_transfer", map["entry_point"] + 100 * STEP);
-        map.insert("transaction", 2 * map["entry_point"] + map["fee_transfer"] + 100 * STEP);
-
-        map.insert("call_contract", SYSCALL_BASE + 10 * STEP + map["entry_point"]);
-        map.insert("deploy", SYSCALL_BASE + 200 * STEP + map["entry_point"]);
-        map.insert("get_execution_info", SYSCALL_BASE + 10 * STEP);
-        map.insert("library_call", map["call_contract"]);
-        map.insert("replace_class", SYSCALL_BASE + 50 * STEP);
-        map.insert("storage_read", SYSCALL_BASE + 50 * STEP);
-        map.insert("storage_write", SYSCALL_BASE + 50 * STEP);
-        map.insert("emit_event", SYSCALL_BASE + 10 * STEP);
-        map.insert("send_message_to_l1", SYSCALL_BASE + 50 * STEP);
-        map.insert("get_block_timestamp", 0);
-        map.insert("keccak", 0);
-        map.insert("get_block_hash", SYSCALL_BASE + 50 * STEP);
-
-        map
-    };
 }
 
 #[derive(Debug)]
@@ -144,6 +131,11 @@ pub struct BusinessLogicSyscallHandler<'a, S: StateReader, C: ContractClassCache
     pub(crate) entry_point_selector: Felt252,
     pub(crate) selector_to_syscall: &'a HashMap<Felt252, &'static str>,
     pub(crate) execution_info_ptr: Option<Relocatable>,
+    pub(crate) secp256k1_points: SecpPointStore<ark_secp256k1::Config>,
+    pub(crate) secp256r1_points: SecpPointStore<ark_secp256r1::Config>,
+    /// End of the segment holding the blocks processed by `sha256_process_block`, if any.
+    pub(crate) sha256_segment_end_ptr: Option<Relocatable>,
+    pub(crate) syscall_tracer: SyscallTracer,
 }
 
 // TODO: execution entry point may no be a parameter field, but there is no way to generate a default for now
@@ -166,6 +158,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         let l2_to_l1_messages = Vec::new();
         let starknet_storage_state = ContractStorageState::new(state, contract_address.clone());
         let internal_calls = Vec::new();
+        let syscall_tracer = SyscallTracer::new(block_context.trace_syscalls);
 
         BusinessLogicSyscallHandler {
             tx_execution_context,
@@ -183,6 +176,10 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             entry_point_selector,
             selector_to_syscall: &SELECTOR_TO_SYSCALL,
             execution_info_ptr: None,
+            secp256k1_points: Default::default(),
+            secp256r1_points: Default::default(),
+            sha256_segment_end_ptr: None,
+            syscall_tracer,
         }
     }
 
@@ -241,6 +238,10 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             entry_point_selector,
             selector_to_syscall: &SELECTOR_TO_SYSCALL,
             execution_info_ptr: None,
+            secp256k1_points: Default::default(),
+            secp256r1_points: Default::default(),
+            sha256_segment_end_ptr: None,
+            syscall_tracer: Default::default(),
         }
     }
 
@@ -259,22 +260,32 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             Rc<RefCell<ProgramCache<'_, ClassHash>>>,
         >,
     ) -> Result<SyscallResponse, SyscallHandlerError> {
+        let mocked_call_info = self
+            .block_context
+            .cheatcodes
+            .mocked_call_info(&execution_entry_point);
         let ExecutionResult {
             call_info,
             revert_error,
             ..
-        } = execution_entry_point
-            .execute(
-                self.starknet_storage_state.state,
-                &self.block_context,
-                &mut self.resources_manager,
-                &mut self.tx_execution_context,
-                false,
-                self.block_context.invoke_tx_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            )
-            .map_err(|err| SyscallHandlerError::ExecutionError(err.to_string()))?;
+        } = match mocked_call_info {
+            Some(call_info) => ExecutionResult {
+                call_info: Some(call_info),
+                ..Default::default()
+            },
+            None => execution_entry_point
+                .execute(
+                    self.starknet_storage_state.state,
+                    &self.block_context,
+                    &mut self.resources_manager,
+                    &mut self.tx_execution_context,
+                    false,
+                    self.block_context.invoke_tx_max_n_steps(),
+                    #[cfg(feature = "cairo-native")]
+                    program_cache,
+                )
+                .map_err(|err| SyscallHandlerError::ExecutionError(err.to_string()))?,
+        };
 
         let call_info = call_info.ok_or(SyscallHandlerError::ExecutionError(
             revert_error.unwrap_or_else(|| "Execution error".to_string()),
@@ -396,7 +407,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
                 &mut self.resources_manager,
                 &mut self.tx_execution_context,
                 self.support_reverted,
-                self.block_context.invoke_tx_max_n_steps,
+                self.block_context.invoke_tx_max_n_steps(),
                 #[cfg(feature = "cairo-native")]
                 program_cache,
             )
@@ -424,9 +435,23 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         >,
     ) -> Result<(), SyscallHandlerError> {
         let selector = get_big_int(vm, syscall_ptr)?;
-        let syscall_name = self.selector_to_syscall.get(&selector).ok_or(
-            SyscallHandlerError::SelectorNotInHandlerMap(selector.to_string()),
-        )?;
+        // Built-in syscalls take precedence over the ones registered by the embedder.
+        let custom_syscall = match self.selector_to_syscall.get(&selector) {
+            Some(_) => None,
+            None => Some(
+                self.block_context
+                    .custom_syscalls
+                    .get(&selector)
+                    .cloned()
+                    .ok_or(SyscallHandlerError::SelectorNotInHandlerMap(
+                        selector.to_string(),
+                    ))?,
+            ),
+        };
+        let syscall_name = match &custom_syscall {
+            Some(custom_syscall) => custom_syscall.name(),
+            None => self.selector_to_syscall[&selector],
+        };
 
         let initial_gas: Felt252 = get_big_int(vm, (syscall_ptr + 1)?)?;
         let initial_gas = initial_gas
@@ -437,15 +462,35 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         let mut syscall_ptr: Relocatable =
             (syscall_ptr + 2_usize).map_err(SyscallHandlerError::from)?;
 
-        let request = self.read_and_validate_syscall_request(vm, &mut syscall_ptr, syscall_name)?;
+        let request = match &custom_syscall {
+            Some(custom_syscall) => {
+                let values = custom_syscall.decode(vm, syscall_ptr)?;
+                syscall_ptr += custom_syscall.request_size();
+                SyscallRequest::Custom(CustomSyscallRequest {
+                    selector: selector.clone(),
+                    values,
+                })
+            }
+            None => self.read_and_validate_syscall_request(vm, &mut syscall_ptr, syscall_name)?,
+        };
+        self.syscall_tracer.begin(
+            syscall_name,
+            || trace_request(vm, &request).unwrap_or_else(|_| format!("{request:?}")),
+            Some(initial_gas),
+            self.internal_calls.len(),
+        );
 
         // Check and reduce gas (after validating the syscall selector for consistency wth the OS).
-        let required_gas = SYSCALL_GAS_COST
-            .get(syscall_name)
-            .map(|&x| x.saturating_sub(SYSCALL_BASE))
-            .ok_or(SyscallHandlerError::SelectorDoesNotHaveAssociatedGas(
-                selector.to_string(),
-            ))?;
+        let required_gas = match &custom_syscall {
+            Some(custom_syscall) => custom_syscall.gas_cost(),
+            None => self
+                .block_context
+                .versioned_constants()
+                .required_syscall_gas(syscall_name)
+                .ok_or(SyscallHandlerError::SelectorDoesNotHaveAssociatedGas(
+                    selector.to_string(),
+                ))?,
+        };
 
         let response = if initial_gas < required_gas {
             let out_of_gas_felt = Felt252::from_bytes_be("Out of gas".as_bytes());
@@ -463,15 +508,37 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         } else {
             // Execute with remaining gas.
             let remaining_gas = initial_gas - required_gas;
-            self.execute_syscall(
+            match self.execute_syscall(
                 request,
                 remaining_gas,
                 vm,
                 #[cfg(feature = "cairo-native")]
                 program_cache,
-            )?
+            ) {
+                Ok(response) => response,
+                Err(err) => {
+                    self.syscall_tracer.end(
+                        || SyscallOutcome::Error(err.to_string()),
+                        None,
+                        self.internal_calls.len(),
+                    );
+                    return Err(err);
+                }
+            }
         };
 
+        self.syscall_tracer.end(
+            || match &response.body {
+                Some(ResponseBody::Failure(reason)) => SyscallOutcome::Failure(
+                    get_felt_range(vm, reason.retdata_start, reason.retdata_end)
+                        .unwrap_or_default(),
+                ),
+                body => SyscallOutcome::Success(format!("{body:?}")),
+            },
+            Some(response.gas),
+            self.internal_calls.len(),
+        );
+
         // Write response to the syscall segment.
         self.expected_syscall_ptr = vm
             .write_arg(syscall_ptr, &response.to_cairo_compatible_args())?
@@ -524,9 +591,103 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             SyscallRequest::GetBlockHash(req) => self.get_block_hash(vm, req, remaining_gas),
             SyscallRequest::ReplaceClass(req) => self.replace_class(vm, req, remaining_gas),
             SyscallRequest::Keccak(req) => self.keccak(vm, req, remaining_gas),
+            SyscallRequest::Sha256ProcessBlock(req) => {
+                self.sha256_process_block(vm, req, remaining_gas)
+            }
+            SyscallRequest::Secp256k1New(req) => {
+                self.secp256_new(vm, req, remaining_gas, |handler| {
+                    &mut handler.secp256k1_points
+                })
+            }
+            SyscallRequest::Secp256k1Add(req) => {
+                self.secp256_add(vm, req, remaining_gas, |handler| {
+                    &mut handler.secp256k1_points
+                })
+            }
+            SyscallRequest::Secp256k1Mul(req) => {
+                self.secp256_mul(vm, req, remaining_gas, |handler| {
+                    &mut handler.secp256k1_points
+                })
+            }
+            SyscallRequest::Secp256k1GetPointFromX(req) => {
+                self.secp256_get_point_from_x(vm, req, remaining_gas, |handler| {
+                    &mut handler.secp256k1_points
+                })
+            }
+            SyscallRequest::Secp256k1GetXy(req) => {
+                self.secp256_get_xy(req, remaining_gas, |handler| &mut handler.secp256k1_points)
+            }
+            SyscallRequest::Secp256r1New(req) => {
+                self.secp256_new(vm, req, remaining_gas, |handler| {
+                    &mut handler.secp256r1_points
+                })
+            }
+            SyscallRequest::Secp256r1Add(req) => {
+                self.secp256_add(vm, req, remaining_gas, |handler| {
+                    &mut handler.secp256r1_points
+                })
+            }
+            SyscallRequest::Secp256r1Mul(req) => {
+                self.secp256_mul(vm, req, remaining_gas, |handler| {
+                    &mut handler.secp256r1_points
+                })
+            }
+            SyscallRequest::Secp256r1GetPointFromX(req) => {
+                self.secp256_get_point_from_x(vm, req, remaining_gas, |handler| {
+                    &mut handler.secp256r1_points
+                })
+            }
+            SyscallRequest::Secp256r1GetXy(req) => {
+                self.secp256_get_xy(req, remaining_gas, |handler| &mut handler.secp256r1_points)
+            }
+            SyscallRequest::Custom(req) => self.custom_syscall(vm, req, remaining_gas),
         }
     }
 
+    fn custom_syscall(
+        &mut self,
+        vm: &mut VirtualMachine,
+        request: CustomSyscallRequest,
+        remaining_gas: u128,
+    ) -> Result<SyscallResponse, SyscallHandlerError> {
+        let custom_syscall = self
+            .block_context
+            .custom_syscalls
+            .get(&request.selector)
+            .cloned()
+            .ok_or(SyscallHandlerError::SelectorNotInHandlerMap(
+                request.selector.to_string(),
+            ))?;
+
+        let mut context = CustomSyscallContext {
+            state: &mut *self.starknet_storage_state.state,
+            vm: &mut *vm,
+            block_context: &self.block_context,
+            contract_address: &self.contract_address,
+            caller_address: &self.caller_address,
+        };
+        let body = match custom_syscall.execute(&mut context, request.values)? {
+            CustomSyscallResponse::Success(values) => ResponseBody::Custom(values),
+            CustomSyscallResponse::Failure(reason) => {
+                let reason_len = reason.len();
+                let retdata_start = self.allocate_segment(
+                    vm,
+                    reason.into_iter().map(MaybeRelocatable::from).collect(),
+                )?;
+                let retdata_end = (retdata_start + reason_len)?;
+                ResponseBody::Failure(FailureReason {
+                    retdata_start,
+                    retdata_end,
+                })
+            }
+        };
+
+        Ok(SyscallResponse {
+            gas: remaining_gas,
+            body: Some(body),
+        })
+    }
+
     fn get_block_hash(
         &mut self,
         vm: &mut VirtualMachine,
@@ -620,6 +781,10 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         let order = self.tx_execution_context.n_emitted_events;
         let keys: Vec<Felt252> = get_felt_range(vm, request.keys_start, request.keys_end)?;
         let data: Vec<Felt252> = get_felt_range(vm, request.data_start, request.data_end)?;
+        self.block_context
+            .versioned_constants
+            .event_limits
+            .check(keys.len(), data.len(), order)?;
         self.events.push(OrderedEvent::new(order, keys, data));
 
         // Update events count.
@@ -638,7 +803,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         Ok(SyscallResponse {
             gas: remaining_gas,
             body: Some(ResponseBody::GetBlockNumber {
-                number: self.block_context.block_info.block_number.into(),
+                number: self.block_info().block_number.into(),
             }),
         })
     }
@@ -684,6 +849,13 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         })
     }
 
+    /// Returns the block info seen by the contract, which may be overridden by cheatcodes.
+    fn block_info(&self) -> BlockInfo {
+        self.block_context
+            .cheatcodes
+            .block_info(&self.contract_address, &self.block_context.block_info)
+    }
+
     // Returns the pointer to the segment with the execution info if it was already written.
     // If it wasn't, it writes the execution info into memory and returns its start address.
     fn get_or_allocate_execution_info(
@@ -695,7 +867,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         }
 
         // Allocate block_info
-        let block_info = &self.block_context.block_info;
+        let block_info = self.block_info();
         let block_info_data = vec![
             MaybeRelocatable::from(Felt252::from(block_info.block_number)),
             MaybeRelocatable::from(Felt252::from(block_info.block_timestamp)),
@@ -703,18 +875,54 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         ];
         let block_info_ptr = self.allocate_segment(vm, block_info_data)?;
 
+        let (tx_info, chain_id) = self.block_context.cheatcodes.tx_info(
+            &self.contract_address,
+            &self.tx_execution_context,
+            &self.block_context.starknet_os_config.chain_id,
+        );
+
         // Allocate signature
-        let signature: Vec<MaybeRelocatable> = self
-            .tx_execution_context
+        let signature: Vec<MaybeRelocatable> = tx_info
             .signature
             .iter()
             .map(MaybeRelocatable::from)
             .collect();
         let signature_start_ptr = self.allocate_segment(vm, signature)?;
-        let signature_end_ptr = (signature_start_ptr + self.tx_execution_context.signature.len())?;
+        let signature_end_ptr = (signature_start_ptr + tx_info.signature.len())?;
 
-        // Allocate tx info
-        let tx_info = &self.tx_execution_context;
+        // Allocate tx info. Contracts compiled with older corelibs only read the legacy fields at
+        // the start, so the extended layout is written for every contract, as the OS does.
+        let extended_tx_info = &tx_info.extended_tx_info;
+        let resource_bounds: Vec<MaybeRelocatable> = extended_tx_info
+            .resource_bounds
+            .iter()
+            .flat_map(|bounds| {
+                [
+                    bounds.resource.to_felt(),
+                    Felt252::from(bounds.max_amount),
+                    Felt252::from(bounds.max_price_per_unit),
+                ]
+            })
+            .map(MaybeRelocatable::from)
+            .collect();
+        let (resource_bounds_start, resource_bounds_end) =
+            self.allocate_span(vm, resource_bounds)?;
+        let (paymaster_data_start, paymaster_data_end) = self.allocate_span(
+            vm,
+            extended_tx_info
+                .paymaster_data
+                .iter()
+                .map(MaybeRelocatable::from)
+                .collect(),
+        )?;
+        let (account_deployment_data_start, account_deployment_data_end) = self.allocate_span(
+            vm,
+            extended_tx_info
+                .account_deployment_data
+                .iter()
+                .map(MaybeRelocatable::from)
+                .collect(),
+        )?;
         let tx_info_data = vec![
             MaybeRelocatable::from(&tx_info.version),
             MaybeRelocatable::from(&tx_info.account_contract_address.0),
@@ -722,16 +930,29 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             signature_start_ptr.into(),
             signature_end_ptr.into(),
             MaybeRelocatable::from(&tx_info.transaction_hash),
-            MaybeRelocatable::from(&self.block_context.starknet_os_config.chain_id),
+            MaybeRelocatable::from(&chain_id),
             MaybeRelocatable::from(&tx_info.nonce),
+            resource_bounds_start.into(),
+            resource_bounds_end.into(),
+            MaybeRelocatable::from(Felt252::from(extended_tx_info.tip)),
+            paymaster_data_start.into(),
+            paymaster_data_end.into(),
+            MaybeRelocatable::from(Felt252::from(extended_tx_info.nonce_data_availability_mode)),
+            MaybeRelocatable::from(Felt252::from(extended_tx_info.fee_data_availability_mode)),
+            account_deployment_data_start.into(),
+            account_deployment_data_end.into(),
         ];
         let tx_info_ptr = self.allocate_segment(vm, tx_info_data)?;
 
         // Allocate execution_info
+        let caller_address = self
+            .block_context
+            .cheatcodes
+            .caller_address(&self.contract_address, &self.caller_address);
         let execution_info = vec![
             block_info_ptr.into(),
             tx_info_ptr.into(),
-            MaybeRelocatable::from(&self.caller_address.0),
+            MaybeRelocatable::from(&caller_address.0),
             MaybeRelocatable::from(&self.contract_address.0),
             MaybeRelocatable::from(&self.entry_point_selector),
         ];
@@ -942,6 +1163,35 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             "send_message_to_l1" => SendMessageToL1Request::from_ptr(vm, syscall_ptr),
             "replace_class" => ReplaceClassRequest::from_ptr(vm, syscall_ptr),
             "keccak" => KeccakRequest::from_ptr(vm, syscall_ptr),
+            "sha256_process_block" => Sha256ProcessBlockRequest::from_ptr(vm, syscall_ptr),
+            "secp256k1_new" => {
+                Secp256NewRequest::from_ptr(vm, syscall_ptr).map(SyscallRequest::Secp256k1New)
+            }
+            "secp256k1_add" => {
+                Secp256AddRequest::from_ptr(vm, syscall_ptr).map(SyscallRequest::Secp256k1Add)
+            }
+            "secp256k1_mul" => {
+                Secp256MulRequest::from_ptr(vm, syscall_ptr).map(SyscallRequest::Secp256k1Mul)
+            }
+            "secp256k1_get_point_from_x" => Secp256GetPointFromXRequest::from_ptr(vm, syscall_ptr)
+                .map(SyscallRequest::Secp256k1GetPointFromX),
+            "secp256k1_get_xy" => {
+                Secp256GetXyRequest::from_ptr(vm, syscall_ptr).map(SyscallRequest::Secp256k1GetXy)
+            }
+            "secp256r1_new" => {
+                Secp256NewRequest::from_ptr(vm, syscall_ptr).map(SyscallRequest::Secp256r1New)
+            }
+            "secp256r1_add" => {
+                Secp256AddRequest::from_ptr(vm, syscall_ptr).map(SyscallRequest::Secp256r1Add)
+            }
+            "secp256r1_mul" => {
+                Secp256MulRequest::from_ptr(vm, syscall_ptr).map(SyscallRequest::Secp256r1Mul)
+            }
+            "secp256r1_get_point_from_x" => Secp256GetPointFromXRequest::from_ptr(vm, syscall_ptr)
+                .map(SyscallRequest::Secp256r1GetPointFromX),
+            "secp256r1_get_xy" => {
+                Secp256GetXyRequest::from_ptr(vm, syscall_ptr).map(SyscallRequest::Secp256r1GetXy)
+            }
             _ => Err(SyscallHandlerError::UnknownSyscall(
                 syscall_name.to_string(),
             )),
@@ -962,6 +1212,17 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         Ok(segment_start)
     }
 
+    /// Allocates a segment with the given data, returning its start and end.
+    fn allocate_span(
+        &mut self,
+        vm: &mut VirtualMachine,
+        data: Vec<MaybeRelocatable>,
+    ) -> Result<(Relocatable, Relocatable), SyscallHandlerError> {
+        let len = data.len();
+        let start = self.allocate_segment(vm, data)?;
+        Ok((start, (start + len)?))
+    }
+
     fn send_message_to_l1(
         &mut self,
         vm: &VirtualMachine,
@@ -1037,7 +1298,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         Ok(SyscallResponse {
             gas: remaining_gas,
             body: Some(ResponseBody::GetBlockTimestamp(GetBlockTimestampResponse {
-                timestamp: self.block_context.block_info.block_timestamp.into(),
+                timestamp: self.block_info().block_timestamp.into(),
             })),
         })
     }
@@ -1066,6 +1327,10 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
     ) -> Result<SyscallResponse, SyscallHandlerError> {
         let length = (request.input_end - request.input_start)?;
         let mut gas = remaining_gas;
+        let keccak_round_cost = self
+            .block_context
+            .versioned_constants()
+            .keccak_round_cost_gas_cost();
 
         if length % 17 != 0 {
             let response = self.failure_from_error_msg(vm, b"Invalid keccak input size")?;
@@ -1078,14 +1343,14 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         let mut state = [0u64; 25];
         for i in 0..n_chunks {
             // TODO: check this before the loop, taking care to preserve functionality.
-            if gas < KECCAK_ROUND_COST {
+            if gas < keccak_round_cost {
                 let response = self.failure_from_error_msg(vm, b"Syscall out of gas")?;
                 return Ok(SyscallResponse {
                     gas,
                     body: Some(response),
                 });
             }
-            gas -= KECCAK_ROUND_COST;
+            gas -= keccak_round_cost;
             let chunk_start = (request.input_start + i * 17)?;
             let chunk = get_felt_range(vm, chunk_start, (chunk_start + 17)?)?;
             for (i, val) in chunk.iter().enumerate() {
@@ -1106,6 +1371,160 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         })
     }
 
+    /// The pinned Cairo Native version doesn't dispatch this syscall yet: native contracts reach
+    /// it only through `NativeSyscallHandler::sha256_process_block`.
+    fn sha256_process_block(
+        &mut self,
+        vm: &mut VirtualMachine,
+        request: Sha256ProcessBlockRequest,
+        remaining_gas: u128,
+    ) -> Result<SyscallResponse, SyscallHandlerError> {
+        let block = get_felt_range(
+            vm,
+            request.input_start,
+            (request.input_start + SHA256_BLOCK_SIZE)?,
+        )?;
+        let prev_state = get_felt_range(
+            vm,
+            request.state_ptr,
+            (request.state_ptr + SHA256_STATE_SIZE)?,
+        )?;
+
+        let to_u32 = |felt: &Felt252| {
+            felt.to_u32().ok_or_else(|| {
+                SyscallHandlerError::Conversion("Felt252".to_string(), "u32".to_string())
+            })
+        };
+        let mut block_words = [0u32; SHA256_BLOCK_SIZE];
+        for (word, felt) in block_words.iter_mut().zip(&block) {
+            *word = to_u32(felt)?;
+        }
+        let mut state = [0u32; SHA256_STATE_SIZE];
+        for (word, felt) in state.iter_mut().zip(&prev_state) {
+            *word = to_u32(felt)?;
+        }
+        sha256_compress(&mut state, &block_words);
+
+        // The OS checks every processed block, so the input, the previous state and the new state
+        // of each of them are laid out one after another in a single segment.
+        let segment_start = match self.sha256_segment_end_ptr {
+            Some(segment_end) => segment_end,
+            None => vm.add_memory_segment(),
+        };
+        let data: Vec<MaybeRelocatable> = block
+            .into_iter()
+            .chain(prev_state)
+            .chain(state.into_iter().map(Felt252::from))
+            .map(MaybeRelocatable::from)
+            .collect();
+        vm.write_arg(segment_start, &data)?;
+
+        let state_ptr = (segment_start + (SHA256_BLOCK_SIZE + SHA256_STATE_SIZE))?;
+        self.sha256_segment_end_ptr = Some((state_ptr + SHA256_STATE_SIZE)?);
+
+        Ok(SyscallResponse {
+            gas: remaining_gas,
+            body: Some(ResponseBody::Sha256ProcessBlock { state_ptr }),
+        })
+    }
+
+    fn secp256_new<P: SWCurveConfig>(
+        &mut self,
+        vm: &mut VirtualMachine,
+        request: Secp256NewRequest,
+        remaining_gas: u128,
+        points: fn(&mut Self) -> &mut SecpPointStore<P>,
+    ) -> Result<SyscallResponse, SyscallHandlerError>
+    where
+        P::BaseField: PrimeField,
+    {
+        let body = match secp::new_point::<P>(&request.x, &request.y) {
+            Ok(Some(point)) => {
+                ResponseBody::Secp256OptionalPoint(Some(points(self).allocate(vm, point)?))
+            }
+            Ok(None) => ResponseBody::Secp256OptionalPoint(None),
+            Err(_) => self.failure_from_error_msg(vm, INVALID_ARGUMENT)?,
+        };
+
+        Ok(SyscallResponse {
+            gas: remaining_gas,
+            body: Some(body),
+        })
+    }
+
+    fn secp256_add<P: SWCurveConfig>(
+        &mut self,
+        vm: &mut VirtualMachine,
+        request: Secp256AddRequest,
+        remaining_gas: u128,
+        points: fn(&mut Self) -> &mut SecpPointStore<P>,
+    ) -> Result<SyscallResponse, SyscallHandlerError> {
+        let store = points(self);
+        let result = secp::add(store.get(request.p0)?, store.get(request.p1)?);
+
+        Ok(SyscallResponse {
+            gas: remaining_gas,
+            body: Some(ResponseBody::Secp256Point(store.allocate(vm, result)?)),
+        })
+    }
+
+    fn secp256_mul<P: SWCurveConfig>(
+        &mut self,
+        vm: &mut VirtualMachine,
+        request: Secp256MulRequest,
+        remaining_gas: u128,
+        points: fn(&mut Self) -> &mut SecpPointStore<P>,
+    ) -> Result<SyscallResponse, SyscallHandlerError> {
+        let store = points(self);
+        let result = secp::mul(store.get(request.p)?, &request.scalar);
+
+        Ok(SyscallResponse {
+            gas: remaining_gas,
+            body: Some(ResponseBody::Secp256Point(store.allocate(vm, result)?)),
+        })
+    }
+
+    fn secp256_get_point_from_x<P: SWCurveConfig>(
+        &mut self,
+        vm: &mut VirtualMachine,
+        request: Secp256GetPointFromXRequest,
+        remaining_gas: u128,
+        points: fn(&mut Self) -> &mut SecpPointStore<P>,
+    ) -> Result<SyscallResponse, SyscallHandlerError>
+    where
+        P::BaseField: PrimeField,
+    {
+        let body = match secp::get_point_from_x::<P>(&request.x, request.y_parity) {
+            Ok(Some(point)) => {
+                ResponseBody::Secp256OptionalPoint(Some(points(self).allocate(vm, point)?))
+            }
+            Ok(None) => ResponseBody::Secp256OptionalPoint(None),
+            Err(_) => self.failure_from_error_msg(vm, INVALID_ARGUMENT)?,
+        };
+
+        Ok(SyscallResponse {
+            gas: remaining_gas,
+            body: Some(body),
+        })
+    }
+
+    fn secp256_get_xy<P: SWCurveConfig>(
+        &mut self,
+        request: Secp256GetXyRequest,
+        remaining_gas: u128,
+        points: fn(&mut Self) -> &mut SecpPointStore<P>,
+    ) -> Result<SyscallResponse, SyscallHandlerError>
+    where
+        P::BaseField: PrimeField,
+    {
+        let (x, y) = secp::get_xy(points(self).get(request.p)?);
+
+        Ok(SyscallResponse {
+            gas: remaining_gas,
+            body: Some(ResponseBody::Secp256GetXy(Secp256GetXyResponse { x, y })),
+        })
+    }
+
     // TODO: refactor code to use this function
     fn failure_from_error_msg(
         &mut self,
@@ -1121,3 +1540,232 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         }))
     }
 }
+
+/// Formats a syscall request for the syscall trace, reading the arrays it points to so that it
+/// matches the request recorded by the Cairo Native handler.
+fn trace_request(
+    vm: &VirtualMachine,
+    request: &SyscallRequest,
+) -> Result<String, SyscallHandlerError> {
+    Ok(match request {
+        SyscallRequest::EmitEvent(request) => trace_request::emit_event(
+            &get_felt_range(vm, request.keys_start, request.keys_end)?,
+            &get_felt_range(vm, request.data_start, request.data_end)?,
+        ),
+        SyscallRequest::CallContract(request) => trace_request::call_contract(
+            &request.contract_address.0,
+            &request.selector,
+            &get_felt_range(vm, request.calldata_start, request.calldata_end)?,
+        ),
+        SyscallRequest::LibraryCall(request) => trace_request::library_call(
+            &request.class_hash,
+            &request.selector,
+            &get_felt_range(vm, request.calldata_start, request.calldata_end)?,
+        ),
+        SyscallRequest::Deploy(request) => trace_request::deploy(
+            &request.class_hash,
+            &request.salt,
+            &get_felt_range(vm, request.calldata_start, request.calldata_end)?,
+            request.deploy_from_zero != 0,
+        ),
+        SyscallRequest::SendMessageToL1(request) => trace_request::send_message_to_l1(
+            &request.to_address.0,
+            &get_felt_range(vm, request.payload_start, request.payload_end)?,
+        ),
+        SyscallRequest::Keccak(request) => trace_request::keccak(
+            &get_felt_range(vm, request.input_start, request.input_end)?
+                .iter()
+                .map(|felt| {
+                    felt.to_u64().ok_or_else(|| {
+                        SyscallHandlerError::Conversion("Felt252".to_string(), "u64".to_string())
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        request => format!("{request:?}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        add_segments,
+        definitions::versioned_constants::{EventLimits, VersionedConstants},
+        execution::{DataAvailabilityMode, ExtendedTxInfo, Resource, ResourceBounds},
+        state::{
+            contract_class_cache::PermanentContractClassCache,
+            in_memory_state_reader::InMemoryStateReader,
+        },
+        utils::{get_relocatable, test_utils::vm},
+    };
+    use coverage_helper::test;
+    use std::sync::Arc;
+
+    #[test]
+    fn sha256_process_block_writes_the_new_state() {
+        let mut vm = vm!();
+        add_segments!(vm, 4);
+        // The initial state and the padded block of the message "abc".
+        let state = [
+            0x6a09e667_u32,
+            0xbb67ae85,
+            0x3c6ef372,
+            0xa54ff53a,
+            0x510e527f,
+            0x9b05688c,
+            0x1f83d9ab,
+            0x5be0cd19,
+        ];
+        let mut block = [0_u32; SHA256_BLOCK_SIZE];
+        block[0] = 0x61626380;
+        block[15] = 0x18;
+        for (i, word) in state.iter().enumerate() {
+            vm.insert_value((2, i).into(), Felt252::from(*word))
+                .unwrap();
+        }
+        for (i, word) in block.iter().enumerate() {
+            vm.insert_value((3, i).into(), Felt252::from(*word))
+                .unwrap();
+        }
+        vm.insert_value(
+            (1, 0).into(),
+            Felt252::from_bytes_be("Sha256ProcessBlock".as_bytes()),
+        )
+        .unwrap();
+        vm.insert_value((1, 1).into(), Felt252::from(1_000_000))
+            .unwrap();
+        vm.insert_value((1, 2).into(), Relocatable::from((2, 0)))
+            .unwrap();
+        vm.insert_value((1, 3).into(), Relocatable::from((3, 0)))
+            .unwrap();
+
+        let mut state_reader =
+            CachedState::<InMemoryStateReader, PermanentContractClassCache>::default();
+        let mut syscall_handler =
+            BusinessLogicSyscallHandler::default_with_state(&mut state_reader);
+        // The syscall is only available from Starknet 0.13.2.
+        syscall_handler.block_context.versioned_constants =
+            Arc::new(VersionedConstants::for_version("0.13.2").unwrap());
+        syscall_handler
+            .syscall(
+                &mut vm,
+                (1, 0).into(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(get_big_int(&vm, (1, 5).into()).unwrap(), Felt252::zero());
+        let state_ptr = get_relocatable(&vm, (1, 6).into()).unwrap();
+        assert_eq!(state_ptr, (4, SHA256_BLOCK_SIZE + SHA256_STATE_SIZE).into());
+        assert_eq!(
+            get_felt_range(&vm, state_ptr, (state_ptr + SHA256_STATE_SIZE).unwrap()).unwrap(),
+            [
+                0xba7816bf_u32,
+                0x8f01cfea,
+                0x414140de,
+                0x5dae2223,
+                0xb00361a3,
+                0x96177a9c,
+                0xb410ff61,
+                0xf20015ad,
+            ]
+            .map(Felt252::from)
+        );
+        assert_eq!(
+            syscall_handler.sha256_segment_end_ptr,
+            Some((state_ptr + SHA256_STATE_SIZE).unwrap())
+        );
+    }
+
+    #[test]
+    fn get_execution_info_writes_the_v2_layout() {
+        let mut vm = vm!();
+        let mut state_reader =
+            CachedState::<InMemoryStateReader, PermanentContractClassCache>::default();
+        let mut syscall_handler =
+            BusinessLogicSyscallHandler::default_with_state(&mut state_reader);
+        syscall_handler.tx_execution_context = TransactionExecutionContext::new(
+            Address(1.into()),
+            2.into(),
+            vec![3.into()],
+            4,
+            5.into(),
+            0,
+            3.into(),
+        )
+        .with_extended_tx_info(ExtendedTxInfo {
+            resource_bounds: vec![ResourceBounds {
+                resource: Resource::L1Gas,
+                max_amount: 6,
+                max_price_per_unit: 7,
+            }],
+            tip: 8,
+            paymaster_data: vec![9.into()],
+            nonce_data_availability_mode: DataAvailabilityMode::L2,
+            fee_data_availability_mode: DataAvailabilityMode::L1,
+            account_deployment_data: vec![10.into(), 11.into()],
+        });
+
+        let execution_info_ptr = syscall_handler
+            .get_or_allocate_execution_info(&mut vm)
+            .unwrap();
+        let tx_info_ptr = get_relocatable(&vm, (execution_info_ptr + 1).unwrap()).unwrap();
+        let felt_at = |offset: usize| get_big_int(&vm, (tx_info_ptr + offset).unwrap()).unwrap();
+        let span_at = |offset: usize| {
+            let start = get_relocatable(&vm, (tx_info_ptr + offset).unwrap()).unwrap();
+            let end = get_relocatable(&vm, (tx_info_ptr + (offset + 1)).unwrap()).unwrap();
+            get_felt_range(&vm, start, end).unwrap()
+        };
+
+        assert_eq!(felt_at(0), 3.into());
+        assert_eq!(felt_at(7), 5.into());
+        assert_eq!(
+            span_at(8),
+            vec![Resource::L1Gas.to_felt(), 6.into(), 7.into()]
+        );
+        assert_eq!(felt_at(10), 8.into());
+        assert_eq!(span_at(11), vec![Felt252::from(9)]);
+        assert_eq!(felt_at(13), Felt252::one());
+        assert_eq!(felt_at(14), Felt252::zero());
+        assert_eq!(span_at(15), vec![Felt252::from(10), Felt252::from(11)]);
+    }
+
+    #[test]
+    fn emit_event_checks_the_event_limits() {
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+        for i in 0..3 {
+            vm.insert_value((1, i).into(), Felt252::from(i)).unwrap();
+        }
+        let request = |n_keys: usize| EmitEventRequest {
+            keys_start: (1, 0).into(),
+            keys_end: (1, n_keys).into(),
+            data_start: (1, 0).into(),
+            data_end: (1, 1).into(),
+        };
+
+        let mut state_reader =
+            CachedState::<InMemoryStateReader, PermanentContractClassCache>::default();
+        let mut syscall_handler =
+            BusinessLogicSyscallHandler::default_with_state(&mut state_reader);
+        *Arc::make_mut(&mut syscall_handler.block_context.versioned_constants).event_limits_mut() =
+            EventLimits {
+                max_keys_length: 2,
+                max_data_length: 1,
+                max_n_emitted_events: 1,
+            };
+
+        assert!(matches!(
+            syscall_handler.emit_event(&vm, request(3), 0),
+            Err(SyscallHandlerError::MaxEventKeysExceeded(3, 2))
+        ));
+        assert!(syscall_handler.emit_event(&vm, request(2), 0).is_ok());
+        assert!(matches!(
+            syscall_handler.emit_event(&vm, request(2), 0),
+            Err(SyscallHandlerError::MaxEmittedEventsExceeded(1))
+        ));
+        assert_eq!(syscall_handler.events.len(), 1);
+    }
+}
@@ -0,0 +1,546 @@
+//! # Test cheatcodes
+//!
+//! Overrides of the execution environment seen by contracts, meant for testing. The syscall
+//! handlers answer `get_execution_info`, `get_block_number`, `get_block_timestamp`,
+//! `get_sequencer_address`, `get_caller_address` and `get_tx_info` (and `get_tx_signature`) with
+//! the overridden values, without having to rebuild the `BlockContext` or the transaction's
+//! execution context:
+//!
+//! - `prank` overrides the caller address.
+//! - `roll` overrides the block number.
+//! - `warp` overrides the block timestamp.
+//! - `elect` overrides the sequencer address.
+//! - `spoof` overrides fields of the transaction info.
+//!
+//! Every override targets either a single contract or all of them, overrides of a single contract
+//! taking precedence.
+//!
+//! Calls to contracts (and library calls to classes) may also be mocked with [`MockedCall`]s,
+//! which return canned results instead of executing the callee.
+
+use crate::{
+    execution::{
+        execution_entry_point::ExecutionEntryPoint, CallInfo, TransactionExecutionContext,
+    },
+    state::BlockInfo,
+    utils::{Address, ClassHash},
+};
+use cairo_vm::felt::Felt252;
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// Contracts affected by a cheatcode.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CheatTarget {
+    /// Only the contract deployed at the address.
+    One(Address),
+    /// Every contract.
+    All,
+}
+
+/// Overridden values of a cheatcode, by target.
+#[derive(Clone, Debug, PartialEq)]
+struct CheatMap<T> {
+    all: Option<T>,
+    by_address: HashMap<Address, T>,
+}
+
+impl<T> CheatMap<T> {
+    fn start(&mut self, target: CheatTarget, value: T) {
+        match target {
+            CheatTarget::One(address) => {
+                self.by_address.insert(address, value);
+            }
+            CheatTarget::All => self.all = Some(value),
+        }
+    }
+
+    fn stop(&mut self, target: CheatTarget) {
+        match target {
+            CheatTarget::One(address) => {
+                self.by_address.remove(&address);
+            }
+            CheatTarget::All => {
+                self.all = None;
+                self.by_address.clear();
+            }
+        }
+    }
+
+    fn get(&self, address: &Address) -> Option<&T> {
+        self.by_address.get(address).or(self.all.as_ref())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.all.is_none() && self.by_address.is_empty()
+    }
+}
+
+impl<T> Default for CheatMap<T> {
+    fn default() -> Self {
+        Self {
+            all: None,
+            by_address: HashMap::new(),
+        }
+    }
+}
+
+/// Fields of the transaction info to override. Fields set to `None` keep their actual value.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TxInfoOverrides {
+    pub version: Option<Felt252>,
+    pub account_contract_address: Option<Address>,
+    pub max_fee: Option<u128>,
+    pub signature: Option<Vec<Felt252>>,
+    pub transaction_hash: Option<Felt252>,
+    pub chain_id: Option<Felt252>,
+    pub nonce: Option<Felt252>,
+}
+
+/// Callee of a mocked call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MockedCallee {
+    /// Calls to the contract deployed at the address, including delegate calls.
+    Contract(Address),
+    /// Library calls to the class.
+    Class(ClassHash),
+}
+
+/// Result returned by a mocked call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MockedResult {
+    /// The call succeeds with the retdata.
+    Success(Vec<Felt252>),
+    /// The call fails with the retdata (the panic data, for Cairo 1 callers).
+    Failure(Vec<Felt252>),
+}
+
+/// Predicate on the calldata of a mocked call.
+pub type CalldataPredicate = Arc<dyn Fn(&[Felt252]) -> bool + Send + Sync>;
+
+/// A call which returns a canned result instead of executing the callee.
+#[derive(Clone)]
+pub struct MockedCall {
+    callee: MockedCallee,
+    selector: Felt252,
+    calldata: Option<CalldataPredicate>,
+    result: MockedResult,
+}
+
+impl MockedCall {
+    /// Mocks the calls to the entry point of the callee.
+    pub fn new(callee: MockedCallee, selector: Felt252, result: MockedResult) -> Self {
+        Self {
+            callee,
+            selector,
+            calldata: None,
+            result,
+        }
+    }
+
+    /// Only mocks the calls whose calldata satisfies the predicate.
+    pub fn with_calldata(
+        mut self,
+        predicate: impl Fn(&[Felt252]) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.calldata = Some(Arc::new(predicate));
+        self
+    }
+
+    fn matches(&self, entry_point: &ExecutionEntryPoint) -> bool {
+        let callee_matches = match &self.callee {
+            MockedCallee::Contract(address) => {
+                entry_point.class_hash.is_none()
+                    && entry_point
+                        .code_address
+                        .as_ref()
+                        .unwrap_or(&entry_point.contract_address)
+                        == address
+            }
+            MockedCallee::Class(class_hash) => entry_point.class_hash.as_ref() == Some(class_hash),
+        };
+
+        callee_matches
+            && entry_point.entry_point_selector == self.selector
+            && self
+                .calldata
+                .as_ref()
+                .map_or(true, |predicate| predicate(&entry_point.calldata))
+    }
+}
+
+impl fmt::Debug for MockedCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockedCall")
+            .field("callee", &self.callee)
+            .field("selector", &self.selector)
+            .field("has_calldata_predicate", &self.calldata.is_some())
+            .field("result", &self.result)
+            .finish()
+    }
+}
+
+/// The cheatcodes in effect, configured through the `BlockContext`.
+#[derive(Clone, Debug, Default)]
+pub struct Cheatcodes {
+    caller_address: CheatMap<Address>,
+    block_number: CheatMap<u64>,
+    block_timestamp: CheatMap<u64>,
+    sequencer_address: CheatMap<Address>,
+    tx_info: CheatMap<TxInfoOverrides>,
+    mocked_calls: Vec<MockedCall>,
+}
+
+impl Cheatcodes {
+    /// Overrides the caller address seen by the target.
+    pub fn prank(&mut self, target: CheatTarget, caller_address: Address) {
+        self.caller_address.start(target, caller_address);
+    }
+
+    /// Removes the caller address override of the target.
+    pub fn stop_prank(&mut self, target: CheatTarget) {
+        self.caller_address.stop(target);
+    }
+
+    /// Overrides the block number seen by the target.
+    pub fn roll(&mut self, target: CheatTarget, block_number: u64) {
+        self.block_number.start(target, block_number);
+    }
+
+    /// Removes the block number override of the target.
+    pub fn stop_roll(&mut self, target: CheatTarget) {
+        self.block_number.stop(target);
+    }
+
+    /// Overrides the block timestamp seen by the target.
+    pub fn warp(&mut self, target: CheatTarget, block_timestamp: u64) {
+        self.block_timestamp.start(target, block_timestamp);
+    }
+
+    /// Removes the block timestamp override of the target.
+    pub fn stop_warp(&mut self, target: CheatTarget) {
+        self.block_timestamp.stop(target);
+    }
+
+    /// Overrides the sequencer address seen by the target.
+    pub fn elect(&mut self, target: CheatTarget, sequencer_address: Address) {
+        self.sequencer_address.start(target, sequencer_address);
+    }
+
+    /// Removes the sequencer address override of the target.
+    pub fn stop_elect(&mut self, target: CheatTarget) {
+        self.sequencer_address.stop(target);
+    }
+
+    /// Overrides the transaction info seen by the target.
+    pub fn spoof(&mut self, target: CheatTarget, tx_info: TxInfoOverrides) {
+        self.tx_info.start(target, tx_info);
+    }
+
+    /// Removes the transaction info overrides of the target.
+    pub fn stop_spoof(&mut self, target: CheatTarget) {
+        self.tx_info.stop(target);
+    }
+
+    /// Mocks a call. When several mocks match a call, the last one added is used.
+    pub fn mock_call(&mut self, mocked_call: MockedCall) {
+        self.mocked_calls.push(mocked_call);
+    }
+
+    /// Removes the mocks of the callee's entry point.
+    pub fn clear_mocked_calls(&mut self, callee: &MockedCallee, selector: &Felt252) {
+        self.mocked_calls.retain(|mocked_call| {
+            &mocked_call.callee != callee || &mocked_call.selector != selector
+        });
+    }
+
+    /// Returns whether no cheatcode is in effect.
+    pub fn is_empty(&self) -> bool {
+        self.caller_address.is_empty()
+            && self.block_number.is_empty()
+            && self.block_timestamp.is_empty()
+            && self.sequencer_address.is_empty()
+            && self.tx_info.is_empty()
+            && self.mocked_calls.is_empty()
+    }
+
+    /// Returns the call info of the call if it's mocked, recording the canned result.
+    pub(crate) fn mocked_call_info(&self, entry_point: &ExecutionEntryPoint) -> Option<CallInfo> {
+        let mocked_call = self
+            .mocked_calls
+            .iter()
+            .rev()
+            .find(|mocked_call| mocked_call.matches(entry_point))?;
+
+        let mut call_info = CallInfo::empty(
+            entry_point.contract_address.clone(),
+            entry_point.caller_address.clone(),
+            entry_point.class_hash,
+            Some(entry_point.call_type.clone()),
+            Some(entry_point.entry_point_type),
+            Some(entry_point.entry_point_selector.clone()),
+            entry_point.code_address.clone(),
+        );
+        call_info.calldata = entry_point.calldata.clone();
+        (call_info.retdata, call_info.failure_flag) = match &mocked_call.result {
+            MockedResult::Success(retdata) => (retdata.clone(), false),
+            MockedResult::Failure(retdata) => (retdata.clone(), true),
+        };
+
+        Some(call_info)
+    }
+
+    /// Returns the caller address seen by the contract.
+    pub(crate) fn caller_address(&self, contract_address: &Address, actual: &Address) -> Address {
+        self.caller_address
+            .get(contract_address)
+            .unwrap_or(actual)
+            .clone()
+    }
+
+    /// Returns the block info seen by the contract.
+    pub(crate) fn block_info(&self, contract_address: &Address, actual: &BlockInfo) -> BlockInfo {
+        BlockInfo {
+            block_number: self
+                .block_number
+                .get(contract_address)
+                .copied()
+                .unwrap_or(actual.block_number),
+            block_timestamp: self
+                .block_timestamp
+                .get(contract_address)
+                .copied()
+                .unwrap_or(actual.block_timestamp),
+            gas_price: actual.gas_price,
+            l1_data_gas_price: actual.l1_data_gas_price,
+            use_kzg_da: actual.use_kzg_da,
+            sequencer_address: self
+                .sequencer_address
+                .get(contract_address)
+                .unwrap_or(&actual.sequencer_address)
+                .clone(),
+        }
+    }
+
+    /// Returns the transaction's execution context and the chain ID seen by the contract.
+    pub(crate) fn tx_info(
+        &self,
+        contract_address: &Address,
+        actual: &TransactionExecutionContext,
+        actual_chain_id: &Felt252,
+    ) -> (TransactionExecutionContext, Felt252) {
+        let mut tx_execution_context = actual.clone();
+        let overrides = match self.tx_info.get(contract_address) {
+            Some(overrides) => overrides,
+            None => return (tx_execution_context, actual_chain_id.clone()),
+        };
+
+        if let Some(version) = &overrides.version {
+            tx_execution_context.version = version.clone();
+        }
+        if let Some(account_contract_address) = &overrides.account_contract_address {
+            tx_execution_context.account_contract_address = account_contract_address.clone();
+        }
+        if let Some(max_fee) = overrides.max_fee {
+            tx_execution_context.max_fee = max_fee;
+        }
+        if let Some(signature) = &overrides.signature {
+            tx_execution_context.signature = signature.clone();
+        }
+        if let Some(transaction_hash) = &overrides.transaction_hash {
+            tx_execution_context.transaction_hash = transaction_hash.clone();
+        }
+        if let Some(nonce) = &overrides.nonce {
+            tx_execution_context.nonce = nonce.clone();
+        }
+
+        let chain_id = overrides
+            .chain_id
+            .as_ref()
+            .unwrap_or(actual_chain_id)
+            .clone();
+        (tx_execution_context, chain_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{execution::CallType, EntryPointType};
+    use coverage_helper::test;
+
+    #[test]
+    fn overrides_of_one_contract_take_precedence() {
+        let contract = Address(1.into());
+        let other_contract = Address(2.into());
+        let actual_caller = Address(3.into());
+
+        let mut cheatcodes = Cheatcodes::default();
+        assert!(cheatcodes.is_empty());
+
+        cheatcodes.prank(CheatTarget::All, Address(10.into()));
+        cheatcodes.prank(CheatTarget::One(contract.clone()), Address(11.into()));
+        assert_eq!(
+            cheatcodes.caller_address(&contract, &actual_caller),
+            Address(11.into())
+        );
+        assert_eq!(
+            cheatcodes.caller_address(&other_contract, &actual_caller),
+            Address(10.into())
+        );
+
+        cheatcodes.stop_prank(CheatTarget::One(contract.clone()));
+        assert_eq!(
+            cheatcodes.caller_address(&contract, &actual_caller),
+            Address(10.into())
+        );
+
+        cheatcodes.stop_prank(CheatTarget::All);
+        assert_eq!(
+            cheatcodes.caller_address(&contract, &actual_caller),
+            actual_caller
+        );
+        assert!(cheatcodes.is_empty());
+    }
+
+    #[test]
+    fn block_info_overrides() {
+        let contract = Address(1.into());
+        let actual = BlockInfo {
+            block_number: 5,
+            block_timestamp: 100,
+            gas_price: 7,
+            sequencer_address: Address(9.into()),
+            ..Default::default()
+        };
+
+        let mut cheatcodes = Cheatcodes::default();
+        cheatcodes.roll(CheatTarget::One(contract.clone()), 50);
+        cheatcodes.warp(CheatTarget::All, 1000);
+        cheatcodes.elect(CheatTarget::One(contract.clone()), Address(8.into()));
+
+        let block_info = cheatcodes.block_info(&contract, &actual);
+        assert_eq!(block_info.block_number, 50);
+        assert_eq!(block_info.block_timestamp, 1000);
+        assert_eq!(block_info.gas_price, 7);
+        assert_eq!(block_info.sequencer_address, Address(8.into()));
+
+        let block_info = cheatcodes.block_info(&Address(2.into()), &actual);
+        assert_eq!(block_info.block_number, 5);
+        assert_eq!(block_info.block_timestamp, 1000);
+        assert_eq!(block_info.sequencer_address, Address(9.into()));
+    }
+
+    #[test]
+    fn tx_info_overrides() {
+        let contract = Address(1.into());
+        let actual = TransactionExecutionContext::new(
+            Address(2.into()),
+            3.into(),
+            vec![4.into()],
+            5,
+            6.into(),
+            10,
+            1.into(),
+        );
+        let actual_chain_id = Felt252::from(7);
+
+        let mut cheatcodes = Cheatcodes::default();
+        cheatcodes.spoof(
+            CheatTarget::One(contract.clone()),
+            TxInfoOverrides {
+                signature: Some(vec![40.into(), 41.into()]),
+                chain_id: Some(70.into()),
+                nonce: Some(60.into()),
+                ..Default::default()
+            },
+        );
+
+        let (tx_execution_context, chain_id) =
+            cheatcodes.tx_info(&contract, &actual, &actual_chain_id);
+        assert_eq!(tx_execution_context.signature, vec![40.into(), 41.into()]);
+        assert_eq!(tx_execution_context.nonce, 60.into());
+        assert_eq!(tx_execution_context.max_fee, 5);
+        assert_eq!(
+            tx_execution_context.account_contract_address,
+            Address(2.into())
+        );
+        assert_eq!(chain_id, 70.into());
+
+        let (tx_execution_context, chain_id) =
+            cheatcodes.tx_info(&Address(2.into()), &actual, &actual_chain_id);
+        assert_eq!(tx_execution_context.signature, actual.signature);
+        assert_eq!(tx_execution_context.nonce, actual.nonce);
+        assert_eq!(chain_id, actual_chain_id);
+    }
+
+    #[test]
+    fn mocked_calls() {
+        let callee = Address(1.into());
+        let class_hash = ClassHash::from(Felt252::from(2));
+        let selector = Felt252::from(3);
+        let call = |calldata: Vec<Felt252>, class_hash: Option<ClassHash>| {
+            ExecutionEntryPoint::new(
+                callee.clone(),
+                calldata,
+                selector.clone(),
+                Address(4.into()),
+                EntryPointType::External,
+                Some(if class_hash.is_some() {
+                    CallType::Delegate
+                } else {
+                    CallType::Call
+                }),
+                class_hash,
+                0,
+            )
+        };
+
+        let mut cheatcodes = Cheatcodes::default();
+        cheatcodes.mock_call(MockedCall::new(
+            MockedCallee::Contract(callee.clone()),
+            selector.clone(),
+            MockedResult::Success(vec![10.into()]),
+        ));
+        cheatcodes.mock_call(
+            MockedCall::new(
+                MockedCallee::Contract(callee.clone()),
+                selector.clone(),
+                MockedResult::Failure(vec![11.into()]),
+            )
+            .with_calldata(|calldata| calldata.first() == Some(&Felt252::from(1))),
+        );
+
+        let call_info = cheatcodes.mocked_call_info(&call(vec![], None)).unwrap();
+        assert_eq!(call_info.retdata, vec![10.into()]);
+        assert!(!call_info.failure_flag);
+        assert_eq!(call_info.contract_address, callee);
+        assert_eq!(call_info.caller_address, Address(4.into()));
+
+        let call_info = cheatcodes
+            .mocked_call_info(&call(vec![1.into()], None))
+            .unwrap();
+        assert_eq!(call_info.retdata, vec![11.into()]);
+        assert!(call_info.failure_flag);
+        assert_eq!(call_info.calldata, vec![1.into()]);
+
+        // Library calls are only matched by class hash.
+        assert!(cheatcodes
+            .mocked_call_info(&call(vec![], Some(class_hash)))
+            .is_none());
+        cheatcodes.mock_call(MockedCall::new(
+            MockedCallee::Class(class_hash),
+            selector.clone(),
+            MockedResult::Success(vec![12.into()]),
+        ));
+        assert_eq!(
+            cheatcodes
+                .mocked_call_info(&call(vec![], Some(class_hash)))
+                .unwrap()
+                .retdata,
+            vec![12.into()]
+        );
+
+        cheatcodes.clear_mocked_calls(&MockedCallee::Contract(callee.clone()), &selector);
+        assert!(cheatcodes.mocked_call_info(&call(vec![], None)).is_none());
+    }
+}
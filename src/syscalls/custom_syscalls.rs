@@ -0,0 +1,268 @@
+//! # Custom syscalls
+//!
+//! Embedders, such as test frameworks and app-chains, may register extra syscalls in the
+//! [`BlockContext`] without patching the syscall handlers. Custom syscalls are identified by their
+//! selector and are only available to Cairo 1 contracts executed in the Cairo VM. Built-in
+//! syscalls take precedence over custom syscalls with the same selector.
+//!
+//! ```ignore
+//! block_context.custom_syscalls_mut().register(
+//!     Felt252::from_bytes_be(b"Print"),
+//!     CustomSyscall::new("print", 100, |context, request| {
+//!         println!("{:?} printed {request:?}", context.contract_address);
+//!         Ok(CustomSyscallResponse::Success(vec![]))
+//!     })
+//!     .with_request(2, |vm, request_ptr| {
+//!         let start = get_relocatable(vm, request_ptr)?;
+//!         let end = get_relocatable(vm, (request_ptr + 1)?)?;
+//!         Ok(get_felt_range(vm, start, end)?)
+//!     }),
+//! );
+//! ```
+
+use super::syscall_handler_errors::SyscallHandlerError;
+use crate::{definitions::block_context::BlockContext, state::state_api::State, utils::Address};
+use cairo_vm::{
+    felt::Felt252,
+    types::relocatable::{MaybeRelocatable, Relocatable},
+    vm::vm_core::VirtualMachine,
+};
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// Reads the request of a custom syscall from the VM memory, given the address of its first cell
+/// (right after the selector and the gas).
+pub type CustomSyscallDecoder = Arc<
+    dyn Fn(&VirtualMachine, Relocatable) -> Result<Vec<Felt252>, SyscallHandlerError> + Send + Sync,
+>;
+
+/// Executes a custom syscall, given its decoded request.
+pub type CustomSyscallHandler = Arc<
+    dyn Fn(
+            &mut CustomSyscallContext<'_>,
+            Vec<Felt252>,
+        ) -> Result<CustomSyscallResponse, SyscallHandlerError>
+        + Send
+        + Sync,
+>;
+
+/// What a custom syscall handler can access.
+pub struct CustomSyscallContext<'a> {
+    /// State of the transaction. Storage accesses made through it aren't recorded in the
+    /// `CallInfo` of the call.
+    pub state: &'a mut dyn State,
+    /// The VM running the contract, to read from or allocate memory.
+    pub vm: &'a mut VirtualMachine,
+    pub block_context: &'a BlockContext,
+    /// Address of the contract which made the syscall.
+    pub contract_address: &'a Address,
+    /// Address of the caller of the contract which made the syscall.
+    pub caller_address: &'a Address,
+}
+
+/// Result of a custom syscall.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CustomSyscallResponse {
+    /// The syscall succeeded. The values are written to the response of the syscall, after the
+    /// remaining gas and the failure flag.
+    Success(Vec<MaybeRelocatable>),
+    /// The syscall failed with the given reason.
+    Failure(Vec<Felt252>),
+}
+
+/// A syscall registered by the embedder.
+#[derive(Clone)]
+pub struct CustomSyscall {
+    name: String,
+    gas_cost: u128,
+    request_size: usize,
+    decoder: CustomSyscallDecoder,
+    handler: CustomSyscallHandler,
+}
+
+impl CustomSyscall {
+    /// Creates a custom syscall without request fields, which charges `gas_cost` gas (on top of
+    /// the syscall base cost charged by the OS).
+    pub fn new<H>(name: impl Into<String>, gas_cost: u128, handler: H) -> Self
+    where
+        H: Fn(
+                &mut CustomSyscallContext<'_>,
+                Vec<Felt252>,
+            ) -> Result<CustomSyscallResponse, SyscallHandlerError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self {
+            name: name.into(),
+            gas_cost,
+            request_size: 0,
+            decoder: Arc::new(|_, _| Ok(Vec::new())),
+            handler: Arc::new(handler),
+        }
+    }
+
+    /// Sets the request of the syscall, which takes `request_size` memory cells and is read with
+    /// `decoder`.
+    pub fn with_request<D>(mut self, request_size: usize, decoder: D) -> Self
+    where
+        D: Fn(&VirtualMachine, Relocatable) -> Result<Vec<Felt252>, SyscallHandlerError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.request_size = request_size;
+        self.decoder = Arc::new(decoder);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn gas_cost(&self) -> u128 {
+        self.gas_cost
+    }
+
+    pub fn request_size(&self) -> usize {
+        self.request_size
+    }
+
+    pub(crate) fn decode(
+        &self,
+        vm: &VirtualMachine,
+        request_ptr: Relocatable,
+    ) -> Result<Vec<Felt252>, SyscallHandlerError> {
+        (self.decoder)(vm, request_ptr)
+    }
+
+    pub(crate) fn execute(
+        &self,
+        context: &mut CustomSyscallContext<'_>,
+        request: Vec<Felt252>,
+    ) -> Result<CustomSyscallResponse, SyscallHandlerError> {
+        (self.handler)(context, request)
+    }
+}
+
+impl fmt::Debug for CustomSyscall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomSyscall")
+            .field("name", &self.name)
+            .field("gas_cost", &self.gas_cost)
+            .field("request_size", &self.request_size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Custom syscalls, by selector.
+#[derive(Clone, Debug, Default)]
+pub struct CustomSyscalls {
+    by_selector: HashMap<Felt252, CustomSyscall>,
+}
+
+impl CustomSyscalls {
+    /// Registers a custom syscall, replacing the one previously registered with the same selector.
+    pub fn register(&mut self, selector: Felt252, syscall: CustomSyscall) {
+        self.by_selector.insert(selector, syscall);
+    }
+
+    /// Removes the custom syscall registered with the selector, returning it.
+    pub fn unregister(&mut self, selector: &Felt252) -> Option<CustomSyscall> {
+        self.by_selector.remove(selector)
+    }
+
+    pub fn get(&self, selector: &Felt252) -> Option<&CustomSyscall> {
+        self.by_selector.get(selector)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_selector.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        add_segments,
+        state::{
+            cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
+            in_memory_state_reader::InMemoryStateReader, state_api::StateReader,
+        },
+        syscalls::business_logic_syscall_handler::BusinessLogicSyscallHandler,
+        utils::{get_big_int, test_utils::vm},
+    };
+    use coverage_helper::test;
+
+    #[test]
+    fn execute_custom_syscall() {
+        let selector = Felt252::from_bytes_be(b"Double");
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+        vm.insert_value((1, 0).into(), selector.clone()).unwrap();
+        vm.insert_value((1, 1).into(), Felt252::from(1000)).unwrap();
+        vm.insert_value((1, 2).into(), Felt252::from(21)).unwrap();
+
+        let mut state = CachedState::<InMemoryStateReader, PermanentContractClassCache>::default();
+        let mut syscall_handler = BusinessLogicSyscallHandler::default_with_state(&mut state);
+        syscall_handler
+            .block_context
+            .custom_syscalls_mut()
+            .register(
+                selector,
+                CustomSyscall::new("double", 100, |context, request| {
+                    context
+                        .state
+                        .set_storage_at(&(context.contract_address.clone(), [0; 32]), 1.into());
+                    Ok(CustomSyscallResponse::Success(vec![(&request[0]
+                        * Felt252::from(2))
+                    .into()]))
+                })
+                .with_request(1, |vm, request_ptr| Ok(vec![get_big_int(vm, request_ptr)?])),
+            );
+
+        syscall_handler
+            .syscall(
+                &mut vm,
+                (1, 0).into(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+
+        // The response holds the remaining gas, the failure flag and the doubled value.
+        assert_eq!(get_big_int(&vm, (1, 3).into()).unwrap(), 900.into());
+        assert_eq!(get_big_int(&vm, (1, 4).into()).unwrap(), 0.into());
+        assert_eq!(get_big_int(&vm, (1, 5).into()).unwrap(), 42.into());
+        assert_eq!(syscall_handler.expected_syscall_ptr, (1, 6).into());
+
+        let contract_address = syscall_handler.contract_address.clone();
+        drop(syscall_handler);
+        assert_eq!(
+            StateReader::get_storage_at(&state, &(contract_address, [0; 32])).unwrap(),
+            1.into()
+        );
+    }
+
+    #[test]
+    fn unregistered_selector_is_rejected() {
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+        vm.insert_value((1, 0).into(), Felt252::from_bytes_be(b"Unknown"))
+            .unwrap();
+        vm.insert_value((1, 1).into(), Felt252::from(1000)).unwrap();
+
+        let mut state = CachedState::<InMemoryStateReader, PermanentContractClassCache>::default();
+        let mut syscall_handler = BusinessLogicSyscallHandler::default_with_state(&mut state);
+
+        assert!(matches!(
+            syscall_handler.syscall(
+                &mut vm,
+                (1, 0).into(),
+                #[cfg(feature = "cairo-native")]
+                None,
+            ),
+            Err(SyscallHandlerError::SelectorNotInHandlerMap(_))
+        ));
+    }
+}
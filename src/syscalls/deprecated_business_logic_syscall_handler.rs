@@ -9,6 +9,7 @@ use super::{
     },
     syscall_handler_errors::SyscallHandlerError,
     syscall_info::get_deprecated_syscall_size_from_name,
+    syscall_trace::{request as trace_request, SyscallOutcome, SyscallTracer},
 };
 use crate::{
     core::errors::state_errors::StateError,
@@ -44,6 +45,7 @@ use cairo_vm::{
     vm::vm_core::VirtualMachine,
 };
 use num_traits::{One, ToPrimitive, Zero};
+use std::fmt::Debug;
 
 #[cfg(feature = "cairo-native")]
 use {
@@ -71,6 +73,7 @@ pub struct DeprecatedBLSyscallHandler<'a, S: StateReader, C: ContractClassCache>
     pub(crate) starknet_storage_state: ContractStorageState<'a, S, C>,
     pub(crate) internal_calls: Vec<CallInfo>,
     pub(crate) expected_syscall_ptr: Relocatable,
+    pub(crate) syscall_tracer: SyscallTracer,
 }
 
 impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S, C> {
@@ -90,6 +93,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         let starknet_storage_state = ContractStorageState::new(state, contract_address.clone());
 
         let internal_calls = Vec::new();
+        let syscall_tracer = SyscallTracer::new(block_context.trace_syscalls);
 
         DeprecatedBLSyscallHandler {
             tx_execution_context,
@@ -104,6 +108,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
             starknet_storage_state,
             internal_calls,
             expected_syscall_ptr: syscall_ptr,
+            syscall_tracer,
         }
     }
 
@@ -161,6 +166,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
             starknet_storage_state,
             internal_calls,
             expected_syscall_ptr,
+            syscall_tracer: Default::default(),
         }
     }
 
@@ -252,7 +258,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
                 &mut self.resources_manager,
                 &mut self.tx_execution_context,
                 false,
-                self.block_context.invoke_tx_max_n_steps,
+                self.block_context.invoke_tx_max_n_steps(),
                 #[cfg(feature = "cairo-native")]
                 program_cache,
             )
@@ -280,6 +286,10 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         let keys_len = request.keys_len;
         let data_len = request.data_len;
         let order = self.tx_execution_context.n_emitted_events;
+        self.block_context
+            .versioned_constants
+            .event_limits
+            .check(keys_len, data_len, order)?;
         let keys: Vec<Felt252> = get_integer_range(vm, request.keys, keys_len)?;
         let data: Vec<Felt252> = get_integer_range(vm, request.data, data_len)?;
         self.events.push(OrderedEvent::new(order, keys, data));
@@ -464,35 +474,52 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         );
         entry_point.code_address = code_address;
 
+        let mocked_call_info = self.block_context.cheatcodes.mocked_call_info(&entry_point);
         let ExecutionResult {
             call_info,
             revert_error,
             ..
-        } = entry_point
-            .execute(
-                self.starknet_storage_state.state,
-                &self.block_context,
-                &mut self.resources_manager,
-                &mut self.tx_execution_context,
-                false,
-                self.block_context.invoke_tx_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            )
-            .map_err(|e| SyscallHandlerError::ExecutionError(e.to_string()))?;
+        } = match mocked_call_info {
+            Some(call_info) => ExecutionResult {
+                call_info: Some(call_info),
+                ..Default::default()
+            },
+            None => entry_point
+                .execute(
+                    self.starknet_storage_state.state,
+                    &self.block_context,
+                    &mut self.resources_manager,
+                    &mut self.tx_execution_context,
+                    false,
+                    self.block_context.invoke_tx_max_n_steps(),
+                    #[cfg(feature = "cairo-native")]
+                    program_cache,
+                )
+                .map_err(|e| SyscallHandlerError::ExecutionError(e.to_string()))?,
+        };
 
         let call_info = call_info.ok_or(SyscallHandlerError::ExecutionError(
             revert_error.unwrap_or_else(|| "Execution error".to_string()),
         ))?;
 
+        // Cairo 0 contracts can't handle failed calls, so a mocked failure aborts the execution.
+        let failure = call_info
+            .failure_flag
+            .then(|| format!("Mocked call failed with retdata {:?}", call_info.retdata));
         let retdata = call_info.retdata.clone();
         self.internal_calls.push(call_info);
 
-        Ok(retdata)
+        match failure {
+            Some(error) => Err(SyscallHandlerError::ExecutionError(error)),
+            None => Ok(retdata),
+        }
     }
 
-    pub(crate) const fn get_block_info(&self) -> &BlockInfo {
-        &self.block_context.block_info
+    /// Returns the block info seen by the contract, which may be overridden by cheatcodes.
+    pub(crate) fn get_block_info(&self) -> BlockInfo {
+        self.block_context
+            .cheatcodes
+            .block_info(&self.contract_address, &self.block_context.block_info)
     }
 
     pub(crate) fn syscall_get_caller_address(
@@ -510,7 +537,10 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
             }
         }
 
-        Ok(self.caller_address.clone())
+        Ok(self
+            .block_context
+            .cheatcodes
+            .caller_address(&self.contract_address, &self.caller_address))
     }
 
     pub(crate) fn delegate_l1_handler(
@@ -584,17 +614,17 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         if let Some(ptr) = &self.tx_info_ptr {
             return Ok(ptr.try_into()?);
         }
-        let tx = self.tx_execution_context.clone();
+        let (tx, chain_id) = self.block_context.cheatcodes.tx_info(
+            &self.contract_address,
+            &self.tx_execution_context,
+            &self.block_context.starknet_os_config.chain_id,
+        );
 
         let signature_data: Vec<MaybeRelocatable> =
             tx.signature.iter().map(|num| num.into()).collect();
         let signature = self.allocate_segment(vm, signature_data)?;
 
-        let tx_info = TxInfoStruct::new(
-            tx,
-            signature,
-            self.block_context.starknet_os_config.chain_id.clone(),
-        );
+        let tx_info = TxInfoStruct::new(tx, signature, chain_id);
 
         let tx_info_ptr_temp = self.allocate_segment(vm, tx_info.to_vec())?;
 
@@ -622,7 +652,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         let value = self.syscall_storage_read(request.address)?;
         let response = DeprecatedStorageReadResponse::new(value);
 
-        response.write_syscall_response(vm, syscall_ptr)
+        self.write_syscall_response(&response, vm, syscall_ptr)
     }
 
     pub(crate) fn storage_write(
@@ -669,7 +699,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
                 offset: 0,
             },
         );
-        response.write_syscall_response(vm, syscall_ptr)?;
+        self.write_syscall_response(&response, vm, syscall_ptr)?;
 
         Ok(())
     }
@@ -706,12 +736,13 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         self.write_syscall_response(&response, vm, syscall_ptr)
     }
 
-    pub(crate) fn write_syscall_response<R: DeprecatedWriteSyscallResponse>(
-        &self,
+    pub(crate) fn write_syscall_response<R: DeprecatedWriteSyscallResponse + Debug>(
+        &mut self,
         response: &R,
         vm: &mut VirtualMachine,
         syscall_ptr: Relocatable,
     ) -> Result<(), SyscallHandlerError> {
+        self.syscall_tracer.respond(|| format!("{response:?}"));
         response.write_syscall_response(vm, syscall_ptr)
     }
 
@@ -721,8 +752,8 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         syscall_ptr: Relocatable,
     ) -> Result<(), SyscallHandlerError> {
         self.read_and_validate_syscall_request("get_block_number", vm, syscall_ptr)?;
-        DeprecatedGetBlockNumberResponse::new(self.get_block_info().block_number)
-            .write_syscall_response(vm, syscall_ptr)
+        let response = DeprecatedGetBlockNumberResponse::new(self.get_block_info().block_number);
+        self.write_syscall_response(&response, vm, syscall_ptr)
     }
 
     pub(crate) fn get_tx_info(
@@ -739,7 +770,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         let tx_info = self.syscall_get_tx_info_ptr(vm)?;
 
         let response = DeprecatedGetTxInfoResponse::new(tx_info);
-        response.write_syscall_response(vm, syscall_ptr)
+        self.write_syscall_response(&response, vm, syscall_ptr)
     }
 
     pub(crate) fn get_tx_signature(
@@ -762,7 +793,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         let response =
             DeprecatedGetTxSignatureResponse::new(tx_info.signature, tx_info.signature_len);
 
-        response.write_syscall_response(vm, syscall_ptr)
+        self.write_syscall_response(&response, vm, syscall_ptr)
     }
 
     pub(crate) fn get_block_timestamp(
@@ -784,7 +815,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
 
         let response = DeprecatedGetBlockTimestampResponse::new(block_timestamp);
 
-        response.write_syscall_response(vm, syscall_ptr)
+        self.write_syscall_response(&response, vm, syscall_ptr)
     }
 
     pub(crate) fn get_caller_address(
@@ -794,7 +825,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
     ) -> Result<(), SyscallHandlerError> {
         let caller_address = self.syscall_get_caller_address(vm, syscall_ptr)?;
         let response = DeprecatedGetCallerAddressResponse::new(caller_address);
-        response.write_syscall_response(vm, syscall_ptr)
+        self.write_syscall_response(&response, vm, syscall_ptr)
     }
 
     pub(crate) fn get_contract_address(
@@ -804,7 +835,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
     ) -> Result<(), SyscallHandlerError> {
         let contract_address = self.syscall_get_contract_address(vm, syscall_ptr)?;
         let response = DeprecatedGetContractAddressResponse::new(contract_address);
-        response.write_syscall_response(vm, syscall_ptr)
+        self.write_syscall_response(&response, vm, syscall_ptr)
     }
 
     pub(crate) fn get_sequencer_address(
@@ -822,11 +853,11 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
             }
         };
 
-        let sequencer_address = self.get_block_info().sequencer_address.clone();
+        let sequencer_address = self.get_block_info().sequencer_address;
 
         let response = DeprecatedGetSequencerAddressResponse::new(sequencer_address);
 
-        response.write_syscall_response(vm, syscall_ptr)
+        self.write_syscall_response(&response, vm, syscall_ptr)
     }
 
     pub(crate) fn library_call(
@@ -923,11 +954,33 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
     ) -> Result<DeprecatedSyscallRequest, SyscallHandlerError> {
         self.increment_syscall_count(syscall_name);
         let syscall_request = self.read_syscall_request(syscall_name, vm, syscall_ptr)?;
+        self.syscall_tracer.begin(
+            syscall_name,
+            || {
+                trace_deprecated_request(vm, &syscall_request)
+                    .unwrap_or_else(|_| format!("{syscall_request:?}"))
+            },
+            None,
+            self.internal_calls.len(),
+        );
 
         self.expected_syscall_ptr.offset += get_deprecated_syscall_size_from_name(syscall_name);
         Ok(syscall_request)
     }
 
+    /// Finishes recording the syscall made by the current hint, if any.
+    pub(crate) fn end_syscall_trace(&mut self, result: &Result<(), SyscallHandlerError>) {
+        let response = self.syscall_tracer.take_response();
+        self.syscall_tracer.end(
+            || match result {
+                Ok(()) => SyscallOutcome::Success(response.unwrap_or_default()),
+                Err(err) => SyscallOutcome::Error(err.to_string()),
+            },
+            None,
+            self.internal_calls.len(),
+        );
+    }
+
     pub(crate) fn read_syscall_request(
         &self,
         syscall_name: &str,
@@ -1004,6 +1057,47 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
     }
 }
 
+/// Formats a syscall request for the syscall trace, reading the arrays it points to so that it
+/// matches the request recorded by the Cairo 1 handlers.
+fn trace_deprecated_request(
+    vm: &VirtualMachine,
+    request: &DeprecatedSyscallRequest,
+) -> Result<String, SyscallHandlerError> {
+    Ok(match request {
+        DeprecatedSyscallRequest::EmitEvent(request) => trace_request::emit_event(
+            &get_integer_range(vm, request.keys, request.keys_len)?,
+            &get_integer_range(vm, request.data, request.data_len)?,
+        ),
+        DeprecatedSyscallRequest::CallContract(request) => trace_request::call_contract(
+            &request.contract_address.0,
+            &request.function_selector,
+            &get_integer_range(vm, request.calldata, request.calldata_size)?,
+        ),
+        DeprecatedSyscallRequest::LibraryCall(request) => trace_request::library_call(
+            &request.class_hash,
+            &request.function_selector,
+            &get_integer_range(vm, request.calldata, request.calldata_size)?,
+        ),
+        DeprecatedSyscallRequest::Deploy(request) => trace_request::deploy(
+            &request.class_hash,
+            &request.contract_address_salt,
+            &get_integer_range(
+                vm,
+                request.constructor_calldata,
+                request.constructor_calldata_size.to_usize().ok_or(
+                    SyscallHandlerError::Conversion("Felt252".to_string(), "usize".to_string()),
+                )?,
+            )?,
+            request.deploy_from_zero != 0,
+        ),
+        DeprecatedSyscallRequest::SendMessageToL1(request) => trace_request::send_message_to_l1(
+            &request.to_address.0,
+            &get_integer_range(vm, request.payload_ptr, request.payload_size)?,
+        ),
+        request => format!("{request:?}"),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
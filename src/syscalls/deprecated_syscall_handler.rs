@@ -3,6 +3,7 @@ use super::{
     other_syscalls, syscall_handler::HintProcessorPostRun,
 };
 use crate::{
+    execution::prepared_program::{DeprecatedCompiledHint, DeprecatedCompiledHints},
     state::{contract_class_cache::ContractClassCache, state_api::StateReader},
     syscalls::syscall_handler_errors::SyscallHandlerError,
 };
@@ -18,18 +19,29 @@ use cairo_vm::{
     serde::deserialize_program::ApTracking,
     types::{exec_scope::ExecutionScopes, relocatable::Relocatable},
     vm::{
-        errors::hint_errors::HintError,
+        errors::{hint_errors::HintError, vm_errors::VirtualMachineError},
         runners::cairo_runner::{ResourceTracker, RunResources},
         vm_core::VirtualMachine,
     },
 };
-use std::{any::Any, collections::HashMap};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    mem,
+};
 
 /// Definition of the deprecated syscall hint processor with associated structs
 pub(crate) struct DeprecatedSyscallHintProcessor<'a, S: StateReader, C: ContractClassCache> {
     pub(crate) builtin_hint_processor: BuiltinHintProcessor,
     pub(crate) syscall_handler: DeprecatedBLSyscallHandler<'a, S, C>,
     run_resources: RunResources,
+    /// The hints compiled by a previous run of the program, in compilation order.
+    compiled_hints: DeprecatedCompiledHints,
+    /// The index of the next hint to be compiled.
+    next_hint: Cell<usize>,
+    /// The hints compiled by this run which weren't among the compiled hints.
+    new_compiled_hints: RefCell<Vec<DeprecatedCompiledHint>>,
 }
 
 /// Implementations and methods for DeprecatedSyscallHintProcessor
@@ -43,9 +55,25 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedSyscallHintProcessor<'
             builtin_hint_processor: BuiltinHintProcessor::new_empty(),
             syscall_handler,
             run_resources,
+            compiled_hints: Default::default(),
+            next_hint: Cell::new(0),
+            new_compiled_hints: RefCell::new(Vec::new()),
         }
     }
 
+    /// Reuses the hints compiled by a previous run of the program.
+    pub fn with_compiled_hints(self, compiled_hints: DeprecatedCompiledHints) -> Self {
+        Self {
+            compiled_hints,
+            ..self
+        }
+    }
+
+    /// Takes the hints compiled by this run, so that they can be reused by later runs.
+    pub fn take_new_compiled_hints(&mut self) -> Vec<DeprecatedCompiledHint> {
+        mem::take(self.new_compiled_hints.get_mut())
+    }
+
     /// Method to determine if a syscall hint should be run
     pub fn should_run_syscall_hint(
         &mut self,
@@ -77,7 +105,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedSyscallHintProcessor<'
             .downcast_ref::<HintProcessorData>()
             .ok_or(SyscallHandlerError::WrongHintData)?;
 
-        match hint_data.code.as_str() {
+        let result = match hint_data.code.as_str() {
             ADDR_BOUND_PRIME => other_syscalls::addr_bound_prime(vm, hint_data, constants),
             ADDR_IS_250 => other_syscalls::addr_is_250(vm, hint_data),
             DEPLOY => {
@@ -189,7 +217,10 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedSyscallHintProcessor<'
                 self.syscall_handler.replace_class(vm, syscall_ptr)
             }
             _ => Err(SyscallHandlerError::NotImplemented(hint_data.code.clone())),
-        }
+        };
+
+        self.syscall_handler.end_syscall_trace(&result);
+        result
     }
 }
 
@@ -217,6 +248,38 @@ impl<'a, S: StateReader, C: ContractClassCache> HintProcessorLogic
         }
         Ok(())
     }
+
+    /// Compiles the received hint, reusing its compiled form from a previous run if available
+    fn compile_hint(
+        &self,
+        hint_code: &str,
+        ap_tracking_data: &ApTracking,
+        reference_ids: &HashMap<String, usize>,
+        references: &[HintReference],
+    ) -> Result<Box<dyn Any>, VirtualMachineError> {
+        // The VM compiles the hints of a program in the same order on every run.
+        let index = self.next_hint.replace(self.next_hint.get() + 1);
+        if let Some(compiled_hint) = self
+            .compiled_hints
+            .get(index)
+            .filter(|hint| hint.matches(hint_code, ap_tracking_data, reference_ids))
+        {
+            return Ok(Box::new(compiled_hint.to_hint_processor_data()));
+        }
+
+        let hint_data = self.builtin_hint_processor.compile_hint(
+            hint_code,
+            ap_tracking_data,
+            reference_ids,
+            references,
+        )?;
+        if let Some(hint_data) = hint_data.downcast_ref::<HintProcessorData>() {
+            self.new_compiled_hints
+                .borrow_mut()
+                .push(DeprecatedCompiledHint::new(hint_data, reference_ids));
+        }
+        Ok(hint_data)
+    }
 }
 
 /// Implement the ResourceTracker trait for DeprecatedSyscallHintProcessor
@@ -286,9 +349,13 @@ mod tests {
             cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
             in_memory_state_reader::InMemoryStateReader, state_api::State,
         },
-        syscalls::deprecated_syscall_request::{
-            DeprecatedDeployRequest, DeprecatedSendMessageToL1SysCallRequest,
-            DeprecatedSyscallRequest,
+        syscalls::{
+            cheatcodes::CheatTarget,
+            deprecated_syscall_request::{
+                DeprecatedDeployRequest, DeprecatedGetBlockTimestampRequest,
+                DeprecatedSendMessageToL1SysCallRequest, DeprecatedSyscallRequest,
+            },
+            syscall_trace::{SyscallInvocation, SyscallOutcome, SyscallTracer},
         },
         transaction::InvokeFunction,
         utils::{
@@ -407,6 +474,86 @@ mod tests {
         );
     }
 
+    /// Test checks that the get block timestamp syscall returns the warped timestamp.
+    #[test]
+    fn get_block_timestamp_with_warp_cheatcode() {
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+
+        memory_insert!(vm, [((1, 0), (1, 1)), ((1, 1), 18)]);
+
+        let ids_data = ids_data!["syscall_ptr"];
+
+        let hint_data = HintProcessorData::new_default(GET_BLOCK_TIMESTAMP.to_string(), ids_data);
+
+        // invoke syscall
+        let mut state = CachedState::<InMemoryStateReader, PermanentContractClassCache>::default();
+        let mut syscall = DeprecatedBLSyscallHandler::default_with(&mut state);
+        let contract_address = syscall.contract_address.clone();
+        syscall
+            .block_context
+            .cheatcodes_mut()
+            .warp(CheatTarget::One(contract_address), 1234);
+        let mut syscall_handler = SyscallHintProcessor::new(syscall, RunResources::default());
+        syscall_handler
+            .execute_hint(
+                &mut vm,
+                &mut ExecutionScopes::new(),
+                &any_box!(hint_data),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+        assert_eq!(get_big_int(&vm, relocatable!(1, 2)).unwrap(), 1234.into());
+    }
+
+    #[test]
+    fn get_block_timestamp_is_traced() {
+        let mut vm = vm!();
+        add_segments!(vm, 2);
+
+        memory_insert!(vm, [((1, 0), (1, 1)), ((1, 1), 18)]);
+
+        let ids_data = ids_data!["syscall_ptr"];
+
+        let hint_data = HintProcessorData::new_default(GET_BLOCK_TIMESTAMP.to_string(), ids_data);
+
+        // invoke syscall
+        let mut state = CachedState::<InMemoryStateReader, PermanentContractClassCache>::default();
+        let mut syscall = DeprecatedBLSyscallHandler::default_with(&mut state);
+        syscall.syscall_tracer = SyscallTracer::new(true);
+        let mut syscall_handler = SyscallHintProcessor::new(syscall, RunResources::default());
+        syscall_handler
+            .execute_hint(
+                &mut vm,
+                &mut ExecutionScopes::new(),
+                &any_box!(hint_data),
+                &HashMap::new(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            syscall_handler.syscall_handler.syscall_tracer.into_trace(),
+            Some(vec![SyscallInvocation {
+                name: "get_block_timestamp".to_string(),
+                request: format!(
+                    "{:?}",
+                    DeprecatedSyscallRequest::GetBlockTimestamp(
+                        DeprecatedGetBlockTimestampRequest {
+                            selector: 18.into()
+                        }
+                    )
+                ),
+                gas_before: None,
+                gas_after: None,
+                outcome: SyscallOutcome::Success(
+                    "DeprecatedGetBlockTimestampResponse { block_timestamp: 0 }".to_string()
+                ),
+                internal_call: None,
+            }])
+        );
+    }
+
     /// Test checks the get sequencer address for business logic.
     #[test]
     fn get_sequencer_address_for_business_logic() {
@@ -475,10 +622,9 @@ mod tests {
 
         // invoke syscall
         let mut state = CachedState::<InMemoryStateReader, PermanentContractClassCache>::default();
-        let mut syscall_handler = SyscallHintProcessor::new(
-            DeprecatedBLSyscallHandler::default_with(&mut state),
-            RunResources::default(),
-        );
+        let mut syscall = DeprecatedBLSyscallHandler::default_with(&mut state);
+        syscall.syscall_tracer = SyscallTracer::new(true);
+        let mut syscall_handler = SyscallHintProcessor::new(syscall, RunResources::default());
         syscall_handler
             .execute_hint(
                 &mut vm,
@@ -510,6 +656,20 @@ mod tests {
                 .n_emitted_events,
             1
         );
+        // The trace records the keys and data, not the pointers to them.
+        let trace = syscall_handler
+            .syscall_handler
+            .syscall_tracer
+            .into_trace()
+            .unwrap();
+        assert_eq!(
+            trace[0].request,
+            format!(
+                "keys: {:?}, data: {:?}",
+                [Felt252::from(1), Felt252::from(1)],
+                [Felt252::from(1), Felt252::from(1)]
+            )
+        );
     }
 
     /// Test checks the get transaction information for business logic.
@@ -549,6 +709,7 @@ mod tests {
             nonce: 263.into(),
             n_sent_messages: 52,
             _n_steps: 100000,
+            extended_tx_info: Default::default(),
         };
         syscall_handler_hint_processor
             .syscall_handler
@@ -882,6 +1043,7 @@ mod tests {
             nonce: 263.into(),
             n_sent_messages: 52,
             _n_steps: 10000,
+            extended_tx_info: Default::default(),
         };
         syscall_handler_hint_processor
             .syscall_handler
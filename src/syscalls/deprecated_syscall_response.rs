@@ -49,6 +49,7 @@ pub(crate) struct DeprecatedGetBlockTimestampResponse {
     block_timestamp: u64,
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub(crate) struct DeprecatedGetTxSignatureResponse {
     signature_len: usize,
     signature: Relocatable,
@@ -337,7 +338,7 @@ mod tests {
             Arc::new(InMemoryStateReader::default()),
             Arc::new(PermanentContractClassCache::default()),
         );
-        let syscall = DeprecatedBLSyscallHandler::default_with(&mut state);
+        let mut syscall = DeprecatedBLSyscallHandler::default_with(&mut state);
         let mut vm = vm!();
 
         // Write the response of get_caller_address into the VM's memory
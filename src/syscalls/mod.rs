@@ -1,4 +1,6 @@
 pub mod business_logic_syscall_handler;
+pub mod cheatcodes;
+pub mod custom_syscalls;
 pub mod deprecated_business_logic_syscall_handler;
 pub mod deprecated_syscall_handler;
 pub mod deprecated_syscall_request;
@@ -7,8 +9,10 @@ pub mod hint_code;
 #[cfg(feature = "cairo-native")]
 pub mod native_syscall_handler;
 pub mod other_syscalls;
+pub(crate) mod secp;
 pub mod syscall_handler;
 pub mod syscall_handler_errors;
 pub mod syscall_info;
 pub mod syscall_request;
 pub mod syscall_response;
+pub mod syscall_trace;
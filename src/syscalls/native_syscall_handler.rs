@@ -1,11 +1,17 @@
 use crate::ContractClassCache;
 use std::{cell::RefCell, rc::Rc};
 
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::PrimeField;
 use cairo_native::{
     cache::ProgramCache,
-    starknet::{BlockInfo, ExecutionInfo, StarkNetSyscallHandler, SyscallResult, TxInfo, U256},
+    starknet::{
+        BlockInfo, ExecutionInfo, Secp256k1Point, StarkNetSyscallHandler, SyscallResult, TxInfo,
+        U256,
+    },
 };
 use cairo_vm::felt::Felt252;
+use num_bigint::BigUint;
 use num_traits::Zero;
 use starknet::core::utils::cairo_short_string_to_felt;
 
@@ -15,9 +21,10 @@ use crate::hash_utils::calculate_contract_address;
 use crate::services::api::contract_class_errors::ContractClassError;
 use crate::services::api::contract_classes::compiled_class::CompiledClass;
 use crate::state::state_api::State;
-use crate::syscalls::business_logic_syscall_handler::KECCAK_ROUND_COST;
-use crate::utils::felt_to_hash;
+use crate::syscalls::secp::{self, INVALID_ARGUMENT};
+use crate::syscalls::syscall_trace::{request, SyscallInvocation, SyscallOutcome, SyscallTracer};
 use crate::utils::ClassHash;
+use crate::utils::{felt_to_hash, sha256_compress, SHA256_BLOCK_SIZE, SHA256_STATE_SIZE};
 use crate::{
     core::errors::state_errors::StateError,
     definitions::block_context::BlockContext,
@@ -29,7 +36,6 @@ use crate::{
         contract_storage_state::ContractStorageState, state_api::StateReader,
         ExecutionResourcesManager,
     },
-    syscalls::business_logic_syscall_handler::{SYSCALL_BASE, SYSCALL_GAS_COST},
     syscalls::syscall_handler_errors::SyscallHandlerError,
     transaction::error::TransactionError,
     utils::Address,
@@ -48,19 +54,24 @@ where
     pub(crate) entry_point_selector: Felt252,
     pub(crate) events: Vec<OrderedEvent>,
     pub(crate) l2_to_l1_messages: Vec<OrderedL2ToL1Message>,
-    pub(crate) resources_manager: ExecutionResourcesManager,
+    // Some syscalls take `&self`, so counting them needs interior mutability.
+    pub(crate) resources_manager: RefCell<ExecutionResourcesManager>,
     pub(crate) tx_execution_context: TransactionExecutionContext,
     pub(crate) block_context: BlockContext,
     pub(crate) internal_calls: Vec<CallInfo>,
     pub(crate) program_cache: Rc<RefCell<ProgramCache<'cache, ClassHash>>>,
+    /// Error which aborts the execution, as it would in the VM, instead of being returned to the
+    /// contract as a syscall failure.
+    pub(crate) unrecoverable_error: Option<TransactionError>,
 }
 
 impl<'a, 'cache, S: StateReader, C: ContractClassCache> NativeSyscallHandler<'a, 'cache, S, C> {
     /// Generic code that needs to be run on all syscalls.
-    fn handle_syscall_request(&mut self, gas: &mut u128, syscall_name: &str) -> SyscallResult<()> {
-        let required_gas = SYSCALL_GAS_COST
-            .get(syscall_name)
-            .map(|&x| x.saturating_sub(SYSCALL_BASE))
+    fn handle_syscall_request(&self, gas: &mut u128, syscall_name: &str) -> SyscallResult<()> {
+        let required_gas = self
+            .block_context
+            .versioned_constants()
+            .required_syscall_gas(syscall_name)
             .unwrap_or(0);
 
         if *gas < required_gas {
@@ -72,10 +83,80 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> NativeSyscallHandler<'a,
         *gas = gas.saturating_sub(required_gas);
 
         self.resources_manager
+            .borrow_mut()
             .increment_syscall_counter(syscall_name, 1);
 
         Ok(())
     }
+
+    /// Records `error` to abort the execution once the contract returns, and fails the syscall
+    /// so that the contract stops.
+    fn abort<T>(&mut self, error: impl Into<TransactionError>) -> SyscallResult<T> {
+        let error = error.into();
+        let reason = Vec::from(TransactionError::CustomError(error.to_string()));
+        self.unrecoverable_error.get_or_insert(error);
+        Err(reason)
+    }
+
+    /// Applies the SHA-256 compression function to `state` with `block`, as the
+    /// `sha256_process_block` syscall does. The pinned Cairo Native version doesn't dispatch this
+    /// syscall through [`StarkNetSyscallHandler`] yet, so it's exposed as an inherent method.
+    /// As in the VM, it's only available if the versioned constants define its gas cost.
+    pub fn sha256_process_block(
+        &mut self,
+        state: &mut [u32; SHA256_STATE_SIZE],
+        block: &[u32; SHA256_BLOCK_SIZE],
+        gas: &mut u128,
+    ) -> SyscallResult<()> {
+        tracing::debug!("Called `sha256_process_block({state:?}, {block:?})` from Cairo Native");
+
+        if self
+            .block_context
+            .versioned_constants()
+            .syscall_gas_cost("sha256_process_block")
+            .is_none()
+        {
+            return self.abort(SyscallHandlerError::SelectorDoesNotHaveAssociatedGas(
+                "sha256_process_block".to_string(),
+            ));
+        }
+        self.handle_syscall_request(gas, "sha256_process_block")?;
+        sha256_compress(state, block);
+
+        Ok(())
+    }
+}
+
+// Cairo Native's u256 is stored as little endian bytes.
+fn u256_to_biguint(value: &U256) -> BigUint {
+    BigUint::from_bytes_le(&value.0)
+}
+
+fn biguint_to_u256(value: &BigUint) -> U256 {
+    let mut bytes = value.to_bytes_le();
+    bytes.resize(32, 0);
+    U256(bytes.try_into().unwrap())
+}
+
+fn native_point_to_affine<P: SWCurveConfig>(point: &Secp256k1Point) -> SyscallResult<Affine<P>>
+where
+    P::BaseField: PrimeField,
+{
+    secp::new_point::<P>(&u256_to_biguint(&point.x), &u256_to_biguint(&point.y))
+        .ok()
+        .flatten()
+        .ok_or_else(|| vec![Felt252::from_bytes_be(INVALID_ARGUMENT)])
+}
+
+fn affine_to_native_point<P: SWCurveConfig>(point: &Affine<P>) -> Secp256k1Point
+where
+    P::BaseField: PrimeField,
+{
+    let (x, y) = secp::get_xy(point);
+    Secp256k1Point {
+        x: biguint_to_u256(&x),
+        y: biguint_to_u256(&y),
+    }
 }
 
 impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
@@ -111,26 +192,35 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
 
         self.handle_syscall_request(gas, "get_execution_info")?;
 
+        let cheatcodes = &self.block_context.cheatcodes;
+        let block_info =
+            cheatcodes.block_info(&self.contract_address, &self.block_context.block_info);
+        let (tx_info, chain_id) = cheatcodes.tx_info(
+            &self.contract_address,
+            &self.tx_execution_context,
+            &self.block_context.starknet_os_config.chain_id,
+        );
+        let caller_address =
+            cheatcodes.caller_address(&self.contract_address, &self.caller_address);
+
         Ok(ExecutionInfo {
             block_info: BlockInfo {
-                block_number: self.block_context.block_info.block_number,
-                block_timestamp: self.block_context.block_info.block_timestamp,
-                sequencer_address: self.block_context.block_info.sequencer_address.0.clone(),
+                block_number: block_info.block_number,
+                block_timestamp: block_info.block_timestamp,
+                sequencer_address: block_info.sequencer_address.0,
             },
+            // The pinned Cairo Native only has the legacy tx info layout, so contracts run natively
+            // can't read the extended tx info (resource bounds, tip, paymaster data, ...).
             tx_info: TxInfo {
-                version: self.tx_execution_context.version.clone(),
-                account_contract_address: self
-                    .tx_execution_context
-                    .account_contract_address
-                    .0
-                    .clone(),
-                max_fee: self.tx_execution_context.max_fee,
-                signature: self.tx_execution_context.signature.clone(),
-                transaction_hash: self.tx_execution_context.transaction_hash.clone(),
-                chain_id: self.block_context.starknet_os_config.chain_id.clone(),
-                nonce: self.tx_execution_context.nonce.clone(),
+                version: tx_info.version,
+                account_contract_address: tx_info.account_contract_address.0,
+                max_fee: tx_info.max_fee,
+                signature: tx_info.signature,
+                transaction_hash: tx_info.transaction_hash,
+                chain_id,
+                nonce: tx_info.nonce,
             },
-            caller_address: self.caller_address.0.clone(),
+            caller_address: caller_address.0,
             contract_address: self.contract_address.0.clone(),
             entry_point_selector: self.entry_point_selector.clone(),
         })
@@ -236,19 +326,32 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
             *gas,
         );
 
+        let mocked_call_info = self
+            .block_context
+            .cheatcodes
+            .mocked_call_info(&execution_entry_point);
         let ExecutionResult {
             call_info,
             revert_error,
             ..
-        } = execution_entry_point.execute(
-            self.starknet_storage_state.state,
-            &self.block_context,
-            &mut self.resources_manager,
-            &mut self.tx_execution_context,
-            false,
-            self.block_context.invoke_tx_max_n_steps,
-            Some(self.program_cache.clone()),
-        )?;
+        } = match mocked_call_info {
+            Some(call_info) => ExecutionResult {
+                call_info: Some(call_info),
+                ..Default::default()
+            },
+            None => match execution_entry_point.execute(
+                self.starknet_storage_state.state,
+                &self.block_context,
+                self.resources_manager.get_mut(),
+                &mut self.tx_execution_context,
+                false,
+                self.block_context.invoke_tx_max_n_steps(),
+                Some(self.program_cache.clone()),
+            ) {
+                Ok(result) => result,
+                Err(err) => return self.abort(err),
+            },
+        };
 
         let call_info = call_info.ok_or(SyscallHandlerError::ExecutionError(
             revert_error.unwrap_or_else(|| "Execution error".to_string()),
@@ -301,19 +404,30 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
             *gas,
         );
 
-        let ExecutionResult { call_info, .. } = exec_entry_point
-            .execute(
+        let mocked_call_info = self
+            .block_context
+            .cheatcodes
+            .mocked_call_info(&exec_entry_point);
+        let ExecutionResult { call_info, .. } = match mocked_call_info {
+            Some(call_info) => ExecutionResult {
+                call_info: Some(call_info),
+                ..Default::default()
+            },
+            None => match exec_entry_point.execute(
                 self.starknet_storage_state.state,
                 // TODO: This fields dont make much sense in the Cairo Native context,
                 // they are only dummy values for the `execute` method.
                 &self.block_context,
-                &mut self.resources_manager,
+                self.resources_manager.get_mut(),
                 &mut self.tx_execution_context,
                 false,
-                self.block_context.invoke_tx_max_n_steps,
+                self.block_context.invoke_tx_max_n_steps(),
                 Some(self.program_cache.clone()),
-            )
-            .unwrap();
+            ) {
+                Ok(result) => result,
+                Err(err) => return self.abort(err),
+            },
+        };
 
         let call_info = call_info.unwrap();
 
@@ -327,10 +441,15 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
             .accessed_keys
             .extend(call_info.accessed_storage_keys.clone());
 
+        let failure_flag = call_info.failure_flag;
         let retdata = call_info.retdata.clone();
         self.internal_calls.push(call_info);
 
-        Ok(retdata)
+        if failure_flag {
+            Err(retdata)
+        } else {
+            Ok(retdata)
+        }
     }
 
     fn storage_read(
@@ -377,6 +496,14 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
         let order = self.tx_execution_context.n_emitted_events;
         tracing::debug!("Called `emit_event(KEYS: {keys:?}, DATA: {data:?})` from Cairo Native");
 
+        if let Err(err) =
+            self.block_context
+                .versioned_constants
+                .event_limits
+                .check(keys.len(), data.len(), order)
+        {
+            return self.abort(err);
+        }
         self.handle_syscall_request(gas, "emit_event")?;
 
         self.events
@@ -427,14 +554,18 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
 
         let n_chunks = length / 17;
         let mut state = [0u64; 25];
+        let keccak_round_cost = self
+            .block_context
+            .versioned_constants()
+            .keccak_round_cost_gas_cost();
 
         for i in 0..n_chunks {
-            if *gas < KECCAK_ROUND_COST {
+            if *gas < keccak_round_cost {
                 let error_msg = b"Syscall out of gas";
                 let felt_error = Felt252::from_bytes_be(error_msg);
                 return Err(vec![felt_error]);
             }
-            *gas -= KECCAK_ROUND_COST;
+            *gas -= keccak_round_cost;
             let chunk = &input[i * 17..(i + 1) * 17]; //(request.input_start + i * 17)?;
             for (i, val) in chunk.iter().enumerate() {
                 state[i] ^= val;
@@ -456,90 +587,154 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
 
     fn secp256k1_add(
         &mut self,
-        _p0: cairo_native::starknet::Secp256k1Point,
-        _p1: cairo_native::starknet::Secp256k1Point,
-        _gas: &mut u128,
+        p0: cairo_native::starknet::Secp256k1Point,
+        p1: cairo_native::starknet::Secp256k1Point,
+        gas: &mut u128,
     ) -> SyscallResult<Option<cairo_native::starknet::Secp256k1Point>> {
-        todo!()
+        tracing::debug!("Called `secp256k1_add({p0:?}, {p1:?})` from Cairo Native");
+
+        self.handle_syscall_request(gas, "secp256k1_add")?;
+
+        let p0 = native_point_to_affine::<ark_secp256k1::Config>(&p0)?;
+        let p1 = native_point_to_affine::<ark_secp256k1::Config>(&p1)?;
+        Ok(Some(affine_to_native_point(&secp::add(&p0, &p1))))
     }
 
     fn secp256k1_get_point_from_x(
         &self,
-        _x: cairo_native::starknet::U256,
-        _y_parity: bool,
-        _gas: &mut u128,
+        x: cairo_native::starknet::U256,
+        y_parity: bool,
+        gas: &mut u128,
     ) -> SyscallResult<Option<cairo_native::starknet::Secp256k1Point>> {
-        todo!()
+        tracing::debug!("Called `secp256k1_get_point_from_x({x:?}, {y_parity})` from Cairo Native");
+
+        self.handle_syscall_request(gas, "secp256k1_get_point_from_x")?;
+
+        secp::get_point_from_x::<ark_secp256k1::Config>(&u256_to_biguint(&x), y_parity)
+            .map(|point| point.as_ref().map(affine_to_native_point))
+            .map_err(|_| vec![Felt252::from_bytes_be(INVALID_ARGUMENT)])
     }
 
     fn secp256k1_get_xy(
         &self,
-        _p: cairo_native::starknet::Secp256k1Point,
-        _gas: &mut u128,
+        p: cairo_native::starknet::Secp256k1Point,
+        gas: &mut u128,
     ) -> SyscallResult<(cairo_native::starknet::U256, cairo_native::starknet::U256)> {
-        todo!()
+        tracing::debug!("Called `secp256k1_get_xy({p:?})` from Cairo Native");
+
+        self.handle_syscall_request(gas, "secp256k1_get_xy")?;
+
+        let point = native_point_to_affine::<ark_secp256k1::Config>(&p)?;
+        let (x, y) = secp::get_xy(&point);
+        Ok((biguint_to_u256(&x), biguint_to_u256(&y)))
     }
 
     fn secp256k1_mul(
         &self,
-        _p: cairo_native::starknet::Secp256k1Point,
-        _m: cairo_native::starknet::U256,
-        _gas: &mut u128,
+        p: cairo_native::starknet::Secp256k1Point,
+        m: cairo_native::starknet::U256,
+        gas: &mut u128,
     ) -> SyscallResult<Option<cairo_native::starknet::Secp256k1Point>> {
-        todo!()
+        tracing::debug!("Called `secp256k1_mul({p:?}, {m:?})` from Cairo Native");
+
+        self.handle_syscall_request(gas, "secp256k1_mul")?;
+
+        let point = native_point_to_affine::<ark_secp256k1::Config>(&p)?;
+        Ok(Some(affine_to_native_point(&secp::mul(
+            &point,
+            &u256_to_biguint(&m),
+        ))))
     }
 
     fn secp256k1_new(
         &self,
-        _x: cairo_native::starknet::U256,
-        _y: cairo_native::starknet::U256,
-        _gas: &mut u128,
+        x: cairo_native::starknet::U256,
+        y: cairo_native::starknet::U256,
+        gas: &mut u128,
     ) -> SyscallResult<Option<cairo_native::starknet::Secp256k1Point>> {
-        todo!()
+        tracing::debug!("Called `secp256k1_new({x:?}, {y:?})` from Cairo Native");
+
+        self.handle_syscall_request(gas, "secp256k1_new")?;
+
+        secp::new_point::<ark_secp256k1::Config>(&u256_to_biguint(&x), &u256_to_biguint(&y))
+            .map(|point| point.as_ref().map(affine_to_native_point))
+            .map_err(|_| vec![Felt252::from_bytes_be(INVALID_ARGUMENT)])
     }
 
     fn secp256r1_add(
         &self,
-        _p0: cairo_native::starknet::Secp256k1Point,
-        _p1: cairo_native::starknet::Secp256k1Point,
-        _gas: &mut u128,
+        p0: cairo_native::starknet::Secp256k1Point,
+        p1: cairo_native::starknet::Secp256k1Point,
+        gas: &mut u128,
     ) -> SyscallResult<Option<cairo_native::starknet::Secp256k1Point>> {
-        todo!()
+        tracing::debug!("Called `secp256r1_add({p0:?}, {p1:?})` from Cairo Native");
+
+        self.handle_syscall_request(gas, "secp256r1_add")?;
+
+        let p0 = native_point_to_affine::<ark_secp256r1::Config>(&p0)?;
+        let p1 = native_point_to_affine::<ark_secp256r1::Config>(&p1)?;
+        Ok(Some(affine_to_native_point(&secp::add(&p0, &p1))))
     }
 
     fn secp256r1_get_point_from_x(
         &self,
-        _x: cairo_native::starknet::U256,
-        _y_parity: bool,
-        _gas: &mut u128,
+        x: cairo_native::starknet::U256,
+        y_parity: bool,
+        gas: &mut u128,
     ) -> SyscallResult<Option<cairo_native::starknet::Secp256k1Point>> {
-        todo!()
+        tracing::debug!("Called `secp256r1_get_point_from_x({x:?}, {y_parity})` from Cairo Native");
+
+        self.handle_syscall_request(gas, "secp256r1_get_point_from_x")?;
+
+        secp::get_point_from_x::<ark_secp256r1::Config>(&u256_to_biguint(&x), y_parity)
+            .map(|point| point.as_ref().map(affine_to_native_point))
+            .map_err(|_| vec![Felt252::from_bytes_be(INVALID_ARGUMENT)])
     }
 
     fn secp256r1_get_xy(
         &self,
-        _p: cairo_native::starknet::Secp256k1Point,
-        _gas: &mut u128,
+        p: cairo_native::starknet::Secp256k1Point,
+        gas: &mut u128,
     ) -> SyscallResult<(cairo_native::starknet::U256, cairo_native::starknet::U256)> {
-        todo!()
+        tracing::debug!("Called `secp256r1_get_xy({p:?})` from Cairo Native");
+
+        self.handle_syscall_request(gas, "secp256r1_get_xy")?;
+
+        let point = native_point_to_affine::<ark_secp256r1::Config>(&p)?;
+        let (x, y) = secp::get_xy(&point);
+        Ok((biguint_to_u256(&x), biguint_to_u256(&y)))
     }
 
     fn secp256r1_mul(
         &self,
-        _p: cairo_native::starknet::Secp256k1Point,
-        _m: cairo_native::starknet::U256,
-        _gas: &mut u128,
+        p: cairo_native::starknet::Secp256k1Point,
+        m: cairo_native::starknet::U256,
+        gas: &mut u128,
     ) -> SyscallResult<Option<cairo_native::starknet::Secp256k1Point>> {
-        todo!()
+        tracing::debug!("Called `secp256r1_mul({p:?}, {m:?})` from Cairo Native");
+
+        self.handle_syscall_request(gas, "secp256r1_mul")?;
+
+        let point = native_point_to_affine::<ark_secp256r1::Config>(&p)?;
+        Ok(Some(affine_to_native_point(&secp::mul(
+            &point,
+            &u256_to_biguint(&m),
+        ))))
     }
 
     fn secp256r1_new(
         &mut self,
-        _x: cairo_native::starknet::U256,
-        _y: cairo_native::starknet::U256,
-        _gas: &mut u128,
+        x: cairo_native::starknet::U256,
+        y: cairo_native::starknet::U256,
+        gas: &mut u128,
     ) -> SyscallResult<Option<cairo_native::starknet::Secp256k1Point>> {
-        todo!()
+        tracing::debug!("Called `secp256r1_new({x:?}, {y:?})` from Cairo Native");
+
+        self.handle_syscall_request(gas, "secp256r1_new")?;
+
+        secp::new_point::<ark_secp256r1::Config>(&u256_to_biguint(&x), &u256_to_biguint(&y))
+            .map(|point| point.as_ref().map(affine_to_native_point))
+            .map_err(|_| vec![Felt252::from_bytes_be(INVALID_ARGUMENT)])
     }
 
     fn pop_log(&mut self) {
@@ -651,7 +846,7 @@ where
             .execute(
                 self.starknet_storage_state.state,
                 &self.block_context,
-                &mut self.resources_manager,
+                self.resources_manager.get_mut(),
                 &mut self.tx_execution_context,
                 false,
                 u64::MAX,
@@ -755,3 +950,518 @@ impl From<SyscallHandlerError> for Vec<Felt252> {
         }
     }
 }
+
+/// Records the syscalls made through a [`NativeSyscallHandler`], if syscall tracing is enabled.
+#[derive(Debug)]
+pub struct TracingNativeSyscallHandler<'h, 'a, 'cache, S, C>
+where
+    S: StateReader,
+    C: ContractClassCache,
+{
+    handler: &'h mut NativeSyscallHandler<'a, 'cache, S, C>,
+    // Some syscalls take `&self`, so the tracer needs interior mutability.
+    tracer: RefCell<SyscallTracer>,
+}
+
+impl<'h, 'a, 'cache, S, C> TracingNativeSyscallHandler<'h, 'a, 'cache, S, C>
+where
+    S: StateReader,
+    C: ContractClassCache,
+{
+    pub fn new(handler: &'h mut NativeSyscallHandler<'a, 'cache, S, C>, enabled: bool) -> Self {
+        Self {
+            handler,
+            tracer: RefCell::new(SyscallTracer::new(enabled)),
+        }
+    }
+
+    /// Returns the recorded syscalls, or `None` if syscall tracing is disabled.
+    pub fn into_trace(self) -> Option<Vec<SyscallInvocation>> {
+        self.tracer.into_inner().into_trace()
+    }
+
+    fn begin(&self, syscall_name: &str, request: impl FnOnce() -> String, gas: u128) {
+        self.tracer.borrow_mut().begin(
+            syscall_name,
+            request,
+            Some(gas),
+            self.handler.internal_calls.len(),
+        );
+    }
+
+    /// Traces [`NativeSyscallHandler::sha256_process_block`].
+    pub fn sha256_process_block(
+        &mut self,
+        state: &mut [u32; SHA256_STATE_SIZE],
+        block: &[u32; SHA256_BLOCK_SIZE],
+        gas: &mut u128,
+    ) -> SyscallResult<()> {
+        self.begin(
+            "sha256_process_block",
+            || format!("state: {state:?}, block: {block:?}"),
+            *gas,
+        );
+        let result = self.handler.sha256_process_block(state, block, gas);
+        self.end(&result.clone().map(|()| *state), *gas);
+        result
+    }
+
+    fn end<T: std::fmt::Debug>(&self, result: &SyscallResult<T>, gas: u128) {
+        self.tracer.borrow_mut().end(
+            || match result {
+                Ok(response) => SyscallOutcome::Success(format!("{response:?}")),
+                Err(reason) => SyscallOutcome::Failure(reason.clone()),
+            },
+            Some(gas),
+            self.handler.internal_calls.len(),
+        );
+    }
+}
+
+impl<'h, 'a, 'cache, S, C> StarkNetSyscallHandler
+    for TracingNativeSyscallHandler<'h, 'a, 'cache, S, C>
+where
+    S: StateReader,
+    C: ContractClassCache,
+{
+    fn get_block_hash(&mut self, block_number: u64, gas: &mut u128) -> SyscallResult<Felt252> {
+        self.begin(
+            "get_block_hash",
+            || format!("block_number: {block_number}"),
+            *gas,
+        );
+        let result = self.handler.get_block_hash(block_number, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn get_execution_info(&mut self, gas: &mut u128) -> SyscallResult<ExecutionInfo> {
+        self.begin("get_execution_info", String::new, *gas);
+        let result = self.handler.get_execution_info(gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn deploy(
+        &mut self,
+        class_hash: Felt252,
+        contract_address_salt: Felt252,
+        calldata: &[Felt252],
+        deploy_from_zero: bool,
+        gas: &mut u128,
+    ) -> SyscallResult<(Felt252, Vec<Felt252>)> {
+        self.begin(
+            "deploy",
+            || {
+                request::deploy(
+                    &class_hash,
+                    &contract_address_salt,
+                    calldata,
+                    deploy_from_zero,
+                )
+            },
+            *gas,
+        );
+        let result = self.handler.deploy(
+            class_hash,
+            contract_address_salt,
+            calldata,
+            deploy_from_zero,
+            gas,
+        );
+        self.end(&result, *gas);
+        result
+    }
+
+    fn replace_class(&mut self, class_hash: Felt252, gas: &mut u128) -> SyscallResult<()> {
+        self.begin(
+            "replace_class",
+            || format!("class_hash: {class_hash}"),
+            *gas,
+        );
+        let result = self.handler.replace_class(class_hash, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn library_call(
+        &mut self,
+        class_hash: Felt252,
+        function_selector: Felt252,
+        calldata: &[Felt252],
+        gas: &mut u128,
+    ) -> SyscallResult<Vec<Felt252>> {
+        self.begin(
+            "library_call",
+            || request::library_call(&class_hash, &function_selector, calldata),
+            *gas,
+        );
+        let result = self
+            .handler
+            .library_call(class_hash, function_selector, calldata, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn call_contract(
+        &mut self,
+        address: Felt252,
+        entrypoint_selector: Felt252,
+        calldata: &[Felt252],
+        gas: &mut u128,
+    ) -> SyscallResult<Vec<Felt252>> {
+        self.begin(
+            "call_contract",
+            || request::call_contract(&address, &entrypoint_selector, calldata),
+            *gas,
+        );
+        let result = self
+            .handler
+            .call_contract(address, entrypoint_selector, calldata, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn storage_read(
+        &mut self,
+        address_domain: u32,
+        address: Felt252,
+        gas: &mut u128,
+    ) -> SyscallResult<Felt252> {
+        self.begin(
+            "storage_read",
+            || format!("address_domain: {address_domain}, address: {address}"),
+            *gas,
+        );
+        let result = self.handler.storage_read(address_domain, address, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn storage_write(
+        &mut self,
+        address_domain: u32,
+        address: Felt252,
+        value: Felt252,
+        gas: &mut u128,
+    ) -> SyscallResult<()> {
+        self.begin(
+            "storage_write",
+            || format!("address_domain: {address_domain}, address: {address}, value: {value}"),
+            *gas,
+        );
+        let result = self
+            .handler
+            .storage_write(address_domain, address, value, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn emit_event(
+        &mut self,
+        keys: &[Felt252],
+        data: &[Felt252],
+        gas: &mut u128,
+    ) -> SyscallResult<()> {
+        self.begin("emit_event", || request::emit_event(keys, data), *gas);
+        let result = self.handler.emit_event(keys, data, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn send_message_to_l1(
+        &mut self,
+        to_address: Felt252,
+        payload: &[Felt252],
+        gas: &mut u128,
+    ) -> SyscallResult<()> {
+        self.begin(
+            "send_message_to_l1",
+            || request::send_message_to_l1(&to_address, payload),
+            *gas,
+        );
+        let result = self.handler.send_message_to_l1(to_address, payload, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn keccak(&mut self, input: &[u64], gas: &mut u128) -> SyscallResult<U256> {
+        self.begin("keccak", || request::keccak(input), *gas);
+        let result = self.handler.keccak(input, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn secp256k1_add(
+        &mut self,
+        p0: Secp256k1Point,
+        p1: Secp256k1Point,
+        gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256k1Point>> {
+        self.begin("secp256k1_add", || format!("p0: {p0:?}, p1: {p1:?}"), *gas);
+        let result = self.handler.secp256k1_add(p0, p1, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn secp256k1_get_point_from_x(
+        &self,
+        x: U256,
+        y_parity: bool,
+        gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256k1Point>> {
+        self.begin(
+            "secp256k1_get_point_from_x",
+            || format!("x: {x:?}, y_parity: {y_parity}"),
+            *gas,
+        );
+        let result = self.handler.secp256k1_get_point_from_x(x, y_parity, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn secp256k1_get_xy(&self, p: Secp256k1Point, gas: &mut u128) -> SyscallResult<(U256, U256)> {
+        self.begin("secp256k1_get_xy", || format!("p: {p:?}"), *gas);
+        let result = self.handler.secp256k1_get_xy(p, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn secp256k1_mul(
+        &self,
+        p: Secp256k1Point,
+        m: U256,
+        gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256k1Point>> {
+        self.begin("secp256k1_mul", || format!("p: {p:?}, m: {m:?}"), *gas);
+        let result = self.handler.secp256k1_mul(p, m, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn secp256k1_new(
+        &self,
+        x: U256,
+        y: U256,
+        gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256k1Point>> {
+        self.begin("secp256k1_new", || format!("x: {x:?}, y: {y:?}"), *gas);
+        let result = self.handler.secp256k1_new(x, y, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn secp256r1_add(
+        &self,
+        p0: Secp256k1Point,
+        p1: Secp256k1Point,
+        gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256k1Point>> {
+        self.begin("secp256r1_add", || format!("p0: {p0:?}, p1: {p1:?}"), *gas);
+        let result = self.handler.secp256r1_add(p0, p1, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn secp256r1_get_point_from_x(
+        &self,
+        x: U256,
+        y_parity: bool,
+        gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256k1Point>> {
+        self.begin(
+            "secp256r1_get_point_from_x",
+            || format!("x: {x:?}, y_parity: {y_parity}"),
+            *gas,
+        );
+        let result = self.handler.secp256r1_get_point_from_x(x, y_parity, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn secp256r1_get_xy(&self, p: Secp256k1Point, gas: &mut u128) -> SyscallResult<(U256, U256)> {
+        self.begin("secp256r1_get_xy", || format!("p: {p:?}"), *gas);
+        let result = self.handler.secp256r1_get_xy(p, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn secp256r1_mul(
+        &self,
+        p: Secp256k1Point,
+        m: U256,
+        gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256k1Point>> {
+        self.begin("secp256r1_mul", || format!("p: {p:?}, m: {m:?}"), *gas);
+        let result = self.handler.secp256r1_mul(p, m, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn secp256r1_new(
+        &mut self,
+        x: U256,
+        y: U256,
+        gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256k1Point>> {
+        self.begin("secp256r1_new", || format!("x: {x:?}, y: {y:?}"), *gas);
+        let result = self.handler.secp256r1_new(x, y, gas);
+        self.end(&result, *gas);
+        result
+    }
+
+    fn pop_log(&mut self) {
+        self.handler.pop_log()
+    }
+
+    fn set_account_contract_address(&mut self, contract_address: Felt252) {
+        self.handler.set_account_contract_address(contract_address)
+    }
+
+    fn set_block_number(&mut self, block_number: u64) {
+        self.handler.set_block_number(block_number)
+    }
+
+    fn set_block_timestamp(&mut self, block_timestamp: u64) {
+        self.handler.set_block_timestamp(block_timestamp)
+    }
+
+    fn set_caller_address(&mut self, address: Felt252) {
+        self.handler.set_caller_address(address)
+    }
+
+    fn set_chain_id(&mut self, chain_id: Felt252) {
+        self.handler.set_chain_id(chain_id)
+    }
+
+    fn set_contract_address(&mut self, address: Felt252) {
+        self.handler.set_contract_address(address)
+    }
+
+    fn set_max_fee(&mut self, max_fee: u128) {
+        self.handler.set_max_fee(max_fee)
+    }
+
+    fn set_nonce(&mut self, nonce: Felt252) {
+        self.handler.set_nonce(nonce)
+    }
+
+    fn set_sequencer_address(&mut self, address: Felt252) {
+        self.handler.set_sequencer_address(address)
+    }
+
+    fn set_signature(&mut self, signature: &[Felt252]) {
+        self.handler.set_signature(signature)
+    }
+
+    fn set_transaction_hash(&mut self, transaction_hash: Felt252) {
+        self.handler.set_transaction_hash(transaction_hash)
+    }
+
+    fn set_version(&mut self, version: Felt252) {
+        self.handler.set_version(version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        definitions::versioned_constants::VersionedConstants,
+        state::{
+            cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
+            in_memory_state_reader::InMemoryStateReader,
+        },
+    };
+    use assert_matches::assert_matches;
+    use coverage_helper::test;
+    use std::sync::Arc;
+
+    fn syscall_handler<'a>(
+        state: &'a mut CachedState<InMemoryStateReader, PermanentContractClassCache>,
+        block_context: BlockContext,
+    ) -> NativeSyscallHandler<'a, 'static, InMemoryStateReader, PermanentContractClassCache> {
+        NativeSyscallHandler {
+            starknet_storage_state: ContractStorageState::new(state, Address(1.into())),
+            contract_address: Address(1.into()),
+            caller_address: Address(0.into()),
+            entry_point_selector: 0.into(),
+            events: Vec::new(),
+            l2_to_l1_messages: Vec::new(),
+            resources_manager: Default::default(),
+            tx_execution_context: Default::default(),
+            block_context,
+            internal_calls: Vec::new(),
+            program_cache: Rc::new(RefCell::new(ProgramCache::new(
+                crate::utils::get_native_context(),
+            ))),
+            unrecoverable_error: None,
+        }
+    }
+
+    #[test]
+    fn sha256_process_block_compresses_the_state() {
+        let mut state = CachedState::default();
+        let mut block_context = BlockContext::default();
+        // The syscall is only available from Starknet 0.13.2.
+        block_context.versioned_constants =
+            Arc::new(VersionedConstants::for_version("0.13.2").unwrap());
+        let mut handler = syscall_handler(&mut state, block_context);
+
+        // The initial state and the padded block of the message "abc".
+        let mut sha_state = [
+            0x6a09e667_u32,
+            0xbb67ae85,
+            0x3c6ef372,
+            0xa54ff53a,
+            0x510e527f,
+            0x9b05688c,
+            0x1f83d9ab,
+            0x5be0cd19,
+        ];
+        let mut block = [0_u32; SHA256_BLOCK_SIZE];
+        block[0] = 0x61626380;
+        block[15] = 0x18;
+        let mut gas = 1_000_000;
+        handler
+            .sha256_process_block(&mut sha_state, &block, &mut gas)
+            .unwrap();
+
+        assert_eq!(
+            sha_state,
+            [
+                0xba7816bf_u32,
+                0x8f01cfea,
+                0x414140de,
+                0x5dae2223,
+                0xb00361a3,
+                0x96177a9c,
+                0xb410ff61,
+                0xf20015ad,
+            ]
+        );
+        assert!(gas < 1_000_000);
+        assert_eq!(
+            handler.resources_manager.borrow().syscall_counter["sha256_process_block"],
+            1
+        );
+    }
+
+    #[test]
+    fn sha256_process_block_needs_a_gas_cost() {
+        let mut state = CachedState::default();
+        let mut handler = syscall_handler(&mut state, BlockContext::default());
+
+        let mut sha_state = [0_u32; SHA256_STATE_SIZE];
+        let mut gas = 1_000_000;
+        assert!(handler
+            .sha256_process_block(&mut sha_state, &[0; SHA256_BLOCK_SIZE], &mut gas)
+            .is_err());
+        assert_matches!(
+            handler.unrecoverable_error,
+            Some(TransactionError::Syscall(
+                SyscallHandlerError::SelectorDoesNotHaveAssociatedGas(_)
+            ))
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! # secp256k1 and secp256r1 syscalls
+//!
+//! Curve operations shared by the VM and the Cairo Native syscall handlers. The VM handler refers
+//! to points through handles, which are addresses in a segment owned by a [`SecpPointStore`].
+
+use super::syscall_handler_errors::SyscallHandlerError;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{BigInteger, PrimeField};
+use cairo_vm::{types::relocatable::Relocatable, vm::vm_core::VirtualMachine};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use std::fmt;
+
+/// Message of the failure returned when a coordinate isn't smaller than the field modulus.
+pub(crate) const INVALID_ARGUMENT: &[u8] = b"Invalid argument";
+
+/// A coordinate wasn't smaller than the modulus of the curve's base field.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct InvalidArgument;
+
+fn check_coordinate<C: SWCurveConfig>(value: &BigUint) -> Result<(), InvalidArgument>
+where
+    C::BaseField: PrimeField,
+{
+    let modulus: BigUint = C::BaseField::MODULUS.into();
+    if value < &modulus {
+        Ok(())
+    } else {
+        Err(InvalidArgument)
+    }
+}
+
+/// Creates the point `(x, y)`, or returns `None` if it isn't on the curve.
+/// `(0, 0)` represents the point at infinity.
+pub(crate) fn new_point<C: SWCurveConfig>(
+    x: &BigUint,
+    y: &BigUint,
+) -> Result<Option<Affine<C>>, InvalidArgument>
+where
+    C::BaseField: PrimeField,
+{
+    check_coordinate::<C>(x)?;
+    check_coordinate::<C>(y)?;
+
+    if x.is_zero() && y.is_zero() {
+        return Ok(Some(Affine::identity()));
+    }
+
+    let point = Affine::new_unchecked(C::BaseField::from(x.clone()), C::BaseField::from(y.clone()));
+    Ok((point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve()).then_some(point))
+}
+
+/// Returns the point with the given `x` coordinate and `y` parity, or `None` if there is no
+/// such point on the curve.
+pub(crate) fn get_point_from_x<C: SWCurveConfig>(
+    x: &BigUint,
+    y_parity: bool,
+) -> Result<Option<Affine<C>>, InvalidArgument>
+where
+    C::BaseField: PrimeField,
+{
+    check_coordinate::<C>(x)?;
+
+    let x = C::BaseField::from(x.clone());
+    Ok(Affine::<C>::get_ys_from_x_unchecked(x)
+        .map(|(smaller, greater)| {
+            if smaller.into_bigint().is_odd() == y_parity {
+                smaller
+            } else {
+                greater
+            }
+        })
+        .map(|y| Affine::new_unchecked(x, y))
+        .filter(|point| point.is_in_correct_subgroup_assuming_on_curve()))
+}
+
+/// Adds two points.
+pub(crate) fn add<C: SWCurveConfig>(p0: &Affine<C>, p1: &Affine<C>) -> Affine<C> {
+    (*p0 + *p1).into()
+}
+
+/// Multiplies a point by a scalar.
+pub(crate) fn mul<C: SWCurveConfig>(point: &Affine<C>, scalar: &BigUint) -> Affine<C> {
+    (*point * C::ScalarField::from(scalar.clone())).into()
+}
+
+/// Returns the coordinates of a point, `(0, 0)` being the point at infinity.
+pub(crate) fn get_xy<C: SWCurveConfig>(point: &Affine<C>) -> (BigUint, BigUint)
+where
+    C::BaseField: PrimeField,
+{
+    if point.infinity {
+        (BigUint::zero(), BigUint::zero())
+    } else {
+        (point.x.into(), point.y.into())
+    }
+}
+
+/// Stores the points created during the execution of a Cairo 1 contract in the VM, each one
+/// identified by an address (its handle) in a segment allocated on first use.
+pub(crate) struct SecpPointStore<C: SWCurveConfig> {
+    points: Vec<Affine<C>>,
+    segment: Option<Relocatable>,
+}
+
+impl<C: SWCurveConfig> SecpPointStore<C> {
+    /// Stores a point, returning its handle.
+    pub(crate) fn allocate(
+        &mut self,
+        vm: &mut VirtualMachine,
+        point: Affine<C>,
+    ) -> Result<Relocatable, SyscallHandlerError> {
+        let segment = *self.segment.get_or_insert_with(|| vm.add_memory_segment());
+        let handle = (segment + self.points.len())?;
+        self.points.push(point);
+
+        Ok(handle)
+    }
+
+    /// Returns the point identified by the handle.
+    pub(crate) fn get(&self, handle: Relocatable) -> Result<&Affine<C>, SyscallHandlerError> {
+        self.segment
+            .filter(|segment| segment.segment_index == handle.segment_index)
+            .and_then(|_| self.points.get(handle.offset))
+            .ok_or(SyscallHandlerError::InvalidSecpPoint(handle))
+    }
+}
+
+impl<C: SWCurveConfig> Default for SecpPointStore<C> {
+    fn default() -> Self {
+        Self {
+            points: Vec::new(),
+            segment: None,
+        }
+    }
+}
+
+impl<C: SWCurveConfig> fmt::Debug for SecpPointStore<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecpPointStore")
+            .field("n_points", &self.points.len())
+            .field("segment", &self.segment)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::AffineRepr;
+    use coverage_helper::test;
+
+    fn curve_operations<C: SWCurveConfig>()
+    where
+        C::BaseField: PrimeField,
+    {
+        let generator = C::GENERATOR;
+        let (x, y) = get_xy(&generator);
+
+        assert_eq!(new_point::<C>(&x, &y), Ok(Some(generator)));
+        assert_eq!(new_point::<C>(&x, &(&y + 1u32)), Ok(None));
+        assert_eq!(
+            new_point::<C>(&BigUint::zero(), &BigUint::zero()),
+            Ok(Some(Affine::identity()))
+        );
+        assert_eq!(
+            new_point::<C>(&C::BaseField::MODULUS.into(), &y),
+            Err(InvalidArgument)
+        );
+
+        let y_parity = y.bit(0);
+        assert_eq!(get_point_from_x::<C>(&x, y_parity), Ok(Some(generator)));
+        assert_ne!(get_point_from_x::<C>(&x, !y_parity), Ok(Some(generator)));
+
+        assert_eq!(add(&generator, &generator), mul(&generator, &2u32.into()));
+        assert!(mul(&generator, &BigUint::zero()).is_zero());
+        assert_eq!(
+            get_xy(&Affine::<C>::identity()),
+            (BigUint::zero(), BigUint::zero())
+        );
+    }
+
+    #[test]
+    fn secp256k1_operations() {
+        curve_operations::<ark_secp256k1::Config>();
+    }
+
+    #[test]
+    fn secp256r1_operations() {
+        curve_operations::<ark_secp256r1::Config>();
+    }
+
+    #[test]
+    fn point_store_handles() {
+        let mut vm = VirtualMachine::new(false);
+        let mut store = SecpPointStore::<ark_secp256k1::Config>::default();
+
+        let generator = ark_secp256k1::Config::GENERATOR;
+        let handle = store.allocate(&mut vm, generator).unwrap();
+        let other_handle = store.allocate(&mut vm, Affine::identity()).unwrap();
+
+        assert_eq!(other_handle, (handle + 1).unwrap());
+        assert_eq!(store.get(handle).unwrap(), &generator);
+        assert!(store.get((handle + 2).unwrap()).is_err());
+    }
+}
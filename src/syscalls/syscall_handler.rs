@@ -1,4 +1,5 @@
 use super::business_logic_syscall_handler::BusinessLogicSyscallHandler;
+use crate::execution::prepared_program::CompiledHints;
 use crate::state::{contract_class_cache::ContractClassCache, state_api::StateReader};
 use crate::transaction::error::TransactionError;
 use cairo_lang_casm::{
@@ -20,7 +21,7 @@ use cairo_vm::{
         vm_core::VirtualMachine,
     },
 };
-use std::{any::Any, boxed::Box, collections::HashMap};
+use std::{any::Any, boxed::Box, collections::HashMap, sync::Arc};
 
 pub(crate) trait HintProcessorPostRun {
     /// Performs post run syscall related tasks (if any).
@@ -36,18 +37,23 @@ pub(crate) struct SyscallHintProcessor<'a, S: StateReader, C: ContractClassCache
     pub(crate) cairo1_hint_processor: Cairo1HintProcessor,
     pub(crate) syscall_handler: BusinessLogicSyscallHandler<'a, S, C>,
     pub(crate) run_resources: RunResources,
+    /// The program's compiled hints, shared with the prepared program.
+    hints: CompiledHints,
 }
 
 impl<'a, S: StateReader, C: ContractClassCache> SyscallHintProcessor<'a, S, C> {
     pub fn new(
         syscall_handler: BusinessLogicSyscallHandler<'a, S, C>,
-        hints: &[(usize, Vec<Hint>)],
+        hints: CompiledHints,
         run_resources: RunResources,
     ) -> Self {
         SyscallHintProcessor {
-            cairo1_hint_processor: Cairo1HintProcessor::new(hints, run_resources.clone()),
+            // The hints are compiled by this processor, so the Cairo 1 processor doesn't need
+            // them (and copying them on every execution is avoided).
+            cairo1_hint_processor: Cairo1HintProcessor::new(&[], run_resources.clone()),
             syscall_handler,
             run_resources,
+            hints,
         }
     }
 }
@@ -62,8 +68,8 @@ impl<'a, S: StateReader, C: ContractClassCache> HintProcessorLogic
         hint_data: &Box<dyn Any>,
         _constants: &HashMap<String, Felt252>,
     ) -> Result<(), HintError> {
-        let hints: &Vec<Hint> = hint_data.downcast_ref().ok_or(HintError::WrongHintData)?;
-        for hint in hints {
+        let hints: &Arc<Vec<Hint>> = hint_data.downcast_ref().ok_or(HintError::WrongHintData)?;
+        for hint in hints.iter() {
             match hint {
                 Hint::Core(_core_hint) => {
                     self.cairo1_hint_processor.execute(vm, exec_scopes, hint)?
@@ -110,6 +116,17 @@ impl<'a, S: StateReader, C: ContractClassCache> HintProcessorLogic
         //List of all references (key corresponds to element of the previous dictionary)
         references: &[HintReference],
     ) -> Result<Box<dyn Any>, VirtualMachineError> {
+        // The hint code of CASM programs is the pc of the hints, which were compiled when the
+        // program was prepared.
+        if let Some(hints) = hint_code
+            .parse()
+            .ok()
+            .and_then(|pc: usize| self.hints.get(&pc))
+        {
+            return Ok(Box::new(Arc::clone(hints)));
+        }
+
+        // Let the Cairo 1 hint processor report the error.
         self.cairo1_hint_processor.compile_hint(
             hint_code,
             ap_tracking_data,
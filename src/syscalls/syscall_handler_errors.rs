@@ -2,7 +2,7 @@ use crate::core::errors::hash_errors::HashError;
 use crate::core::errors::state_errors::StateError;
 use cairo_vm::felt::Felt252;
 use cairo_vm::{
-    types::errors::math_errors::MathError,
+    types::{errors::math_errors::MathError, relocatable::Relocatable},
     vm::errors::{
         hint_errors::HintError, memory_errors::MemoryError, vm_errors::VirtualMachineError,
     },
@@ -71,6 +71,14 @@ pub enum SyscallHandlerError {
     Hint(#[from] HintError),
     #[error("Unsupported address domain: {0}")]
     UnsupportedAddressDomain(String),
+    #[error("Invalid secp256 point handle: {0:?}")]
+    InvalidSecpPoint(Relocatable),
+    #[error("Event has {0} keys, exceeding the limit of {1}")]
+    MaxEventKeysExceeded(usize, usize),
+    #[error("Event data has length {0}, exceeding the limit of {1}")]
+    MaxEventDataExceeded(usize, usize),
+    #[error("Transaction exceeded the limit of {0} emitted events")]
+    MaxEmittedEventsExceeded(u64),
     #[error("{0:?}")]
     CustomError(String),
 }
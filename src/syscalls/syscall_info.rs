@@ -10,7 +10,13 @@ pub fn get_syscall_size_from_name(syscall_name: &str) -> usize {
         "call_contract" => 4,
         "replace_class" => 1,
         "keccak" => 2,
+        "sha256_process_block" => 2,
         "get_block_hash" => 1,
+        "secp256k1_add" | "secp256r1_add" => 2,
+        "secp256k1_get_point_from_x" | "secp256r1_get_point_from_x" => 3,
+        "secp256k1_get_xy" | "secp256r1_get_xy" => 1,
+        "secp256k1_mul" | "secp256r1_mul" => 3,
+        "secp256k1_new" | "secp256r1_new" => 4,
         _ => unimplemented!(),
     }
 }
@@ -1,6 +1,7 @@
 use cairo_vm::felt::Felt252;
 use cairo_vm::{types::relocatable::Relocatable, vm::vm_core::VirtualMachine};
-use num_traits::ToPrimitive;
+use num_bigint::BigUint;
+use num_traits::{ToPrimitive, Zero};
 
 use crate::{
     syscalls::syscall_handler_errors::SyscallHandlerError,
@@ -48,6 +49,30 @@ pub(crate) enum SyscallRequest {
     ReplaceClass(ReplaceClassRequest),
     /// Computes the Keccak256 hash of the given data.
     Keccak(KeccakRequest),
+    /// Applies the SHA-256 compression function to a state and a block of data.
+    Sha256ProcessBlock(Sha256ProcessBlockRequest),
+    /// Creates a secp256k1 point from its coordinates.
+    Secp256k1New(Secp256NewRequest),
+    /// Adds two secp256k1 points.
+    Secp256k1Add(Secp256AddRequest),
+    /// Multiplies a secp256k1 point by a scalar.
+    Secp256k1Mul(Secp256MulRequest),
+    /// Gets a secp256k1 point from its x coordinate and the parity of its y coordinate.
+    Secp256k1GetPointFromX(Secp256GetPointFromXRequest),
+    /// Gets the coordinates of a secp256k1 point.
+    Secp256k1GetXy(Secp256GetXyRequest),
+    /// Creates a secp256r1 point from its coordinates.
+    Secp256r1New(Secp256NewRequest),
+    /// Adds two secp256r1 points.
+    Secp256r1Add(Secp256AddRequest),
+    /// Multiplies a secp256r1 point by a scalar.
+    Secp256r1Mul(Secp256MulRequest),
+    /// Gets a secp256r1 point from its x coordinate and the parity of its y coordinate.
+    Secp256r1GetPointFromX(Secp256GetPointFromXRequest),
+    /// Gets the coordinates of a secp256r1 point.
+    Secp256r1GetXy(Secp256GetXyRequest),
+    /// A syscall registered by the embedder.
+    Custom(CustomSyscallRequest),
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -192,6 +217,67 @@ pub(crate) struct KeccakRequest {
     pub(crate) input_end: Relocatable,
 }
 
+/// Applies the SHA-256 compression function to a state and a block of data.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Sha256ProcessBlockRequest {
+    /// The state, 8 u32 words.
+    pub(crate) state_ptr: Relocatable,
+    /// The block of data, 16 u32 words.
+    pub(crate) input_start: Relocatable,
+}
+
+/// Creates a secp256k1 or secp256r1 point from its coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Secp256NewRequest {
+    /// The x coordinate, a u256.
+    pub(crate) x: BigUint,
+    /// The y coordinate, a u256.
+    pub(crate) y: BigUint,
+}
+
+/// Adds two secp256k1 or secp256r1 points.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Secp256AddRequest {
+    /// The handle of the first point.
+    pub(crate) p0: Relocatable,
+    /// The handle of the second point.
+    pub(crate) p1: Relocatable,
+}
+
+/// Multiplies a secp256k1 or secp256r1 point by a scalar.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Secp256MulRequest {
+    /// The handle of the point.
+    pub(crate) p: Relocatable,
+    /// The scalar, a u256.
+    pub(crate) scalar: BigUint,
+}
+
+/// Gets a secp256k1 or secp256r1 point from its x coordinate and the parity of its y coordinate.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Secp256GetPointFromXRequest {
+    /// The x coordinate, a u256.
+    pub(crate) x: BigUint,
+    /// Whether the y coordinate is odd.
+    pub(crate) y_parity: bool,
+}
+
+/// Gets the coordinates of a secp256k1 or secp256r1 point.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Secp256GetXyRequest {
+    /// The handle of the point.
+    pub(crate) p: Relocatable,
+}
+
+/// Request of a syscall registered by the embedder.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CustomSyscallRequest {
+    /// The selector of the syscall.
+    pub(crate) selector: Felt252,
+    /// The request, as decoded by the syscall's decoder.
+    pub(crate) values: Vec<Felt252>,
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~
 //  Into<SyscallRequest> implementations
 // ~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -256,6 +342,85 @@ impl From<KeccakRequest> for SyscallRequest {
     }
 }
 
+impl From<Sha256ProcessBlockRequest> for SyscallRequest {
+    fn from(request: Sha256ProcessBlockRequest) -> SyscallRequest {
+        SyscallRequest::Sha256ProcessBlock(request)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~
+//  secp256 requests
+// ~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// The secp256k1 and secp256r1 syscalls share their requests, so they are read into the request
+// structs, which are then wrapped into the variant of the corresponding curve.
+
+/// Reads a u256, stored as its low and high 128 bits.
+fn get_u256(vm: &VirtualMachine, ptr: Relocatable) -> Result<BigUint, SyscallHandlerError> {
+    let low = get_big_int(vm, ptr)?.to_biguint();
+    let high = get_big_int(vm, (ptr + 1)?)?.to_biguint();
+    Ok((high << 128) + low)
+}
+
+impl Secp256NewRequest {
+    pub(crate) fn from_ptr(
+        vm: &VirtualMachine,
+        syscall_ptr: Relocatable,
+    ) -> Result<Self, SyscallHandlerError> {
+        Ok(Secp256NewRequest {
+            x: get_u256(vm, syscall_ptr)?,
+            y: get_u256(vm, (syscall_ptr + 2)?)?,
+        })
+    }
+}
+
+impl Secp256AddRequest {
+    pub(crate) fn from_ptr(
+        vm: &VirtualMachine,
+        syscall_ptr: Relocatable,
+    ) -> Result<Self, SyscallHandlerError> {
+        Ok(Secp256AddRequest {
+            p0: get_relocatable(vm, syscall_ptr)?,
+            p1: get_relocatable(vm, (syscall_ptr + 1)?)?,
+        })
+    }
+}
+
+impl Secp256MulRequest {
+    pub(crate) fn from_ptr(
+        vm: &VirtualMachine,
+        syscall_ptr: Relocatable,
+    ) -> Result<Self, SyscallHandlerError> {
+        Ok(Secp256MulRequest {
+            p: get_relocatable(vm, syscall_ptr)?,
+            scalar: get_u256(vm, (syscall_ptr + 1)?)?,
+        })
+    }
+}
+
+impl Secp256GetPointFromXRequest {
+    pub(crate) fn from_ptr(
+        vm: &VirtualMachine,
+        syscall_ptr: Relocatable,
+    ) -> Result<Self, SyscallHandlerError> {
+        Ok(Secp256GetPointFromXRequest {
+            x: get_u256(vm, syscall_ptr)?,
+            y_parity: !get_big_int(vm, (syscall_ptr + 2)?)?.is_zero(),
+        })
+    }
+}
+
+impl Secp256GetXyRequest {
+    pub(crate) fn from_ptr(
+        vm: &VirtualMachine,
+        syscall_ptr: Relocatable,
+    ) -> Result<Self, SyscallHandlerError> {
+        Ok(Secp256GetXyRequest {
+            p: get_relocatable(vm, syscall_ptr)?,
+        })
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~
 //  FromPtr trait
 // ~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -444,3 +609,19 @@ impl FromPtr for KeccakRequest {
         .into())
     }
 }
+
+impl FromPtr for Sha256ProcessBlockRequest {
+    fn from_ptr(
+        vm: &VirtualMachine,
+        syscall_ptr: Relocatable,
+    ) -> Result<SyscallRequest, SyscallHandlerError> {
+        let state_ptr = get_relocatable(vm, syscall_ptr)?;
+        let input_start = get_relocatable(vm, &syscall_ptr + 1)?;
+
+        Ok(Sha256ProcessBlockRequest {
+            state_ptr,
+            input_start,
+        }
+        .into())
+    }
+}
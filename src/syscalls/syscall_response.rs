@@ -1,17 +1,37 @@
 use cairo_vm::felt::Felt252;
 use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
 
 /// Abstracts every response variant body for each syscall.
+#[derive(Debug)]
 pub(crate) enum ResponseBody {
-    StorageReadResponse { value: Option<Felt252> },
-    GetBlockNumber { number: Felt252 },
+    StorageReadResponse {
+        value: Option<Felt252>,
+    },
+    GetBlockNumber {
+        number: Felt252,
+    },
     Deploy(DeployResponse),
     CallContract(CallContractResponse),
     Failure(FailureReason),
     GetBlockTimestamp(GetBlockTimestampResponse),
-    GetExecutionInfo { exec_info_ptr: Relocatable },
+    GetExecutionInfo {
+        exec_info_ptr: Relocatable,
+    },
     GetBlockHash(GetBlockHashResponse),
     Keccak(KeccakResponse),
+    Sha256ProcessBlock {
+        state_ptr: Relocatable,
+    },
+    /// A secp256 point handle, or `None` if the point isn't on the curve.
+    Secp256OptionalPoint(Option<Relocatable>),
+    /// A secp256 point handle.
+    Secp256Point(Relocatable),
+    Secp256GetXy(Secp256GetXyResponse),
+    /// The response of a syscall registered by the embedder.
+    Custom(Vec<MaybeRelocatable>),
 }
 /// Wraps around any response body. It also contains the remaining gas after the execution.
 #[allow(unused)]
@@ -65,6 +85,31 @@ impl SyscallResponse {
                 cairo_args.push(hash_low.into());
                 cairo_args.push(hash_high.into());
             }
+            Some(ResponseBody::Secp256OptionalPoint(handle)) => {
+                // Cairo's `Option` is an enum whose `Some` variant comes first.
+                match handle {
+                    Some(handle) => {
+                        cairo_args.push(Felt252::zero().into());
+                        cairo_args.push(handle.into());
+                    }
+                    None => {
+                        cairo_args.push(Felt252::one().into());
+                        cairo_args.push(Felt252::zero().into());
+                    }
+                }
+            }
+            Some(ResponseBody::Secp256Point(handle)) => cairo_args.push(handle.into()),
+            Some(ResponseBody::Secp256GetXy(Secp256GetXyResponse { x, y })) => {
+                for coordinate in [x, y] {
+                    let (high, low) = coordinate.div_rem(&(BigUint::one() << 128));
+                    cairo_args.push(Felt252::from(low).into());
+                    cairo_args.push(Felt252::from(high).into());
+                }
+            }
+            Some(ResponseBody::Sha256ProcessBlock { state_ptr }) => {
+                cairo_args.push(state_ptr.into())
+            }
+            Some(ResponseBody::Custom(values)) => cairo_args.extend(values.iter().cloned()),
             None => {}
         }
         cairo_args
@@ -83,6 +128,7 @@ pub struct GetBlockTimestampResponse {
 }
 
 /// Represents the response of deploy syscall.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DeployResponse {
     /// Address of the deployed contract.
     pub contract_address: Felt252,
@@ -93,6 +139,7 @@ pub struct DeployResponse {
 }
 
 /// Represents error data of any syscall response.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct FailureReason {
     /// The retdata segment start.
     pub retdata_start: Relocatable,
@@ -126,3 +173,12 @@ pub struct KeccakResponse {
     pub hash_low: Felt252,
     pub hash_high: Felt252,
 }
+
+/// Represents the response of the `secp256k1_get_xy` and `secp256r1_get_xy` syscalls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Secp256GetXyResponse {
+    /// The x coordinate, a u256.
+    pub x: BigUint,
+    /// The y coordinate, a u256.
+    pub y: BigUint,
+}
@@ -0,0 +1,223 @@
+//! # Syscall traces
+//!
+//! When [`BlockContext::trace_syscalls`](crate::definitions::block_context::BlockContext) is
+//! enabled, every call records the syscalls it made, in order, in its
+//! [`CallInfo::syscall_trace`](crate::execution::CallInfo).
+
+use cairo_vm::felt::Felt252;
+
+/// A syscall made by a contract call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyscallInvocation {
+    /// Name of the syscall (for example, `storage_read`).
+    pub name: String,
+    /// Arguments of the syscall, as decoded by the syscall handler.
+    pub request: String,
+    /// Gas available to the contract before the syscall. Cairo 0 contracts don't use gas.
+    pub gas_before: Option<u128>,
+    /// Gas available to the contract after the syscall. Cairo 0 contracts don't use gas.
+    pub gas_after: Option<u128>,
+    /// What the syscall returned to the contract.
+    pub outcome: SyscallOutcome,
+    /// Index in [`CallInfo::internal_calls`](crate::execution::CallInfo) of the call made by
+    /// the syscall, if any.
+    pub internal_call: Option<usize>,
+}
+
+/// Result of a syscall.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SyscallOutcome {
+    /// The syscall succeeded with the given response.
+    Success(String),
+    /// The syscall failed, returning the given reason to the contract.
+    Failure(Vec<Felt252>),
+    /// The syscall handler raised an error, which aborted the call.
+    Error(String),
+}
+
+/// A syscall whose outcome isn't known yet.
+#[derive(Debug)]
+struct PendingInvocation {
+    name: String,
+    request: String,
+    gas_before: Option<u128>,
+    n_internal_calls: usize,
+    response: Option<String>,
+}
+
+/// Records the syscalls made by a contract call, if syscall tracing is enabled.
+#[derive(Debug, Default)]
+pub(crate) struct SyscallTracer {
+    invocations: Option<Vec<SyscallInvocation>>,
+    pending: Option<PendingInvocation>,
+}
+
+impl SyscallTracer {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            invocations: enabled.then(Vec::new),
+            pending: None,
+        }
+    }
+
+    /// Starts recording a syscall. `n_internal_calls` is the number of internal calls made by
+    /// the contract before the syscall.
+    pub(crate) fn begin(
+        &mut self,
+        name: &str,
+        request: impl FnOnce() -> String,
+        gas_before: Option<u128>,
+        n_internal_calls: usize,
+    ) {
+        if self.invocations.is_some() {
+            self.pending = Some(PendingInvocation {
+                name: name.to_string(),
+                request: request(),
+                gas_before,
+                n_internal_calls,
+                response: None,
+            });
+        }
+    }
+
+    /// Sets the response of the syscall being recorded.
+    pub(crate) fn respond(&mut self, response: impl FnOnce() -> String) {
+        if let Some(pending) = self.pending.as_mut() {
+            pending.response = Some(response());
+        }
+    }
+
+    /// Takes the response of the syscall being recorded, if it was set.
+    pub(crate) fn take_response(&mut self) -> Option<String> {
+        self.pending
+            .as_mut()
+            .and_then(|pending| pending.response.take())
+    }
+
+    /// Finishes recording the syscall. `n_internal_calls` is the number of internal calls made by
+    /// the contract after the syscall.
+    pub(crate) fn end(
+        &mut self,
+        outcome: impl FnOnce() -> SyscallOutcome,
+        gas_after: Option<u128>,
+        n_internal_calls: usize,
+    ) {
+        if let (Some(invocations), Some(pending)) = (self.invocations.as_mut(), self.pending.take())
+        {
+            invocations.push(SyscallInvocation {
+                name: pending.name,
+                request: pending.request,
+                gas_before: pending.gas_before,
+                gas_after,
+                outcome: outcome(),
+                internal_call: (n_internal_calls > pending.n_internal_calls)
+                    .then(|| n_internal_calls - 1),
+            });
+        }
+    }
+
+    /// Returns the recorded syscalls, or `None` if syscall tracing is disabled.
+    pub(crate) fn into_trace(self) -> Option<Vec<SyscallInvocation>> {
+        self.invocations
+    }
+}
+
+/// Formats the arguments of the syscalls that take arrays. Both the VM and the Cairo Native
+/// handlers use these, so that a call records the same request on either backend.
+pub(crate) mod request {
+    use cairo_vm::felt::Felt252;
+
+    pub(crate) fn emit_event(keys: &[Felt252], data: &[Felt252]) -> String {
+        format!("keys: {keys:?}, data: {data:?}")
+    }
+
+    pub(crate) fn call_contract(
+        address: &Felt252,
+        entrypoint_selector: &Felt252,
+        calldata: &[Felt252],
+    ) -> String {
+        format!(
+            "address: {address}, entrypoint_selector: {entrypoint_selector}, \
+             calldata: {calldata:?}"
+        )
+    }
+
+    pub(crate) fn library_call(
+        class_hash: &Felt252,
+        function_selector: &Felt252,
+        calldata: &[Felt252],
+    ) -> String {
+        format!(
+            "class_hash: {class_hash}, function_selector: {function_selector}, \
+             calldata: {calldata:?}"
+        )
+    }
+
+    pub(crate) fn deploy(
+        class_hash: &Felt252,
+        contract_address_salt: &Felt252,
+        calldata: &[Felt252],
+        deploy_from_zero: bool,
+    ) -> String {
+        format!(
+            "class_hash: {class_hash}, contract_address_salt: {contract_address_salt}, \
+             calldata: {calldata:?}, deploy_from_zero: {deploy_from_zero}"
+        )
+    }
+
+    pub(crate) fn send_message_to_l1(to_address: &Felt252, payload: &[Felt252]) -> String {
+        format!("to_address: {to_address}, payload: {payload:?}")
+    }
+
+    pub(crate) fn keccak(input: &[u64]) -> String {
+        format!("input: {input:?}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    #[test]
+    fn disabled_tracer_records_nothing() {
+        let mut tracer = SyscallTracer::new(false);
+        tracer.begin("storage_read", || unreachable!(), Some(100), 0);
+        tracer.end(|| unreachable!(), Some(90), 0);
+
+        assert_eq!(tracer.into_trace(), None);
+    }
+
+    #[test]
+    fn tracer_links_internal_calls() {
+        let mut tracer = SyscallTracer::new(true);
+        tracer.begin("storage_read", || "key: 1".to_string(), Some(100), 0);
+        tracer.respond(|| "value: 2".to_string());
+        let response = tracer.take_response().unwrap();
+        tracer.end(|| SyscallOutcome::Success(response), Some(90), 0);
+        tracer.begin("call_contract", || "selector: 3".to_string(), Some(90), 0);
+        tracer.end(|| SyscallOutcome::Failure(vec![4.into()]), Some(50), 1);
+
+        assert_eq!(
+            tracer.into_trace(),
+            Some(vec![
+                SyscallInvocation {
+                    name: "storage_read".to_string(),
+                    request: "key: 1".to_string(),
+                    gas_before: Some(100),
+                    gas_after: Some(90),
+                    outcome: SyscallOutcome::Success("value: 2".to_string()),
+                    internal_call: None,
+                },
+                SyscallInvocation {
+                    name: "call_contract".to_string(),
+                    request: "selector: 3".to_string(),
+                    gas_before: Some(90),
+                    gas_after: Some(50),
+                    outcome: SyscallOutcome::Failure(vec![4.into()]),
+                    internal_call: Some(0),
+                },
+            ])
+        );
+    }
+}
@@ -4,7 +4,6 @@ use crate::definitions::block_context::BlockContext;
 use crate::definitions::constants::VALIDATE_DECLARE_ENTRY_POINT_SELECTOR;
 use crate::definitions::transaction_type::TransactionType;
 use crate::execution::gas_usage::get_onchain_data_segment_length;
-use crate::execution::os_usage::ESTIMATED_DECLARE_STEPS;
 use crate::services::api::contract_classes::deprecated_contract_class::EntryPointType;
 use crate::services::eth_definitions::eth_gas_constants::SHARP_GAS_PER_MEMORY_WORD;
 use crate::state::cached_state::CachedState;
@@ -216,6 +215,7 @@ impl Declare {
             changes,
             None,
             0,
+            block_context,
         )
         .map_err(|_| TransactionError::ResourcesCalculation)?;
 
@@ -273,9 +273,9 @@ impl Declare {
             state,
             block_context,
             resources_manager,
-            &mut self.get_execution_context(block_context.invoke_tx_max_n_steps),
+            &mut self.get_execution_context(block_context.invoke_tx_max_n_steps()),
             false,
-            block_context.validate_max_n_steps,
+            block_context.validate_max_n_steps(),
             #[cfg(feature = "cairo-native")]
             program_cache,
         )?;
@@ -307,7 +307,7 @@ impl Declare {
         Ok(())
     }
 
-    fn check_fee_balance<S: State + StateReader>(
+    pub(crate) fn check_fee_balance<S: State + StateReader>(
         &self,
         state: &mut S,
         block_context: &BlockContext,
@@ -335,7 +335,10 @@ impl Declare {
     }
 
     fn estimate_minimal_fee(&self, block_context: &BlockContext) -> Result<u128, TransactionError> {
-        let n_estimated_steps = ESTIMATED_DECLARE_STEPS;
+        let n_estimated_steps = block_context
+            .versioned_constants()
+            .os_resources()
+            .estimated_tx_steps(TransactionType::Declare);
         let onchain_data_length = get_onchain_data_segment_length(&StateChangesCount {
             n_storage_updates: 1,
             n_class_hash_updates: 0,
@@ -395,7 +398,7 @@ impl Declare {
         )?;
 
         let mut tx_execution_context =
-            self.get_execution_context(block_context.invoke_tx_max_n_steps);
+            self.get_execution_context(block_context.invoke_tx_max_n_steps());
         let (fee_transfer_info, actual_fee) = charge_fee(
             state,
             &tx_exec_info.actual_resources,
@@ -4,7 +4,6 @@ use crate::core::contract_address::{compute_casm_class_hash, compute_sierra_clas
 use crate::definitions::constants::VALIDATE_RETDATA;
 use crate::execution::execution_entry_point::ExecutionResult;
 use crate::execution::gas_usage::get_onchain_data_segment_length;
-use crate::execution::os_usage::ESTIMATED_DECLARE_STEPS;
 use crate::services::api::contract_classes::deprecated_contract_class::EntryPointType;
 
 use crate::services::api::contract_classes::compiled_class::CompiledClass;
@@ -21,8 +20,8 @@ use crate::{
         transaction_type::TransactionType,
     },
     execution::{
-        execution_entry_point::ExecutionEntryPoint, CallType, TransactionExecutionContext,
-        TransactionExecutionInfo,
+        execution_entry_point::ExecutionEntryPoint, CallType, ExtendedTxInfo,
+        TransactionExecutionContext, TransactionExecutionInfo,
     },
     state::state_api::{State, StateReader},
     state::ExecutionResourcesManager,
@@ -60,6 +59,8 @@ pub struct DeclareV2 {
     pub sierra_class_hash: Felt252,
     pub hash_value: Felt252,
     pub casm_class: Option<CasmContractClass>,
+    /// Fields of the transaction only exposed to contracts reading the extended tx info.
+    pub extended_tx_info: ExtendedTxInfo,
     pub skip_validate: bool,
     pub skip_execute: bool,
     pub skip_fee_transfer: bool,
@@ -159,6 +160,7 @@ impl DeclareV2 {
             compiled_class_hash,
             hash_value,
             casm_class: casm_contract_class,
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_execute: false,
             skip_validate: false,
             skip_fee_transfer: false,
@@ -273,6 +275,7 @@ impl DeclareV2 {
             n_steps,
             self.version.clone(),
         )
+        .with_extended_tx_info(self.extended_tx_info.clone())
     }
 
     /// returns the calldata with which the contract is executed
@@ -296,7 +299,7 @@ impl DeclareV2 {
         Ok(())
     }
 
-    fn check_fee_balance<S: State + StateReader>(
+    pub(crate) fn check_fee_balance<S: State + StateReader>(
         &self,
         state: &mut S,
         block_context: &BlockContext,
@@ -324,7 +327,10 @@ impl DeclareV2 {
     }
 
     fn estimate_minimal_fee(&self, block_context: &BlockContext) -> Result<u128, TransactionError> {
-        let n_estimated_steps = ESTIMATED_DECLARE_STEPS;
+        let n_estimated_steps = block_context
+            .versioned_constants()
+            .os_resources()
+            .estimated_tx_steps(TransactionType::Declare);
         let onchain_data_length = get_onchain_data_segment_length(&StateChangesCount {
             n_storage_updates: 1,
             n_class_hash_updates: 0,
@@ -374,6 +380,12 @@ impl DeclareV2 {
             ));
         }
 
+        if let Some(sierra_contract_class) = &self.sierra_contract_class {
+            block_context
+                .allowed_libfuncs
+                .validate(sierra_contract_class)?;
+        }
+
         if !self.skip_fee_transfer {
             self.check_fee_balance(state, block_context)?;
         }
@@ -410,10 +422,11 @@ impl DeclareV2 {
             storage_changes,
             None,
             execution_result.n_reverted_steps,
+            block_context,
         )?;
 
         let mut tx_execution_context =
-            self.get_execution_context(block_context.invoke_tx_max_n_steps);
+            self.get_execution_context(block_context.invoke_tx_max_n_steps());
         let (fee_transfer_info, actual_fee) = charge_fee(
             state,
             &actual_resources,
@@ -496,18 +509,21 @@ impl DeclareV2 {
         };
 
         let mut tx_execution_context =
-            self.get_execution_context(block_context.validate_max_n_steps);
+            self.get_execution_context(block_context.validate_max_n_steps());
 
         let execution_result = if self.skip_execute {
             ExecutionResult::default()
         } else {
+            // Like the other account transactions, a failed validation rejects the transaction. A
+            // revertible call would turn the failure into a missing call info, which skips the
+            // checks below and lets the class be declared.
             entry_point.execute(
                 state,
                 block_context,
                 resources_manager,
                 &mut tx_execution_context,
-                true,
-                block_context.validate_max_n_steps,
+                false,
+                block_context.validate_max_n_steps(),
                 #[cfg(feature = "cairo-native")]
                 program_cache,
             )?
@@ -573,13 +589,15 @@ mod tests {
     use crate::core::contract_address::{compute_casm_class_hash, compute_sierra_class_hash};
     use crate::definitions::block_context::{BlockContext, StarknetChainId};
     use crate::definitions::constants::QUERY_VERSION_2;
+    use crate::definitions::libfunc_allow_list::LibfuncAllowList;
     use crate::services::api::contract_classes::compiled_class::CompiledClass;
     use crate::state::state_api::StateReader;
     use crate::transaction::error::TransactionError;
     use crate::utils::ClassHash;
     use crate::{
         state::{
-            cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
+            cached_state::CachedState,
+            contract_class_cache::{ContractClassCache, PermanentContractClassCache},
             in_memory_state_reader::InMemoryStateReader,
         },
         utils::Address,
@@ -976,4 +994,113 @@ mod tests {
         Err(TransactionError::UnsupportedTxVersion(tx, ver, supp))
         if tx == "DeclareV2" && ver == 1.into() && supp == vec![2]);
     }
+
+    #[test]
+    fn declarev2_disallowed_libfuncs() {
+        let path;
+        #[cfg(not(feature = "cairo_1_tests"))]
+        {
+            path = PathBuf::from("starknet_programs/cairo2/fibonacci.sierra");
+        }
+
+        #[cfg(feature = "cairo_1_tests")]
+        {
+            path = PathBuf::from("starknet_programs/cairo1/fibonacci.sierra");
+        }
+
+        let file = File::open(path).unwrap();
+        let reader = BufReader::new(file);
+        let sierra_contract_class: cairo_lang_starknet::contract_class::ContractClass =
+            serde_json::from_reader(reader).unwrap();
+
+        let chain_id = StarknetChainId::TestNet.to_felt();
+
+        // declare tx
+        let internal_declare = DeclareV2::new(
+            &sierra_contract_class,
+            None,
+            Felt252::one(),
+            chain_id,
+            Address(Felt252::one()),
+            0,
+            2.into(),
+            Vec::new(),
+            Felt252::zero(),
+        )
+        .unwrap();
+
+        let mut block_context = BlockContext::default();
+        *block_context.allowed_libfuncs_mut() = LibfuncAllowList::new("empty", []);
+
+        let result = internal_declare.execute(
+            &mut CachedState::<InMemoryStateReader, PermanentContractClassCache>::default(),
+            &block_context,
+            #[cfg(feature = "cairo-native")]
+            None,
+        );
+
+        assert_matches!(
+        result,
+        Err(TransactionError::DisallowedLibfuncs(list, libfuncs))
+        if list == "empty" && libfuncs.contains(&"felt252_add".to_string()));
+    }
+
+    #[test]
+    fn declarev2_failed_validation_rejects_tx() {
+        let path;
+        #[cfg(not(feature = "cairo_1_tests"))]
+        {
+            path = PathBuf::from("starknet_programs/cairo2/fibonacci.sierra");
+        }
+
+        #[cfg(feature = "cairo_1_tests")]
+        {
+            path = PathBuf::from("starknet_programs/cairo1/fibonacci.sierra");
+        }
+
+        let file = File::open(path).unwrap();
+        let reader = BufReader::new(file);
+        let sierra_contract_class: cairo_lang_starknet::contract_class::ContractClass =
+            serde_json::from_reader(reader).unwrap();
+        let casm_class =
+            CasmContractClass::from_contract_class(sierra_contract_class.clone(), true).unwrap();
+        let casm_class_hash = compute_casm_class_hash(&casm_class).unwrap();
+
+        // The sender is a Cairo 1 contract without a `__validate_declare__` entry point, so its
+        // validation fails.
+        let sender_address = Address(Felt252::one());
+        let contract_class_cache = PermanentContractClassCache::default();
+        contract_class_cache.set_contract_class(
+            ClassHash::from(casm_class_hash.clone()),
+            CompiledClass::Casm(Arc::new(casm_class)),
+        );
+        let mut state_reader = InMemoryStateReader::default();
+        state_reader.address_to_class_hash_mut().insert(
+            sender_address.clone(),
+            ClassHash::from(casm_class_hash.clone()),
+        );
+        let mut state = CachedState::new(Arc::new(state_reader), Arc::new(contract_class_cache));
+
+        let internal_declare = DeclareV2::new(
+            &sierra_contract_class,
+            None,
+            casm_class_hash,
+            StarknetChainId::TestNet.to_felt(),
+            sender_address,
+            0,
+            2.into(),
+            Vec::new(),
+            Felt252::zero(),
+        )
+        .unwrap();
+
+        let result = internal_declare.execute(
+            &mut state,
+            &BlockContext::default(),
+            #[cfg(feature = "cairo-native")]
+            None,
+        );
+
+        assert_matches!(result, Err(TransactionError::EntryPointNotFound));
+    }
 }
@@ -172,7 +172,7 @@ impl Deploy {
 
         if self.constructor_entry_points_empty(self.contract_class.clone())? {
             // Contract has no constructors
-            Ok(self.handle_empty_constructor(state)?)
+            Ok(self.handle_empty_constructor(state, block_context)?)
         } else {
             self.invoke_constructor(
                 state,
@@ -186,9 +186,11 @@ impl Deploy {
     /// Executes the contract without constructor
     /// ## Parameters
     /// - state: A state that implements the [`State`] and [`StateReader`] traits.
+    /// - block_context: The block's execution context.
     pub fn handle_empty_constructor<S: State + StateReader>(
         &self,
         state: &mut S,
+        block_context: &BlockContext,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         if !self.constructor_calldata.is_empty() {
             return Err(TransactionError::EmptyConstructorCalldata);
@@ -211,6 +213,7 @@ impl Deploy {
             changes,
             None,
             0,
+            block_context,
         )?;
 
         Ok(TransactionExecutionInfo::new_without_fee_info(
@@ -251,7 +254,7 @@ impl Deploy {
             Vec::new(),
             0,
             Felt252::zero(),
-            block_context.invoke_tx_max_n_steps,
+            block_context.invoke_tx_max_n_steps(),
             self.version.clone(),
         );
 
@@ -266,7 +269,7 @@ impl Deploy {
             &mut resources_manager,
             &mut tx_execution_context,
             true,
-            block_context.validate_max_n_steps,
+            block_context.validate_max_n_steps(),
             #[cfg(feature = "cairo-native")]
             program_cache,
         )?;
@@ -279,6 +282,7 @@ impl Deploy {
             changes,
             None,
             n_reverted_steps,
+            block_context,
         )?;
 
         Ok(TransactionExecutionInfo::new_without_fee_info(
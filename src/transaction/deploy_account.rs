@@ -4,7 +4,6 @@ use super::{invoke_function::verify_no_calls_to_other_contracts, Transaction};
 use crate::definitions::constants::VALIDATE_RETDATA;
 use crate::execution::execution_entry_point::ExecutionResult;
 use crate::execution::gas_usage::get_onchain_data_segment_length;
-use crate::execution::os_usage::ESTIMATED_DEPLOY_ACCOUNT_STEPS;
 use crate::services::api::contract_classes::deprecated_contract_class::EntryPointType;
 use crate::services::eth_definitions::eth_gas_constants::SHARP_GAS_PER_MEMORY_WORD;
 use crate::state::cached_state::CachedState;
@@ -24,8 +23,8 @@ use crate::{
         transaction_type::TransactionType,
     },
     execution::{
-        execution_entry_point::ExecutionEntryPoint, CallInfo, TransactionExecutionContext,
-        TransactionExecutionInfo,
+        execution_entry_point::ExecutionEntryPoint, CallInfo, ExtendedTxInfo,
+        TransactionExecutionContext, TransactionExecutionInfo,
     },
     hash_utils::calculate_contract_address,
     services::api::{
@@ -68,13 +67,18 @@ pub struct DeployAccount {
     class_hash: ClassHash,
     #[getset(get = "pub")]
     constructor_calldata: Vec<Felt252>,
+    #[getset(get = "pub")]
     version: Felt252,
+    #[getset(get = "pub")]
     nonce: Felt252,
+    #[getset(get = "pub")]
     max_fee: u128,
     #[getset(get = "pub")]
     hash_value: Felt252,
     #[getset(get = "pub")]
     signature: Vec<Felt252>,
+    #[getset(get = "pub")]
+    extended_tx_info: ExtendedTxInfo,
     skip_validate: bool,
     skip_execute: bool,
     skip_fee_transfer: bool,
@@ -122,6 +126,7 @@ impl DeployAccount {
             max_fee,
             hash_value,
             signature,
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_execute: false,
             skip_validate: false,
             skip_fee_transfer: false,
@@ -158,6 +163,7 @@ impl DeployAccount {
             max_fee,
             hash_value,
             signature,
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_execute: false,
             skip_validate: false,
             skip_fee_transfer: false,
@@ -235,7 +241,7 @@ impl DeployAccount {
         }
 
         let mut tx_execution_context =
-            self.get_execution_context(block_context.invoke_tx_max_n_steps);
+            self.get_execution_context(block_context.invoke_tx_max_n_steps());
         let (fee_transfer_info, actual_fee) = charge_fee(
             state,
             &tx_exec_info.actual_resources,
@@ -313,6 +319,7 @@ impl DeployAccount {
             )))?,
             None,
             0,
+            block_context,
         )
         .map_err::<TransactionError, _>(|_| TransactionError::ResourcesCalculation)?;
 
@@ -373,7 +380,7 @@ impl DeployAccount {
         Ok(())
     }
 
-    fn check_fee_balance<S: State + StateReader>(
+    pub(crate) fn check_fee_balance<S: State + StateReader>(
         &self,
         state: &mut S,
         block_context: &BlockContext,
@@ -401,7 +408,10 @@ impl DeployAccount {
     }
 
     fn estimate_minimal_fee(&self, block_context: &BlockContext) -> Result<u128, TransactionError> {
-        let n_estimated_steps = ESTIMATED_DEPLOY_ACCOUNT_STEPS;
+        let n_estimated_steps = block_context
+            .versioned_constants()
+            .os_resources()
+            .estimated_tx_steps(TransactionType::DeployAccount);
         let onchain_data_length = get_onchain_data_segment_length(&StateChangesCount {
             n_storage_updates: 1,
             n_class_hash_updates: 1,
@@ -449,9 +459,9 @@ impl DeployAccount {
                 state,
                 block_context,
                 resources_manager,
-                &mut self.get_execution_context(block_context.validate_max_n_steps),
+                &mut self.get_execution_context(block_context.validate_max_n_steps()),
                 false,
-                block_context.validate_max_n_steps,
+                block_context.validate_max_n_steps(),
                 #[cfg(feature = "cairo-native")]
                 program_cache,
             )?
@@ -472,6 +482,7 @@ impl DeployAccount {
             n_steps,
             self.version.clone(),
         )
+        .with_extended_tx_info(self.extended_tx_info.clone())
     }
 
     pub fn run_validate_entrypoint<S: StateReader, C: ContractClassCache>(
@@ -507,9 +518,9 @@ impl DeployAccount {
                 state,
                 block_context,
                 resources_manager,
-                &mut self.get_execution_context(block_context.validate_max_n_steps),
+                &mut self.get_execution_context(block_context.validate_max_n_steps()),
                 false,
-                block_context.validate_max_n_steps,
+                block_context.validate_max_n_steps(),
                 #[cfg(feature = "cairo-native")]
                 program_cache,
             )?
@@ -538,6 +549,20 @@ impl DeployAccount {
         Ok(call_info)
     }
 
+    /// Returns the transaction with the given signature. The signature isn't part of the
+    /// transaction hash, so it can be set once the hash is known.
+    pub(crate) fn with_signature(self, signature: Vec<Felt252>) -> Self {
+        Self { signature, ..self }
+    }
+
+    /// Sets the fields of the transaction only exposed to contracts reading the extended tx info.
+    pub fn with_extended_tx_info(self, extended_tx_info: ExtendedTxInfo) -> Self {
+        Self {
+            extended_tx_info,
+            ..self
+        }
+    }
+
     pub fn create_for_simulation(
         &self,
         skip_validate: bool,
@@ -562,6 +587,7 @@ impl DeployAccount {
         Transaction::DeployAccount(tx)
     }
 
+    #[deprecated(note = "use `Transaction::try_from` with a `StarknetApiTransaction` instead")]
     pub fn from_sn_api_transaction(
         value: starknet_api::transaction::DeployAccountTransaction,
         chain_id: Felt252,
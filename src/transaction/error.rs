@@ -68,6 +68,8 @@ pub enum TransactionError {
     UnexpectedHolesInEventOrder,
     #[error("Unexpected holes in the L2-to-L1 message order.")]
     UnexpectedHolesL2toL1Messages,
+    #[error("Message to L1 with hash 0x{0} was not sent or was already consumed")]
+    MessageToL1NotPending(String),
     #[error("Attemp to return class hash with incorrect call type")]
     CallTypeIsNotDelegate,
     #[error("Attemp to return code address when it is None")]
@@ -76,6 +78,10 @@ pub enum TransactionError {
     FailToReadClassHash,
     #[error("Missing compiled class after fetching")]
     MissingCompiledClass,
+    #[error("Missing the contract class of the {0:?} transaction")]
+    MissingContractClass(TransactionType),
+    #[error("The contract class of the {0:?} transaction is of the wrong kind")]
+    ContractClassMismatch(TransactionType),
     #[error("Contract address {0:?} is not deployed")]
     NotDeployedContract(ClassHash),
     #[error("Non-unique entry points are not possible in a ContractClass object")]
@@ -150,6 +156,14 @@ pub enum TransactionError {
     FromByteArrayError(#[from] FromByteArrayError),
     #[error("DeclareV2 transaction has neither Sierra nor Casm contract class set")]
     DeclareV2NoSierraOrCasm,
+    #[error("Sierra class uses libfuncs which are not in the '{0}' allowed libfuncs list: {}", .1.join(", "))]
+    DisallowedLibfuncs(String, Vec<String>),
+    #[error("Invalid allowed libfuncs list: {0}")]
+    InvalidLibfuncAllowList(String),
+    #[error("Invalid versioned constants: {0}")]
+    InvalidVersionedConstants(String),
+    #[error("No versioned constants bundled for Starknet version {0}")]
+    UnknownStarknetVersion(String),
     #[error("Unsupported {0} transaction version: {1}. Supported versions:{2:?}")]
     UnsupportedTxVersion(String, Felt252, Vec<usize>),
     #[error("The `validate` entry point should return `VALID`.")]
@@ -158,4 +172,6 @@ pub enum TransactionError {
     MaxFeeTooLow(u128, u128),
     #[error("Max fee ({0}) exceeds balance (Uint256({1}, {2})).")]
     MaxFeeExceedsBalance(u128, Felt252, Felt252),
+    #[error("Invalid transaction JSON: {0}")]
+    InvalidTransactionJson(String),
 }
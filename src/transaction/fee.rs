@@ -2,7 +2,7 @@ use super::error::TransactionError;
 use crate::{
     definitions::{
         block_context::BlockContext,
-        constants::{FEE_FACTOR, INITIAL_GAS_COST, TRANSFER_ENTRY_POINT_SELECTOR},
+        constants::{FEE_FACTOR, INITIAL_GAS_COST, STEP_GAS_COST, TRANSFER_ENTRY_POINT_SELECTOR},
     },
     execution::{
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
@@ -74,7 +74,7 @@ pub(crate) fn execute_fee_transfer<S: StateReader, C: ContractClassCache>(
             &mut resources_manager,
             tx_execution_context,
             false,
-            block_context.invoke_tx_max_n_steps,
+            block_context.invoke_tx_max_n_steps(),
             #[cfg(feature = "cairo-native")]
             program_cache,
         )
@@ -83,14 +83,47 @@ pub(crate) fn execute_fee_transfer<S: StateReader, C: ContractClassCache>(
     call_info.ok_or(TransactionError::CallInfoIsNone)
 }
 
+/// The fee of a transaction, split by the kind of gas it pays for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    /// L1 gas used, including the gas consumed by the Cairo resources.
+    pub l1_gas_usage: u128,
+    /// Fee paid for the L1 gas.
+    pub l1_gas_fee: u128,
+    /// L1 data gas used to post the state diff as blobs.
+    pub l1_data_gas_usage: u128,
+    /// Fee paid for the L1 data gas.
+    pub l1_data_gas_fee: u128,
+}
+
+impl FeeBreakdown {
+    /// Returns the total fee.
+    pub fn total(&self) -> u128 {
+        self.l1_gas_fee + self.l1_data_gas_fee
+    }
+}
+
 /// Calculates the fee of a transaction given its execution resources.
 /// We add the l1_gas_usage (which may include, for example, the direct cost of L2-to-L1
 /// messages) to the gas consumed by Cairo resource and multiply by the L1 gas price.
+/// The l1_data_gas_usage, if the state diff is posted as blobs, is charged at the L1 data gas
+/// price of the block.
 pub fn calculate_tx_fee(
     resources: &HashMap<String, usize>,
     gas_price: u128,
     block_context: &BlockContext,
 ) -> Result<u128, TransactionError> {
+    calculate_tx_fee_breakdown(resources, gas_price, block_context)
+        .map(|breakdown| breakdown.total())
+}
+
+/// Calculates the fee of a transaction given its execution resources, split by the kind of gas it
+/// pays for. See [`calculate_tx_fee`].
+pub fn calculate_tx_fee_breakdown(
+    resources: &HashMap<String, usize>,
+    gas_price: u128,
+    block_context: &BlockContext,
+) -> Result<FeeBreakdown, TransactionError> {
     let gas_usage = resources
         .get(&"l1_gas_usage".to_string())
         .ok_or_else(|| TransactionError::FeeError("Invalid fee value".to_string()))?
@@ -98,8 +131,49 @@ pub fn calculate_tx_fee(
 
     let l1_gas_by_cairo_usage = calculate_l1_gas_by_cairo_usage(block_context, resources)?;
     let total_l1_gas_usage = gas_usage.to_f64().unwrap() + l1_gas_by_cairo_usage;
+    let l1_gas_usage = total_l1_gas_usage.ceil() as u128;
 
-    Ok(total_l1_gas_usage.ceil() as u128 * gas_price)
+    let l1_data_gas_usage = resources
+        .get("l1_data_gas_usage")
+        .copied()
+        .unwrap_or_default() as u128;
+
+    Ok(FeeBreakdown {
+        l1_gas_usage,
+        l1_gas_fee: l1_gas_usage * gas_price,
+        l1_data_gas_usage,
+        l1_data_gas_fee: l1_data_gas_usage * block_context.block_info.l1_data_gas_price,
+    })
+}
+
+/// Calculates the Sierra gas available to the execute phase of a transaction, the way the
+/// sequencer does: the L1 gas that `max_fee` pays for at the block's gas price is converted to
+/// Cairo steps through their fee weight, and then to Sierra gas. The gas consumed by the validation
+/// is subtracted, and the result is capped by the protocol's initial gas.
+/// If there's no max fee or gas price to derive it from, the whole initial gas is available.
+pub fn calculate_initial_gas(
+    max_fee: u128,
+    validate_gas_consumed: u128,
+    block_context: &BlockContext,
+) -> u128 {
+    let initial_gas = block_context.versioned_constants.initial_gas();
+    let gas_price = block_context.starknet_os_config.gas_price;
+    let step_fee_weight = block_context
+        .cairo_resource_fee_weights()
+        .get("n_steps")
+        .copied()
+        .unwrap_or_default();
+    if max_fee == 0 || gas_price == 0 || step_fee_weight <= 0.0 {
+        return initial_gas;
+    }
+
+    let max_l1_gas = max_fee / gas_price;
+    // Float to int casts saturate, so huge max fees end up capped by the initial gas.
+    let max_steps = (max_l1_gas as f64 / step_fee_weight) as u128;
+    max_steps
+        .saturating_mul(STEP_GAS_COST)
+        .saturating_sub(validate_gas_consumed)
+        .min(initial_gas)
 }
 
 /// Calculates the L1 gas consumed when submitting the underlying Cairo program to SHARP.
@@ -109,17 +183,19 @@ pub(crate) fn calculate_l1_gas_by_cairo_usage(
     block_context: &BlockContext,
     cairo_resource_usage: &HashMap<String, usize>,
 ) -> Result<f64, TransactionError> {
-    if !cairo_resource_usage
-        .keys()
-        .all(|k| k == "l1_gas_usage" || block_context.cairo_resource_fee_weights.contains_key(k))
-    {
+    if !cairo_resource_usage.keys().all(|k| {
+        k == "l1_gas_usage"
+            || k == "l1_data_gas_usage"
+            || k == "n_memory_holes"
+            || block_context.cairo_resource_fee_weights().contains_key(k)
+    }) {
         return Err(TransactionError::ResourcesError);
     }
 
     // Convert Cairo usage to L1 gas usage.
     Ok(max_of_keys(
         cairo_resource_usage,
-        &block_context.cairo_resource_fee_weights,
+        block_context.cairo_resource_fee_weights(),
     ))
 }
 
@@ -207,7 +283,10 @@ mod tests {
             cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
             in_memory_state_reader::InMemoryStateReader,
         },
-        transaction::fee::charge_fee,
+        transaction::fee::{
+            calculate_initial_gas, calculate_tx_fee, calculate_tx_fee_breakdown, charge_fee,
+            FeeBreakdown,
+        },
     };
     use std::{collections::HashMap, sync::Arc};
 
@@ -279,4 +358,53 @@ mod tests {
 
         assert_eq!(result.1, max_fee);
     }
+
+    #[test]
+    fn fee_breakdown_with_blob_data_gas() {
+        let mut block_context = BlockContext::default();
+        block_context.block_info.use_kzg_da = true;
+        block_context.block_info.l1_data_gas_price = 3;
+        let resources = HashMap::from([
+            ("l1_gas_usage".to_string(), 200_usize),
+            ("l1_data_gas_usage".to_string(), 50_usize),
+        ]);
+
+        let breakdown = calculate_tx_fee_breakdown(&resources, 2, &block_context).unwrap();
+
+        assert_eq!(
+            breakdown,
+            FeeBreakdown {
+                l1_gas_usage: 200,
+                l1_gas_fee: 400,
+                l1_data_gas_usage: 50,
+                l1_data_gas_fee: 150,
+            }
+        );
+        assert_eq!(
+            calculate_tx_fee(&resources, 2, &block_context).unwrap(),
+            550
+        );
+    }
+
+    #[test]
+    fn initial_gas_is_derived_from_the_max_fee() {
+        let mut block_context = BlockContext::default();
+        let initial_gas = block_context.versioned_constants().initial_gas();
+
+        // Without a gas price there's nothing to derive the budget from.
+        block_context.starknet_os_config.gas_price = 0;
+        assert_eq!(calculate_initial_gas(1000, 0, &block_context), initial_gas);
+
+        // 1000 wei pay for 10 L1 gas, which are 1000 steps, which are 100_000 Sierra gas.
+        block_context.starknet_os_config.gas_price = 100;
+        assert_eq!(calculate_initial_gas(1000, 0, &block_context), 100_000);
+        assert_eq!(calculate_initial_gas(1000, 30_000, &block_context), 70_000);
+        assert_eq!(calculate_initial_gas(1000, 200_000, &block_context), 0);
+
+        // The budget never exceeds the protocol's initial gas.
+        assert_eq!(
+            calculate_initial_gas(u128::MAX, 0, &block_context),
+            initial_gas
+        );
+    }
 }
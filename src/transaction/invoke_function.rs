@@ -1,5 +1,5 @@
 use super::{
-    fee::{calculate_tx_fee, charge_fee},
+    fee::{calculate_initial_gas, calculate_tx_fee, charge_fee},
     get_tx_version, Transaction,
 };
 use crate::{
@@ -14,8 +14,7 @@ use crate::{
     execution::{
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         gas_usage::get_onchain_data_segment_length,
-        os_usage::ESTIMATED_INVOKE_FUNCTION_STEPS,
-        CallInfo, TransactionExecutionContext, TransactionExecutionInfo,
+        CallInfo, ExtendedTxInfo, TransactionExecutionContext, TransactionExecutionInfo,
     },
     services::{
         api::contract_classes::{
@@ -49,19 +48,26 @@ use {
 pub struct InvokeFunction {
     #[getset(get = "pub")]
     contract_address: Address,
+    #[getset(get = "pub")]
     entry_point_selector: Felt252,
     #[allow(dead_code)]
     entry_point_type: EntryPointType,
+    #[getset(get = "pub")]
     calldata: Vec<Felt252>,
     tx_type: TransactionType,
+    #[getset(get = "pub")]
     version: Felt252,
     validate_entry_point_selector: Felt252,
     #[getset(get = "pub")]
     hash_value: Felt252,
     #[getset(get = "pub")]
     signature: Vec<Felt252>,
+    #[getset(get = "pub")]
     max_fee: u128,
+    #[getset(get = "pub")]
     nonce: Option<Felt252>,
+    #[getset(get = "pub")]
+    extended_tx_info: ExtendedTxInfo,
     skip_validation: bool,
     skip_execute: bool,
     skip_fee_transfer: bool,
@@ -135,6 +141,7 @@ impl InvokeFunction {
             validate_entry_point_selector,
             nonce,
             hash_value,
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: false,
             skip_execute: false,
             skip_fee_transfer: false,
@@ -143,6 +150,7 @@ impl InvokeFunction {
     }
 
     /// Creates a `InvokeFunction` from a starknet api `InvokeTransaction`.
+    #[deprecated(note = "use `Transaction::try_from` with a `StarknetApiTransaction` instead")]
     pub fn from_invoke_transaction(
         tx: starknet_api::transaction::InvokeTransaction,
         chain_id: StarknetChainId,
@@ -169,7 +177,8 @@ impl InvokeFunction {
             },
             n_steps,
             self.version.clone(),
-        ))
+        )
+        .with_extended_tx_info(self.extended_tx_info.clone()))
     }
 
     /// Execute the validation entrypoint of the contract and returns the call info.
@@ -208,9 +217,9 @@ impl InvokeFunction {
             state,
             block_context,
             resources_manager,
-            &mut self.get_execution_context(block_context.validate_max_n_steps)?,
+            &mut self.get_execution_context(block_context.validate_max_n_steps())?,
             false,
-            block_context.validate_max_n_steps,
+            block_context.validate_max_n_steps(),
             #[cfg(feature = "cairo-native")]
             program_cache,
         )?;
@@ -264,9 +273,9 @@ impl InvokeFunction {
             state,
             block_context,
             resources_manager,
-            &mut self.get_execution_context(block_context.invoke_tx_max_n_steps)?,
+            &mut self.get_execution_context(block_context.invoke_tx_max_n_steps())?,
             true,
-            block_context.invoke_tx_max_n_steps,
+            block_context.invoke_tx_max_n_steps(),
             #[cfg(feature = "cairo-native")]
             program_cache,
         )
@@ -286,6 +295,26 @@ impl InvokeFunction {
         #[cfg(feature = "cairo-native")] program_cache: Option<
             Rc<RefCell<ProgramCache<'_, ClassHash>>>,
         >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        self.apply_with_gas_budget(
+            state,
+            block_context,
+            Some(remaining_gas),
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )
+    }
+
+    /// Like [`InvokeFunction::apply`], but the gas budget of the execute phase is derived from the
+    /// max fee when `remaining_gas` is `None`. See [`calculate_initial_gas`].
+    pub fn apply_with_gas_budget<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        remaining_gas: Option<u128>,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         let mut resources_manager = ExecutionResourcesManager::default();
         let validate_info = if self.skip_validation {
@@ -300,6 +329,17 @@ impl InvokeFunction {
             )?
         };
 
+        let remaining_gas = remaining_gas.unwrap_or_else(|| {
+            calculate_initial_gas(
+                self.max_fee,
+                validate_info
+                    .as_ref()
+                    .map(|info| info.gas_consumed)
+                    .unwrap_or_default(),
+                block_context,
+            )
+        });
+
         // Execute transaction
         let ExecutionResult {
             call_info,
@@ -328,6 +368,7 @@ impl InvokeFunction {
             changes,
             None,
             n_reverted_steps,
+            block_context,
         )?;
         let transaction_execution_info = TransactionExecutionInfo::new_without_fee_info(
             validate_info,
@@ -345,6 +386,27 @@ impl InvokeFunction {
     /// - state: A state that implements the [`State`] and [`StateReader`] traits.
     /// - block_context: The block's execution context.
     /// - remaining_gas: The amount of gas that the transaction disposes.
+    pub fn execute<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        remaining_gas: u128,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        self.execute_with_gas_budget(
+            state,
+            block_context,
+            Some(remaining_gas),
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )
+    }
+
+    /// Like [`InvokeFunction::execute`], but the gas budget of the execute phase is derived from
+    /// the max fee when `remaining_gas` is `None`, as the sequencer does. See
+    /// [`calculate_initial_gas`].
     #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, program_cache), fields(
         tx_type = ?TransactionType::InvokeFunction,
         self.version = ?self.version,
@@ -353,11 +415,11 @@ impl InvokeFunction {
         self.entry_point_selector = ?self.entry_point_selector,
         self.entry_point_type = ?self.entry_point_type,
     ))]
-    pub fn execute<S: StateReader, C: ContractClassCache>(
+    pub fn execute_with_gas_budget<S: StateReader, C: ContractClassCache>(
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        remaining_gas: u128,
+        remaining_gas: Option<u128>,
         #[cfg(feature = "cairo-native")] program_cache: Option<
             Rc<RefCell<ProgramCache<'_, ClassHash>>>,
         >,
@@ -377,7 +439,7 @@ impl InvokeFunction {
         self.handle_nonce(state)?;
 
         let mut transactional_state = state.create_transactional()?;
-        let mut tx_exec_info = self.apply(
+        let mut tx_exec_info = self.apply_with_gas_budget(
             &mut transactional_state,
             block_context,
             remaining_gas,
@@ -409,7 +471,7 @@ impl InvokeFunction {
         }
 
         let mut tx_execution_context =
-            self.get_execution_context(block_context.invoke_tx_max_n_steps)?;
+            self.get_execution_context(block_context.invoke_tx_max_n_steps())?;
         let (fee_transfer_info, actual_fee) = charge_fee(
             state,
             &tx_exec_info.actual_resources,
@@ -452,7 +514,7 @@ impl InvokeFunction {
         }
     }
 
-    fn check_fee_balance<S: State + StateReader>(
+    pub(crate) fn check_fee_balance<S: State + StateReader>(
         &self,
         state: &mut S,
         block_context: &BlockContext,
@@ -480,7 +542,10 @@ impl InvokeFunction {
     }
 
     fn estimate_minimal_fee(&self, block_context: &BlockContext) -> Result<u128, TransactionError> {
-        let n_estimated_steps = ESTIMATED_INVOKE_FUNCTION_STEPS;
+        let n_estimated_steps = block_context
+            .versioned_constants()
+            .os_resources()
+            .estimated_tx_steps(TransactionType::InvokeFunction);
         let onchain_data_length = get_onchain_data_segment_length(&StateChangesCount {
             n_storage_updates: 1,
             n_class_hash_updates: 0,
@@ -501,6 +566,20 @@ impl InvokeFunction {
         )
     }
 
+    /// Returns the transaction with the given signature. The signature isn't part of the
+    /// transaction hash, so it can be set once the hash is known.
+    pub(crate) fn with_signature(self, signature: Vec<Felt252>) -> Self {
+        Self { signature, ..self }
+    }
+
+    /// Sets the fields of the transaction only exposed to contracts reading the extended tx info.
+    pub fn with_extended_tx_info(self, extended_tx_info: ExtendedTxInfo) -> Self {
+        Self {
+            extended_tx_info,
+            ..self
+        }
+    }
+
     // Simulation function
 
     pub fn create_for_simulation(
@@ -677,6 +756,7 @@ mod tests {
     use std::sync::Arc;
 
     #[test]
+    #[allow(deprecated)]
     fn test_from_invoke_transaction() {
         // https://starkscan.co/tx/0x05b6cf416d56e7c7c519b44e6d06a41657ff6c6a3f2629044fac395e6d200ac4
         // result 0x05b6cf416d56e7c7c519b44e6d06a41657ff6c6a3f2629044fac395e6d200ac4
@@ -760,6 +840,7 @@ mod tests {
             signature: Vec::new(),
             max_fee: 0,
             nonce: Some(0.into()),
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: false,
             skip_execute: false,
             skip_fee_transfer: false,
@@ -843,6 +924,7 @@ mod tests {
             signature: Vec::new(),
             max_fee: 0,
             nonce: Some(0.into()),
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: false,
             skip_execute: false,
             skip_fee_transfer: false,
@@ -917,6 +999,7 @@ mod tests {
             signature: Vec::new(),
             max_fee: 0,
             nonce: Some(0.into()),
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: false,
             skip_execute: false,
             skip_fee_transfer: false,
@@ -985,6 +1068,7 @@ mod tests {
             signature: Vec::new(),
             max_fee: 0,
             nonce: None,
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: false,
             skip_execute: false,
             skip_fee_transfer: false,
@@ -1064,6 +1148,7 @@ mod tests {
             signature: Vec::new(),
             max_fee: 0,
             nonce: None,
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: false,
             skip_execute: false,
             skip_fee_transfer: false,
@@ -1149,6 +1234,7 @@ mod tests {
             signature: Vec::new(),
             max_fee: 1000,
             nonce: Some(0.into()),
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: false,
             skip_execute: false,
             skip_fee_transfer: false,
@@ -1202,6 +1288,7 @@ mod tests {
             signature: Vec::new(),
             max_fee,
             nonce: Some(0.into()),
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: false,
             skip_execute: false,
             skip_fee_transfer: true,
@@ -1278,6 +1365,7 @@ mod tests {
             signature: Vec::new(),
             max_fee: 0,
             nonce: Some(0.into()),
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: false,
             skip_execute: false,
             skip_fee_transfer: false,
@@ -1355,6 +1443,7 @@ mod tests {
             signature: Vec::new(),
             max_fee: 0,
             nonce: None,
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: false,
             skip_execute: false,
             skip_fee_transfer: false,
@@ -1504,6 +1593,7 @@ mod tests {
             signature: Vec::new(),
             max_fee: 0,
             nonce: Some(0.into()),
+            extended_tx_info: ExtendedTxInfo::default(),
             skip_validation: true,
             skip_execute: false,
             skip_fee_transfer: true,
@@ -1606,4 +1696,29 @@ mod tests {
         Err(TransactionError::UnsupportedTxVersion(tx, ver, supp))
         if tx == "Invoke" && ver == 2.into() && supp == vec![0, 1]);
     }
+
+    #[test]
+    fn execution_context_exposes_the_extended_tx_info() {
+        let extended_tx_info = ExtendedTxInfo {
+            tip: 3,
+            paymaster_data: vec![4.into()],
+            account_deployment_data: vec![5.into()],
+            ..Default::default()
+        };
+        let invoke_function = InvokeFunction::new(
+            Address(Felt252::one()),
+            Felt252::one(),
+            0,
+            1.into(),
+            vec![],
+            vec![],
+            StarknetChainId::TestNet.to_felt(),
+            Some(Felt252::zero()),
+        )
+        .unwrap()
+        .with_extended_tx_info(extended_tx_info.clone());
+
+        let execution_context = invoke_function.get_execution_context(10).unwrap();
+        assert_eq!(execution_context.extended_tx_info(), &extended_tx_info);
+    }
 }
@@ -0,0 +1,1110 @@
+//! # JSON transactions
+//!
+//! (De)serialization of transactions in the Starknet JSON-RPC and feeder-gateway formats.
+//!
+//! A [`Transaction`] deserializes from the RPC `*_TXN` shapes and from the feeder gateway's
+//! transactions, which carry their `transaction_hash`. Declare (and deploy) transactions must also
+//! carry the contract class they declare, either as a Cairo 0 class with its program compressed
+//! (or in plain JSON) or as a Sierra class, as in the RPC `BROADCASTED_DECLARE_TXN` shapes and the
+//! gateway's `add_transaction` payloads.
+//!
+//! Broadcasted transactions don't carry their hash, which depends on the chain they're sent to. They
+//! are deserialized as a [`BroadcastedTransaction`] and turned into a [`Transaction`] once the chain
+//! is known:
+//!
+//! ```ignore
+//! let tx = serde_json::from_str::<BroadcastedTransaction>(json)?
+//!     .into_transaction(StarknetChainId::TestNet.to_felt())?;
+//! tx.execute(&mut state, &block_context, remaining_gas)?;
+//! ```
+//!
+//! Transactions serialize to the RPC `*_TXN` shapes, and each kind of transaction can also be
+//! (de)serialized on its own, e.g. as an [`InvokeFunction`]. Cairo 1 declares include their Sierra
+//! class, without its debug info. The programs of Cairo 0 classes can't be serialized back, so
+//! serializing a Cairo 0 declare or deploy fails.
+//!
+//! The RPC returns declares with only the hash of their class. Deserializing them fails with
+//! [`TransactionError::MissingContractClass`] unless the class is bundled with the transaction:
+//!
+//! ```ignore
+//! let tx = serde_json::from_str::<BroadcastedTransaction>(json)?
+//!     .with_contract_class(contract_class)
+//!     .into_transaction(StarknetChainId::TestNet.to_felt())?;
+//! ```
+
+use super::{
+    error::TransactionError, get_tx_version, Declare, DeclareV2, Deploy, DeployAccount,
+    InvokeFunction, L1Handler, Transaction,
+};
+use crate::{
+    core::transaction_hash::calculate_deploy_transaction_hash,
+    definitions::{
+        constants::{EXECUTE_ENTRY_POINT_SELECTOR, L1_HANDLER_VERSION},
+        transaction_type::TransactionType,
+    },
+    hash_utils::calculate_contract_address,
+    services::api::contract_classes::{
+        compiled_class::{decode_reader, CompiledClass},
+        deprecated_contract_class::ContractClass,
+    },
+    utils::{felt_to_hash, Address},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use cairo_lang_starknet::contract_class::ContractClass as SierraContractClass;
+use cairo_vm::felt::Felt252;
+use num_traits::{Num, ToPrimitive, Zero};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::str::FromStr;
+
+/// A transaction in any of the JSON formats, including the broadcasted ones, which don't carry
+/// their hash, along with the class it declares or deploys if it doesn't carry it. See the
+/// [module docs](self).
+#[derive(Clone, Debug, PartialEq)]
+pub struct BroadcastedTransaction {
+    json: Value,
+    contract_class: Option<CompiledClass>,
+}
+
+impl BroadcastedTransaction {
+    /// Sets the class declared or deployed by a transaction which only carries its hash. A Cairo 1
+    /// declare may also carry its compiled class.
+    pub fn with_contract_class(self, contract_class: CompiledClass) -> Self {
+        Self {
+            contract_class: Some(contract_class),
+            ..self
+        }
+    }
+
+    /// Builds the transaction, calculating its hash for the given chain if it doesn't carry one.
+    pub fn into_transaction(self, chain_id: Felt252) -> Result<Transaction, TransactionError> {
+        parse_transaction(&self.json, Some(chain_id), self.contract_class)
+    }
+}
+
+impl<'de> Deserialize<'de> for BroadcastedTransaction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self {
+            json: Value::deserialize(deserializer)?,
+            contract_class: None,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Transaction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        parse_transaction(&value, None, None).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for Transaction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Transaction::InvokeFunction(tx) => tx.serialize(serializer),
+            Transaction::DeclareV2(tx) => tx.serialize(serializer),
+            Transaction::DeployAccount(tx) => tx.serialize(serializer),
+            Transaction::L1Handler(tx) => tx.serialize(serializer),
+            Transaction::Declare(_) | Transaction::Deploy(_) => Err(serde::ser::Error::custom(
+                "Cairo 0 declare and deploy transactions can't be serialized, as the programs of \
+                 their classes can't",
+            )),
+        }
+    }
+}
+
+impl Serialize for InvokeFunction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        invoke_json(self).serialize(serializer)
+    }
+}
+
+impl Serialize for DeclareV2 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        declare_v2_json(self)
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl Serialize for DeployAccount {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        deploy_account_json(self).serialize(serializer)
+    }
+}
+
+impl Serialize for L1Handler {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        l1_handler_json(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InvokeFunction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_variant(
+            deserializer,
+            TransactionType::InvokeFunction,
+            |tx| match tx {
+                Transaction::InvokeFunction(tx) => Some(tx),
+                _ => None,
+            },
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for Declare {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_variant(deserializer, TransactionType::Declare, |tx| match tx {
+            Transaction::Declare(tx) => Some(tx),
+            _ => None,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for DeclareV2 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_variant(deserializer, TransactionType::Declare, |tx| match tx {
+            Transaction::DeclareV2(tx) => Some(*tx),
+            _ => None,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Deploy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_variant(deserializer, TransactionType::Deploy, |tx| match tx {
+            Transaction::Deploy(tx) => Some(tx),
+            _ => None,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for DeployAccount {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_variant(
+            deserializer,
+            TransactionType::DeployAccount,
+            |tx| match tx {
+                Transaction::DeployAccount(tx) => Some(tx),
+                _ => None,
+            },
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for L1Handler {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_variant(deserializer, TransactionType::L1Handler, |tx| match tx {
+            Transaction::L1Handler(tx) => Some(tx),
+            _ => None,
+        })
+    }
+}
+
+/// Deserializes a [`Transaction`] and takes the variant out of it, failing if it's of another
+/// kind.
+fn deserialize_variant<'de, D, T>(
+    deserializer: D,
+    expected: TransactionType,
+    variant: impl FnOnce(Transaction) -> Option<T>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tx = Transaction::deserialize(deserializer)?;
+    let tx_type = tx.tx_type();
+    variant(tx).ok_or_else(|| {
+        serde::de::Error::custom(format!(
+            "expected a {expected:?} transaction, found a {tx_type:?} one"
+        ))
+    })
+}
+
+// ----------------------
+//    Deserialization
+// ----------------------
+
+fn parse_transaction(
+    json: &Value,
+    chain_id: Option<Felt252>,
+    contract_class: Option<CompiledClass>,
+) -> Result<Transaction, TransactionError> {
+    let hash_value = optional_felt(json, "transaction_hash")?;
+    if hash_value.is_none() && chain_id.is_none() {
+        return Err(invalid("missing field `transaction_hash`"));
+    }
+    let chain_id = chain_id.unwrap_or_default();
+    let version = optional_felt(json, "version")?.unwrap_or_default();
+    // Query versions are only used to pick the transaction's shape.
+    let base_version = get_tx_version(version.clone());
+    let max_fee = optional_felt(json, "max_fee")?
+        .unwrap_or_default()
+        .to_u128()
+        .ok_or_else(|| invalid("`max_fee` doesn't fit in 128 bits"))?;
+    let signature = felts(json, "signature")?;
+
+    let tx_type = json
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("missing field `type`"))?;
+    match tx_type {
+        "INVOKE" | "INVOKE_FUNCTION" => {
+            let contract_address = Address(match optional_felt(json, "sender_address")? {
+                Some(sender_address) => sender_address,
+                None => felt(json, "contract_address")?,
+            });
+            let (entry_point_selector, nonce) = if base_version.is_zero() {
+                (felt(json, "entry_point_selector")?, None)
+            } else {
+                (
+                    EXECUTE_ENTRY_POINT_SELECTOR.clone(),
+                    Some(felt(json, "nonce")?),
+                )
+            };
+            let calldata = felts(json, "calldata")?;
+
+            let tx = match hash_value {
+                Some(hash_value) => InvokeFunction::new_with_tx_hash(
+                    contract_address,
+                    entry_point_selector,
+                    max_fee,
+                    version,
+                    calldata,
+                    signature,
+                    nonce,
+                    hash_value,
+                ),
+                None => InvokeFunction::new(
+                    contract_address,
+                    entry_point_selector,
+                    max_fee,
+                    version,
+                    calldata,
+                    signature,
+                    chain_id,
+                    nonce,
+                ),
+            }?;
+            Ok(Transaction::InvokeFunction(tx))
+        }
+        "DECLARE" if base_version < 2.into() => {
+            let contract_class = match (contract_class_json(json), contract_class) {
+                (Some(contract_class), _) => deprecated_contract_class(contract_class)?,
+                (None, Some(CompiledClass::Deprecated(contract_class))) => {
+                    contract_class.as_ref().clone()
+                }
+                (None, _) => {
+                    return Err(TransactionError::MissingContractClass(
+                        TransactionType::Declare,
+                    ))
+                }
+            };
+            let sender_address = Address(felt(json, "sender_address")?);
+            let nonce = optional_felt(json, "nonce")?.unwrap_or_default();
+
+            let tx = match hash_value {
+                Some(hash_value) => Declare::new_with_tx_hash(
+                    contract_class,
+                    sender_address,
+                    max_fee,
+                    version,
+                    signature,
+                    nonce,
+                    hash_value,
+                ),
+                None => Declare::new(
+                    contract_class,
+                    chain_id,
+                    sender_address,
+                    max_fee,
+                    version,
+                    signature,
+                    nonce,
+                ),
+            }?;
+            Ok(Transaction::Declare(tx))
+        }
+        "DECLARE" => {
+            let casm_class = match contract_class {
+                Some(CompiledClass::Casm(casm_class)) => Some(casm_class.as_ref().clone()),
+                _ => None,
+            };
+            let compiled_class_hash = felt(json, "compiled_class_hash")?;
+            let sender_address = Address(felt(json, "sender_address")?);
+            let nonce = felt(json, "nonce")?;
+
+            let Some(contract_class) = contract_class_json(json) else {
+                if casm_class.is_none() {
+                    return Err(TransactionError::MissingContractClass(
+                        TransactionType::Declare,
+                    ));
+                }
+                let sierra_class_hash = felt(json, "class_hash")?;
+                let tx = match hash_value {
+                    Some(hash_value) => DeclareV2::new_with_sierra_class_hash_and_tx_hash(
+                        None,
+                        sierra_class_hash,
+                        casm_class,
+                        compiled_class_hash,
+                        sender_address,
+                        max_fee,
+                        version,
+                        signature,
+                        nonce,
+                        hash_value,
+                    ),
+                    None => DeclareV2::new_with_sierra_class_hash(
+                        None,
+                        sierra_class_hash,
+                        casm_class,
+                        compiled_class_hash,
+                        chain_id,
+                        sender_address,
+                        max_fee,
+                        version,
+                        signature,
+                        nonce,
+                    ),
+                }?;
+                return Ok(Transaction::DeclareV2(Box::new(tx)));
+            };
+            let sierra_contract_class = sierra_contract_class(contract_class)?;
+
+            let tx = match hash_value {
+                Some(hash_value) => DeclareV2::new_with_tx_hash(
+                    &sierra_contract_class,
+                    casm_class,
+                    compiled_class_hash,
+                    sender_address,
+                    max_fee,
+                    version,
+                    signature,
+                    nonce,
+                    hash_value,
+                ),
+                None => DeclareV2::new(
+                    &sierra_contract_class,
+                    casm_class,
+                    compiled_class_hash,
+                    chain_id,
+                    sender_address,
+                    max_fee,
+                    version,
+                    signature,
+                    nonce,
+                ),
+            }?;
+            Ok(Transaction::DeclareV2(Box::new(tx)))
+        }
+        "DEPLOY" => {
+            let contract_address_salt = felt(json, "contract_address_salt")?;
+            let constructor_calldata = felts(json, "constructor_calldata")?;
+
+            let Some(contract_class) = contract_class_json(json) else {
+                let contract_class = contract_class.ok_or(
+                    TransactionError::MissingContractClass(TransactionType::Deploy),
+                )?;
+                let class_hash = felt(json, "class_hash")?;
+                let contract_address = Address(calculate_contract_address(
+                    &contract_address_salt,
+                    &class_hash,
+                    &constructor_calldata,
+                    Address(Felt252::zero()),
+                )?);
+                let hash_value = match hash_value {
+                    Some(hash_value) => hash_value,
+                    None => calculate_deploy_transaction_hash(
+                        version.clone(),
+                        &contract_address,
+                        &constructor_calldata,
+                        chain_id,
+                    )?,
+                };
+
+                return Ok(Transaction::Deploy(Deploy {
+                    hash_value,
+                    version,
+                    contract_address,
+                    contract_address_salt,
+                    contract_hash: felt_to_hash(&class_hash),
+                    contract_class,
+                    constructor_calldata,
+                    skip_validate: false,
+                    skip_execute: false,
+                    skip_fee_transfer: false,
+                }));
+            };
+            let contract_class = deprecated_contract_class(contract_class)?;
+
+            let tx = match hash_value {
+                Some(hash_value) => Deploy::new_with_tx_hash(
+                    contract_address_salt,
+                    contract_class,
+                    constructor_calldata,
+                    version,
+                    hash_value,
+                ),
+                None => Deploy::new(
+                    contract_address_salt,
+                    contract_class,
+                    constructor_calldata,
+                    chain_id,
+                    version,
+                ),
+            }?;
+            Ok(Transaction::Deploy(tx))
+        }
+        "DEPLOY_ACCOUNT" => {
+            let class_hash = felt_to_hash(&felt(json, "class_hash")?);
+            let nonce = felt(json, "nonce")?;
+            let constructor_calldata = felts(json, "constructor_calldata")?;
+            let contract_address_salt = felt(json, "contract_address_salt")?;
+
+            let tx = match hash_value {
+                Some(hash_value) => DeployAccount::new_with_tx_hash(
+                    class_hash,
+                    max_fee,
+                    version,
+                    nonce,
+                    constructor_calldata,
+                    signature,
+                    contract_address_salt,
+                    hash_value,
+                ),
+                None => DeployAccount::new(
+                    class_hash,
+                    max_fee,
+                    version,
+                    nonce,
+                    constructor_calldata,
+                    signature,
+                    contract_address_salt,
+                    chain_id,
+                ),
+            }?;
+            Ok(Transaction::DeployAccount(tx))
+        }
+        "L1_HANDLER" => {
+            let contract_address = Address(felt(json, "contract_address")?);
+            let entry_point_selector = felt(json, "entry_point_selector")?;
+            let calldata = felts(json, "calldata")?;
+            let nonce = optional_felt(json, "nonce")?.unwrap_or_default();
+
+            let tx = match hash_value {
+                Some(hash_value) => L1Handler::new_with_tx_hash(
+                    contract_address,
+                    entry_point_selector,
+                    calldata,
+                    nonce,
+                    None,
+                    hash_value,
+                ),
+                None => L1Handler::new(
+                    contract_address,
+                    entry_point_selector,
+                    calldata,
+                    nonce,
+                    chain_id,
+                    None,
+                ),
+            }?;
+            Ok(Transaction::L1Handler(tx))
+        }
+        tx_type => Err(invalid(format!("unknown transaction type `{tx_type}`"))),
+    }
+}
+
+/// Returns the contract class carried by a declare or deploy transaction, if any.
+fn contract_class_json(json: &Value) -> Option<&Value> {
+    ["contract_class", "contract_definition"]
+        .into_iter()
+        .find_map(|field| json.get(field).filter(|value| !value.is_null()))
+}
+
+/// Parses a Cairo 0 contract class, whose program may be compressed.
+fn deprecated_contract_class(json: &Value) -> Result<ContractClass, TransactionError> {
+    let program = match json.get("program") {
+        Some(Value::String(compressed_program)) => decompress(compressed_program)?,
+        Some(program) => program.clone(),
+        None => return Err(invalid("missing field `program` in the contract class")),
+    };
+    let contract_class = json!({
+        "program": program,
+        "entry_points_by_type": json.get("entry_points_by_type").cloned().unwrap_or_default(),
+        "abi": json.get("abi").cloned().unwrap_or_default(),
+    });
+
+    ContractClass::from_str(&contract_class.to_string())
+        .map_err(|err| invalid(format!("invalid contract class: {err}")))
+}
+
+/// Parses a Sierra contract class, whose program may be compressed and whose ABI may be given as
+/// a string.
+fn sierra_contract_class(json: &Value) -> Result<SierraContractClass, TransactionError> {
+    let mut json = json.clone();
+    if let Some(Value::String(compressed_program)) = json.get("sierra_program") {
+        json["sierra_program"] = decompress(compressed_program)?;
+    }
+    if let Some(Value::String(abi)) = json.get("abi") {
+        json["abi"] = if abi.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(abi)
+                .map_err(|err| invalid(format!("invalid contract class ABI: {err}")))?
+        };
+    }
+
+    serde_json::from_value(json).map_err(|err| invalid(format!("invalid contract class: {err}")))
+}
+
+/// Decodes a base64 encoded, gzip compressed JSON value.
+fn decompress(compressed: &str) -> Result<Value, TransactionError> {
+    let bytes = STANDARD
+        .decode(compressed)
+        .map_err(|err| invalid(format!("invalid base64: {err}")))?;
+    let json =
+        decode_reader(bytes).map_err(|err| invalid(format!("invalid compressed data: {err}")))?;
+    serde_json::from_str(&json).map_err(|err| invalid(format!("invalid compressed JSON: {err}")))
+}
+
+fn felt(json: &Value, field: &str) -> Result<Felt252, TransactionError> {
+    optional_felt(json, field)?.ok_or_else(|| invalid(format!("missing field `{field}`")))
+}
+
+fn optional_felt(json: &Value, field: &str) -> Result<Option<Felt252>, TransactionError> {
+    match json.get(field) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => parse_felt(value)
+            .map(Some)
+            .ok_or_else(|| invalid(format!("invalid felt in field `{field}`: {value}"))),
+    }
+}
+
+fn felts(json: &Value, field: &str) -> Result<Vec<Felt252>, TransactionError> {
+    match json.get(field) {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|value| {
+                parse_felt(value)
+                    .ok_or_else(|| invalid(format!("invalid felt in field `{field}`: {value}")))
+            })
+            .collect(),
+        Some(value) => Err(invalid(format!(
+            "expected an array in field `{field}`: {value}"
+        ))),
+    }
+}
+
+/// Parses a felt given as a hex string, a decimal string or a number.
+fn parse_felt(value: &Value) -> Option<Felt252> {
+    match value {
+        Value::String(string) => match string.strip_prefix("0x") {
+            Some(hex) => Felt252::from_str_radix(hex, 16).ok(),
+            None => Felt252::from_str_radix(string, 10).ok(),
+        },
+        Value::Number(number) => Felt252::from_str_radix(&number.to_string(), 10).ok(),
+        _ => None,
+    }
+}
+
+fn invalid(message: impl Into<String>) -> TransactionError {
+    TransactionError::InvalidTransactionJson(message.into())
+}
+
+// ----------------------
+//     Serialization
+// ----------------------
+
+fn invoke_json(tx: &InvokeFunction) -> Value {
+    if tx.version().is_zero() {
+        json!({
+            "transaction_hash": hex(tx.hash_value()),
+            "type": "INVOKE",
+            "version": hex(tx.version()),
+            "max_fee": hex(&Felt252::from(*tx.max_fee())),
+            "signature": hex_array(tx.signature()),
+            "contract_address": hex(&tx.contract_address().0),
+            "entry_point_selector": hex(tx.entry_point_selector()),
+            "calldata": hex_array(tx.calldata()),
+        })
+    } else {
+        json!({
+            "transaction_hash": hex(tx.hash_value()),
+            "type": "INVOKE",
+            "version": hex(tx.version()),
+            "max_fee": hex(&Felt252::from(*tx.max_fee())),
+            "signature": hex_array(tx.signature()),
+            "nonce": hex(&tx.nonce().clone().unwrap_or_default()),
+            "sender_address": hex(&tx.contract_address().0),
+            "calldata": hex_array(tx.calldata()),
+        })
+    }
+}
+
+fn declare_v2_json(tx: &DeclareV2) -> Result<Value, serde_json::Error> {
+    let mut json = json!({
+        "transaction_hash": hex(&tx.hash_value),
+        "type": "DECLARE",
+        "version": hex(&tx.version),
+        "max_fee": hex(&Felt252::from(tx.max_fee)),
+        "signature": hex_array(&tx.signature),
+        "nonce": hex(&tx.nonce),
+        "class_hash": hex(&tx.sierra_class_hash),
+        "compiled_class_hash": hex(&tx.compiled_class_hash),
+        "sender_address": hex(&tx.sender_address.0),
+    });
+    if let Some(sierra_contract_class) = &tx.sierra_contract_class {
+        json["contract_class"] = rpc_sierra_contract_class(sierra_contract_class)?;
+    }
+    Ok(json)
+}
+
+/// Returns the Sierra class in the RPC shape, which has no debug info and the ABI as a string.
+fn rpc_sierra_contract_class(
+    sierra_contract_class: &SierraContractClass,
+) -> Result<Value, serde_json::Error> {
+    let mut json = serde_json::to_value(sierra_contract_class)?;
+    if let Value::Object(fields) = &mut json {
+        fields.remove("sierra_program_debug_info");
+        let abi = match fields.remove("abi") {
+            None | Some(Value::Null) => String::new(),
+            Some(abi) => abi.to_string(),
+        };
+        fields.insert("abi".to_string(), Value::String(abi));
+    }
+    Ok(json)
+}
+
+fn deploy_account_json(tx: &DeployAccount) -> Value {
+    json!({
+        "transaction_hash": hex(tx.hash_value()),
+        "type": "DEPLOY_ACCOUNT",
+        "version": hex(tx.version()),
+        "max_fee": hex(&Felt252::from(*tx.max_fee())),
+        "signature": hex_array(tx.signature()),
+        "nonce": hex(tx.nonce()),
+        "contract_address_salt": hex(tx.contract_address_salt()),
+        "constructor_calldata": hex_array(tx.constructor_calldata()),
+        "class_hash": hex(&Felt252::from_bytes_be(tx.class_hash().to_bytes_be())),
+    })
+}
+
+fn l1_handler_json(tx: &L1Handler) -> Value {
+    json!({
+        "transaction_hash": hex(tx.hash_value()),
+        "type": "L1_HANDLER",
+        "version": hex(&Felt252::from(L1_HANDLER_VERSION)),
+        "nonce": hex(&tx.nonce().clone().unwrap_or_default()),
+        "contract_address": hex(&tx.contract_address().0),
+        "entry_point_selector": hex(tx.entry_point_selector()),
+        "calldata": hex_array(tx.calldata()),
+    })
+}
+
+pub(super) fn hex(felt: &Felt252) -> String {
+    format!("0x{}", felt.to_str_radix(16))
+}
+
+pub(super) fn hex_array(felts: &[Felt252]) -> Vec<String> {
+    felts.iter().map(hex).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::contract_address::{compute_casm_class_hash, compute_sierra_class_hash},
+        definitions::block_context::StarknetChainId,
+        CasmContractClass,
+    };
+    use assert_matches::assert_matches;
+    use coverage_helper::test;
+    use flate2::{write::GzEncoder, Compression};
+    use pretty_assertions_sorted::assert_eq;
+    use std::io::Write;
+
+    fn invoke_json() -> Value {
+        json!({
+            "type": "INVOKE",
+            "version": "0x1",
+            "max_fee": "0x2c90b3981000",
+            "signature": [
+                "0x18315db8eb360a82ea11f302d6a6a35a11b9df1dc220ec1376c4d4604770dd4",
+                "0x5e8642259ac8e99c84cdf88c17385698150eb11dccfb3036ecc2b97c0903d27",
+            ],
+            "nonce": "0x16",
+            "sender_address": "0xc4658311841a69ce121543af332622bc243cf5593fc4aaf822481c7b7f183d",
+            "calldata": [
+                "0x1",
+                "0x454f0bd015e730e5adbb4f080b075fdbf55654ff41ee336203aa2e1ac4d4309",
+                "0x32a99297e1d12a9b91d4f90d5dd4b160d93c84a9e3b4daa916fec14ec852e05",
+                "0x0",
+                "0x2",
+                "0x2",
+                "0x383538353434346334616431626237363933663435643237376236313461663",
+                "0x393762666334373463313762393535303530383563613961323435643965666",
+            ],
+        })
+    }
+
+    #[test]
+    fn broadcasted_invoke_is_hashed_for_the_chain() {
+        let json = invoke_json();
+        assert!(serde_json::from_value::<Transaction>(json.clone()).is_err());
+
+        let tx = serde_json::from_value::<BroadcastedTransaction>(json)
+            .unwrap()
+            .into_transaction(StarknetChainId::MainNet.to_felt())
+            .unwrap();
+        assert_eq!(
+            hex(tx.hash_value()),
+            "0x5b6cf416d56e7c7c519b44e6d06a41657ff6c6a3f2629044fac395e6d200ac4"
+        );
+        assert_eq!(tx.nonce(), Some(&Felt252::from(22)));
+        assert_eq!(tx.max_fee(), 49000000000000);
+    }
+
+    #[test]
+    fn rpc_transactions_round_trip() {
+        let mut invoke = invoke_json();
+        invoke["transaction_hash"] =
+            json!("0x5b6cf416d56e7c7c519b44e6d06a41657ff6c6a3f2629044fac395e6d200ac4");
+        let l1_handler = json!({
+            "transaction_hash": "0x1234",
+            "type": "L1_HANDLER",
+            "version": "0x0",
+            "nonce": "0x5",
+            "contract_address": "0x42",
+            "entry_point_selector": "0x2d757788a8d8d6f21d1cd40bce38a8222d70654214e96ff95d8086e684fbee5",
+            "calldata": ["0x8359e4b0152ed5a731162d3c7b0d8d56edb165a0", "0x1", "0x2"],
+        });
+
+        let invoke_v0 = json!({
+            "transaction_hash": "0x1234",
+            "type": "INVOKE",
+            "version": "0x0",
+            "max_fee": "0x0",
+            "signature": [],
+            "contract_address": "0x42",
+            "entry_point_selector": "0x43",
+            "calldata": ["0x1", "0x2"],
+        });
+        let deploy_account = json!({
+            "transaction_hash": "0x1234",
+            "type": "DEPLOY_ACCOUNT",
+            "version": "0x1",
+            "max_fee": "0x10",
+            "signature": ["0x5"],
+            "nonce": "0x0",
+            "contract_address_salt": "0x2",
+            "constructor_calldata": ["0x3"],
+            "class_hash": "0x4",
+        });
+
+        for json in [&invoke, &invoke_v0, &l1_handler, &deploy_account] {
+            let tx: Transaction = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(&serde_json::to_value(&tx).unwrap(), json);
+        }
+
+        // Each kind of transaction round-trips on its own too.
+        let tx: InvokeFunction = serde_json::from_value(invoke.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&tx).unwrap(), invoke);
+        let tx: L1Handler = serde_json::from_value(l1_handler.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&tx).unwrap(), l1_handler);
+        let tx: DeployAccount = serde_json::from_value(deploy_account.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&tx).unwrap(), deploy_account);
+        assert!(serde_json::from_value::<InvokeFunction>(l1_handler).is_err());
+    }
+
+    /// Serializes the transaction and parses it back, bundling the given class.
+    fn round_trip(tx: &Transaction, contract_class: Option<CompiledClass>) -> Transaction {
+        let json = serde_json::to_value(tx).unwrap();
+        let mut broadcasted: BroadcastedTransaction = serde_json::from_value(json.clone()).unwrap();
+        if let Some(contract_class) = contract_class {
+            broadcasted = broadcasted.with_contract_class(contract_class);
+        }
+        let parsed = broadcasted
+            .into_transaction(StarknetChainId::TestNet.to_felt())
+            .unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+        parsed
+    }
+
+    fn sierra_fibonacci() -> SierraContractClass {
+        serde_json::from_slice(include_bytes!(
+            "../../starknet_programs/raw_contract_classes/fibonacci.sierra"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn cairo_0_declare_is_parsed_with_the_bundled_class() {
+        let contract_class = ContractClass::from_path("starknet_programs/fibonacci.json").unwrap();
+        let tx = Declare::new(
+            contract_class.clone(),
+            StarknetChainId::TestNet.to_felt(),
+            Address(1.into()),
+            100,
+            1.into(),
+            vec![2.into()],
+            3.into(),
+        )
+        .unwrap();
+
+        // The program of the class can't be serialized.
+        assert!(serde_json::to_value(Transaction::Declare(tx.clone())).is_err());
+
+        let json = json!({
+            "transaction_hash": hex(&tx.hash_value),
+            "type": "DECLARE",
+            "version": "0x1",
+            "max_fee": "0x64",
+            "signature": ["0x2"],
+            "nonce": "0x3",
+            "class_hash": hex(&Felt252::from_bytes_be(tx.class_hash.to_bytes_be())),
+            "sender_address": "0x1",
+        });
+        assert_matches!(
+            parse_transaction(&json, None, None),
+            Err(TransactionError::MissingContractClass(
+                TransactionType::Declare
+            ))
+        );
+
+        let parsed = serde_json::from_value::<BroadcastedTransaction>(json)
+            .unwrap()
+            .with_contract_class(CompiledClass::Deprecated(contract_class.clone().into()))
+            .into_transaction(StarknetChainId::TestNet.to_felt())
+            .unwrap();
+        let Transaction::Declare(parsed) = parsed else {
+            panic!("expected a declare transaction");
+        };
+        assert_eq!(parsed.hash_value, tx.hash_value);
+        assert_eq!(parsed.class_hash, tx.class_hash);
+        assert_eq!(parsed.contract_class, contract_class);
+    }
+
+    #[test]
+    fn deploy_is_parsed_with_the_bundled_class() {
+        let contract_class = ContractClass::from_path("starknet_programs/fibonacci.json").unwrap();
+        let tx = Deploy::new(
+            5.into(),
+            contract_class.clone(),
+            vec![6.into()],
+            StarknetChainId::TestNet.to_felt(),
+            0.into(),
+        )
+        .unwrap();
+
+        assert!(serde_json::to_value(Transaction::Deploy(tx.clone())).is_err());
+
+        let json = json!({
+            "transaction_hash": hex(&tx.hash_value),
+            "type": "DEPLOY",
+            "version": "0x0",
+            "contract_address_salt": "0x5",
+            "constructor_calldata": ["0x6"],
+            "class_hash": hex(&Felt252::from_bytes_be(tx.contract_hash.to_bytes_be())),
+        });
+        assert_matches!(
+            parse_transaction(&json, None, None),
+            Err(TransactionError::MissingContractClass(
+                TransactionType::Deploy
+            ))
+        );
+
+        let parsed = serde_json::from_value::<BroadcastedTransaction>(json)
+            .unwrap()
+            .with_contract_class(CompiledClass::Deprecated(contract_class.into()))
+            .into_transaction(StarknetChainId::TestNet.to_felt())
+            .unwrap();
+        let Transaction::Deploy(parsed) = parsed else {
+            panic!("expected a deploy transaction");
+        };
+        assert_eq!(parsed.hash_value, tx.hash_value);
+        assert_eq!(parsed.contract_address, tx.contract_address);
+        assert_eq!(parsed.contract_class, tx.contract_class);
+    }
+
+    #[test]
+    fn sierra_declare_round_trips() {
+        let sierra_contract_class = sierra_fibonacci();
+        let sierra_class_hash = compute_sierra_class_hash(&sierra_contract_class).unwrap();
+        let tx = Transaction::DeclareV2(Box::new(
+            DeclareV2::new(
+                &sierra_contract_class,
+                None,
+                7.into(),
+                StarknetChainId::TestNet.to_felt(),
+                Address(1.into()),
+                100,
+                2.into(),
+                vec![2.into()],
+                3.into(),
+            )
+            .unwrap(),
+        ));
+
+        // The Sierra class is serialized in the RPC shape, so the transaction deserializes on its
+        // own.
+        let json = serde_json::to_value(&tx).unwrap();
+        assert!(json["contract_class"]["abi"].is_string());
+        assert!(json["contract_class"]
+            .get("sierra_program_debug_info")
+            .is_none());
+        let parsed: DeclareV2 = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+
+        let Transaction::DeclareV2(parsed) = round_trip(&tx, None) else {
+            panic!("expected a declare v2 transaction");
+        };
+        assert_eq!(
+            parsed.sierra_contract_class,
+            Some(SierraContractClass {
+                sierra_program_debug_info: None,
+                ..sierra_contract_class
+            })
+        );
+        assert_eq!(parsed.sierra_class_hash, sierra_class_hash);
+    }
+
+    #[test]
+    fn compiled_declare_round_trips_with_its_class() {
+        let sierra_contract_class = sierra_fibonacci();
+        let casm_class =
+            CasmContractClass::from_contract_class(sierra_contract_class.clone(), true).unwrap();
+        let tx = Transaction::DeclareV2(Box::new(
+            DeclareV2::new_with_sierra_class_hash(
+                None,
+                compute_sierra_class_hash(&sierra_contract_class).unwrap(),
+                Some(casm_class.clone()),
+                compute_casm_class_hash(&casm_class).unwrap(),
+                StarknetChainId::TestNet.to_felt(),
+                Address(1.into()),
+                100,
+                2.into(),
+                vec![2.into()],
+                3.into(),
+            )
+            .unwrap(),
+        ));
+
+        let json = serde_json::to_value(&tx).unwrap();
+        assert_matches!(
+            parse_transaction(&json, None, None),
+            Err(TransactionError::MissingContractClass(
+                TransactionType::Declare
+            ))
+        );
+
+        let Transaction::DeclareV2(parsed) =
+            round_trip(&tx, Some(CompiledClass::Casm(casm_class.clone().into())))
+        else {
+            panic!("expected a declare v2 transaction");
+        };
+        assert_eq!(parsed.casm_class, Some(casm_class));
+    }
+
+    #[test]
+    fn feeder_gateway_invoke() {
+        let json = json!({
+            "transaction_hash": "0x1234",
+            "type": "INVOKE_FUNCTION",
+            "version": "0x0",
+            "max_fee": "0x0",
+            "signature": [],
+            "contract_address": "0x42",
+            "entry_point_selector": "0x43",
+            "calldata": ["0x1", "0x2"],
+        });
+
+        let Transaction::InvokeFunction(tx) = serde_json::from_value(json).unwrap() else {
+            panic!("expected an invoke transaction");
+        };
+        assert_eq!(tx.contract_address(), &Address(0x42.into()));
+        assert_eq!(tx.entry_point_selector(), &Felt252::from(0x43));
+        assert_eq!(tx.calldata(), &vec![1.into(), 2.into()]);
+        assert_eq!(tx.nonce(), &None);
+    }
+
+    #[test]
+    fn broadcasted_declare_with_compressed_program() {
+        let contract_json: Value = serde_json::from_str(
+            &std::fs::read_to_string("starknet_programs/fibonacci.json").unwrap(),
+        )
+        .unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(contract_json["program"].to_string().as_bytes())
+            .unwrap();
+        let compressed_program = STANDARD.encode(encoder.finish().unwrap());
+
+        let json = json!({
+            "type": "DECLARE",
+            "version": "0x1",
+            "max_fee": "0x0",
+            "signature": [],
+            "nonce": "0x0",
+            "sender_address": "0x1",
+            "contract_class": {
+                "program": compressed_program,
+                "entry_points_by_type": contract_json["entry_points_by_type"],
+                "abi": contract_json["abi"],
+            },
+        });
+        let tx = serde_json::from_value::<BroadcastedTransaction>(json)
+            .unwrap()
+            .into_transaction(StarknetChainId::TestNet.to_felt())
+            .unwrap();
+
+        let Transaction::Declare(tx) = tx else {
+            panic!("expected a declare transaction");
+        };
+        let expected_class = ContractClass::from_path("starknet_programs/fibonacci.json").unwrap();
+        assert_eq!(
+            tx.contract_class.entry_points_by_type(),
+            expected_class.entry_points_by_type()
+        );
+        assert_eq!(tx.contract_class.program(), expected_class.program());
+    }
+}
@@ -38,9 +38,13 @@ pub struct L1Handler {
     hash_value: Felt252,
     #[getset(get = "pub")]
     contract_address: Address,
+    #[getset(get = "pub")]
     entry_point_selector: Felt252,
+    #[getset(get = "pub")]
     calldata: Vec<Felt252>,
+    #[getset(get = "pub")]
     nonce: Option<Felt252>,
+    #[getset(get = "pub")]
     paid_fee_on_l1: Option<Felt252>,
     skip_validate: bool,
     skip_execute: bool,
@@ -142,9 +146,9 @@ impl L1Handler {
                 state,
                 block_context,
                 &mut resources_manager,
-                &mut self.get_execution_context(block_context.invoke_tx_max_n_steps)?,
+                &mut self.get_execution_context(block_context.invoke_tx_max_n_steps())?,
                 true,
-                block_context.invoke_tx_max_n_steps,
+                block_context.invoke_tx_max_n_steps(),
                 #[cfg(feature = "cairo-native")]
                 program_cache,
             )?
@@ -158,6 +162,7 @@ impl L1Handler {
             changes,
             Some(self.get_payload_size()),
             n_reverted_steps,
+            block_context,
         )?;
 
         // Enforce L1 fees.
@@ -224,6 +229,7 @@ impl L1Handler {
     }
 
     /// Creates a `L1Handler` from a starknet api `L1HandlerTransaction`.
+    #[deprecated(note = "use `Transaction::try_from` with a `StarknetApiTransaction` instead")]
     pub fn from_sn_api_tx(
         tx: starknet_api::transaction::L1HandlerTransaction,
         tx_hash: Felt252,
@@ -380,12 +386,14 @@ mod test {
                 internal_calls: vec![],
                 gas_consumed: 0,
                 failure_flag: false,
+                syscall_trace: None,
             }),
             revert_error: None,
             fee_transfer_info: None,
             actual_fee: 0,
             actual_resources: HashMap::from([
                 ("n_steps".to_string(), 1319),
+                ("n_memory_holes".to_string(), 20),
                 ("pedersen_builtin".to_string(), 13),
                 ("range_check_builtin".to_string(), 23),
                 ("l1_gas_usage".to_string(), 18471),
@@ -1,7 +1,9 @@
 use crate::{
     definitions::block_context::BlockContext,
     definitions::constants::{QUERY_VERSION_0, QUERY_VERSION_1, QUERY_VERSION_2},
+    definitions::transaction_type::TransactionType,
     execution::TransactionExecutionInfo,
+    metrics,
     state::{
         cached_state::CachedState, contract_class_cache::ContractClassCache, state_api::StateReader,
     },
@@ -13,7 +15,11 @@ pub use deploy::Deploy;
 pub use deploy_account::DeployAccount;
 use error::TransactionError;
 pub use invoke_function::InvokeFunction;
+pub use json::BroadcastedTransaction;
 pub use l1_handler::L1Handler;
+pub use receipt::TransactionReceipt;
+pub use sn_api::StarknetApiTransaction;
+pub use validation::ValidationInfo;
 
 pub mod declare;
 pub mod declare_v2;
@@ -22,10 +28,15 @@ pub mod deploy_account;
 pub mod error;
 pub mod fee;
 pub mod invoke_function;
+pub mod json;
 pub mod l1_handler;
+pub mod receipt;
+pub mod sn_api;
+pub mod validation;
 
 use cairo_vm::felt::Felt252;
 use num_traits::{One, Zero};
+use std::time::Instant;
 
 #[cfg(feature = "cairo-native")]
 use {
@@ -43,6 +54,7 @@ use {
 /// - DeployAccount
 /// - InvokeFunction
 /// - L1Handler
+#[derive(Debug, Clone)]
 pub enum Transaction {
     /// A declare transaction.
     Declare(Declare),
@@ -71,6 +83,53 @@ impl Transaction {
         }
     }
 
+    /// returns the hash of the transaction.
+    pub fn hash_value(&self) -> &Felt252 {
+        match self {
+            Transaction::Declare(tx) => &tx.hash_value,
+            Transaction::DeclareV2(tx) => &tx.hash_value,
+            Transaction::Deploy(tx) => &tx.hash_value,
+            Transaction::DeployAccount(tx) => tx.hash_value(),
+            Transaction::InvokeFunction(tx) => tx.hash_value(),
+            Transaction::L1Handler(tx) => tx.hash_value(),
+        }
+    }
+
+    /// returns the nonce of the transaction, if it has one.
+    pub fn nonce(&self) -> Option<&Felt252> {
+        match self {
+            Transaction::Declare(tx) => Some(&tx.nonce),
+            Transaction::DeclareV2(tx) => Some(&tx.nonce),
+            Transaction::Deploy(_) => None,
+            Transaction::DeployAccount(tx) => Some(tx.nonce()),
+            Transaction::InvokeFunction(tx) => tx.nonce().as_ref(),
+            Transaction::L1Handler(tx) => tx.nonce().as_ref(),
+        }
+    }
+
+    /// returns the maximum fee the transaction pays on L2, which is zero for the transactions
+    /// that don't pay fees on L2.
+    pub fn max_fee(&self) -> u128 {
+        match self {
+            Transaction::Declare(tx) => tx.max_fee,
+            Transaction::DeclareV2(tx) => tx.max_fee,
+            Transaction::DeployAccount(tx) => *tx.max_fee(),
+            Transaction::InvokeFunction(tx) => *tx.max_fee(),
+            Transaction::Deploy(_) | Transaction::L1Handler(_) => 0,
+        }
+    }
+
+    /// returns the type of the transaction.
+    pub fn tx_type(&self) -> TransactionType {
+        match self {
+            Transaction::Declare(_) | Transaction::DeclareV2(_) => TransactionType::Declare,
+            Transaction::Deploy(_) => TransactionType::Deploy,
+            Transaction::DeployAccount(_) => TransactionType::DeployAccount,
+            Transaction::InvokeFunction(_) => TransactionType::InvokeFunction,
+            Transaction::L1Handler(_) => TransactionType::L1Handler,
+        }
+    }
+
     /// execute the transaction in cairo-vm and returns a TransactionExecutionInfo structure.
     ///## Parameters:
     ///- state: a structure that implements State and StateReader traits.
@@ -85,7 +144,30 @@ impl Transaction {
             Rc<RefCell<ProgramCache<'_, ClassHash>>>,
         >,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
-        match self {
+        self.execute_with_gas_budget(
+            state,
+            block_context,
+            Some(remaining_gas),
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )
+    }
+
+    /// Like [`Transaction::execute`], but when `remaining_gas` is `None` the gas budget is derived
+    /// as the sequencer does: invoke transactions get what their max fee pays for, minus the
+    /// validation usage (see [`fee::calculate_initial_gas`]), and L1 handlers the protocol's
+    /// initial gas.
+    pub fn execute_with_gas_budget<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        block_context: &BlockContext,
+        remaining_gas: Option<u128>,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        let start = Instant::now();
+        let execution_result = match self {
             Transaction::Declare(tx) => tx.execute(
                 state,
                 block_context,
@@ -110,7 +192,7 @@ impl Transaction {
                 #[cfg(feature = "cairo-native")]
                 program_cache,
             ),
-            Transaction::InvokeFunction(tx) => tx.execute(
+            Transaction::InvokeFunction(tx) => tx.execute_with_gas_budget(
                 state,
                 block_context,
                 remaining_gas,
@@ -120,11 +202,21 @@ impl Transaction {
             Transaction::L1Handler(tx) => tx.execute(
                 state,
                 block_context,
-                remaining_gas,
+                remaining_gas.unwrap_or_else(|| block_context.versioned_constants().initial_gas()),
                 #[cfg(feature = "cairo-native")]
                 program_cache,
             ),
+        };
+
+        if let Ok(execution_info) = &execution_result {
+            metrics::record_transaction(
+                self.tx_type(),
+                start.elapsed(),
+                execution_info.actual_resources.get("n_steps").copied(),
+            );
         }
+
+        execution_result
     }
 
     /// It creates a new transaction structure modificating the skip flags. It is meant to be used only to run a simulation
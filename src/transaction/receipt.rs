@@ -0,0 +1,359 @@
+//! # Transaction receipts
+//!
+//! A [`TransactionReceipt`] gathers what a Starknet JSON-RPC `starknet_getTransactionReceipt`
+//! returns for an executed transaction, built from the transaction and its
+//! [`TransactionExecutionInfo`]. It serializes to the RPC receipt shape, so RPC front ends can
+//! return it directly:
+//!
+//! ```ignore
+//! let execution_info = tx.execute(&mut state, &block_context, remaining_gas)?;
+//! let receipt = TransactionReceipt::new(&tx, &execution_info)?;
+//! let json = serde_json::to_value(&receipt)?;
+//! ```
+
+use super::{
+    error::TransactionError,
+    json::{hex, hex_array},
+    Transaction,
+};
+use crate::{
+    definitions::transaction_type::TransactionType,
+    execution::{Event, L2toL1MessageInfo, TransactionExecutionInfo},
+    services::api::messages::StarknetMessageToL2,
+    utils::Address,
+};
+use cairo_vm::{
+    felt::Felt252,
+    vm::runners::builtin_runner::{
+        BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+        POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME,
+    },
+};
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+
+/// The unit in which a fee is paid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FeeUnit {
+    Wei,
+}
+
+/// Whether the transaction was executed successfully or reverted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExecutionStatus {
+    Succeeded,
+    Reverted,
+}
+
+/// How final the transaction is. Executing a transaction only gets it accepted on L2, so receipts
+/// start there and it's up to the caller to update them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FinalityStatus {
+    AcceptedOnL2,
+    AcceptedOnL1,
+}
+
+/// The Cairo resources used by the transaction, named as in the RPC.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ExecutionResources {
+    /// VM steps, not including the memory holes.
+    pub steps: usize,
+    pub memory_holes: usize,
+    pub range_check_builtin_applications: usize,
+    pub pedersen_builtin_applications: usize,
+    pub poseidon_builtin_applications: usize,
+    pub ec_op_builtin_applications: usize,
+    pub ecdsa_builtin_applications: usize,
+    pub bitwise_builtin_applications: usize,
+    pub keccak_builtin_applications: usize,
+}
+
+impl ExecutionResources {
+    /// Extracts the resources used by the transaction from its execution info.
+    pub fn from_execution_info(execution_info: &TransactionExecutionInfo) -> Self {
+        let resource = |name: &str| {
+            execution_info
+                .actual_resources
+                .get(name)
+                .copied()
+                .unwrap_or_default()
+        };
+        // The memory holes are charged as steps, so they're counted in `n_steps`.
+        let memory_holes = resource("n_memory_holes");
+
+        Self {
+            steps: resource("n_steps").saturating_sub(memory_holes),
+            memory_holes,
+            range_check_builtin_applications: resource(RANGE_CHECK_BUILTIN_NAME),
+            pedersen_builtin_applications: resource(HASH_BUILTIN_NAME),
+            poseidon_builtin_applications: resource(POSEIDON_BUILTIN_NAME),
+            ec_op_builtin_applications: resource(EC_OP_BUILTIN_NAME),
+            ecdsa_builtin_applications: resource(SIGNATURE_BUILTIN_NAME),
+            bitwise_builtin_applications: resource(BITWISE_BUILTIN_NAME),
+            keccak_builtin_applications: resource(KECCAK_BUILTIN_NAME),
+        }
+    }
+}
+
+/// The receipt of an executed transaction. See the [module docs](self).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionReceipt {
+    pub transaction_hash: Felt252,
+    pub tx_type: TransactionType,
+    pub actual_fee: u128,
+    pub fee_unit: FeeUnit,
+    pub execution_status: ExecutionStatus,
+    pub revert_reason: Option<String>,
+    pub finality_status: FinalityStatus,
+    /// Events emitted by the transaction, in order.
+    pub events: Vec<Event>,
+    /// Messages sent to L1 by the transaction, in order.
+    pub messages_sent: Vec<L2toL1MessageInfo>,
+    pub execution_resources: ExecutionResources,
+    /// The address of the deployed contract, for deploy and deploy account transactions.
+    pub contract_address: Option<Address>,
+    /// The hash of the message from L1 handled by an L1 handler transaction.
+    pub message_hash: Option<Vec<u8>>,
+}
+
+impl TransactionReceipt {
+    /// Builds the receipt of the transaction, given the info of its execution.
+    pub fn new(
+        transaction: &Transaction,
+        execution_info: &TransactionExecutionInfo,
+    ) -> Result<Self, TransactionError> {
+        let execution_status = match execution_info.revert_error {
+            Some(_) => ExecutionStatus::Reverted,
+            None => ExecutionStatus::Succeeded,
+        };
+        let contract_address = match transaction {
+            Transaction::Deploy(_) | Transaction::DeployAccount(_) => {
+                Some(transaction.contract_address())
+            }
+            _ => None,
+        };
+        let message_hash = match transaction {
+            Transaction::L1Handler(tx) => tx.calldata().split_first().map(|(from, payload)| {
+                StarknetMessageToL2::new(
+                    Address(from.clone()),
+                    tx.contract_address().clone(),
+                    tx.entry_point_selector().clone(),
+                    payload.to_vec(),
+                    tx.nonce().clone().unwrap_or_default(),
+                )
+                .get_hash()
+            }),
+            _ => None,
+        };
+
+        Ok(Self {
+            transaction_hash: transaction.hash_value().clone(),
+            tx_type: transaction.tx_type(),
+            actual_fee: execution_info.actual_fee,
+            fee_unit: FeeUnit::Wei,
+            execution_status,
+            revert_reason: execution_info.revert_error.clone(),
+            finality_status: FinalityStatus::AcceptedOnL2,
+            events: execution_info.get_sorted_events()?,
+            messages_sent: execution_info.get_sorted_l2_to_l1_messages()?,
+            execution_resources: ExecutionResources::from_execution_info(execution_info),
+            contract_address,
+            message_hash,
+        })
+    }
+}
+
+impl Serialize for TransactionReceipt {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let tx_type = match self.tx_type {
+            TransactionType::Declare => "DECLARE",
+            TransactionType::Deploy => "DEPLOY",
+            TransactionType::DeployAccount => "DEPLOY_ACCOUNT",
+            TransactionType::InvokeFunction | TransactionType::InitializeBlockInfo => "INVOKE",
+            TransactionType::L1Handler => "L1_HANDLER",
+        };
+        let events: Vec<Value> = self
+            .events
+            .iter()
+            .map(|event| {
+                json!({
+                    "from_address": hex(&event.from_address.0),
+                    "keys": hex_array(&event.keys),
+                    "data": hex_array(&event.data),
+                })
+            })
+            .collect();
+        let messages_sent: Vec<Value> = self
+            .messages_sent
+            .iter()
+            .map(|message| {
+                json!({
+                    "from_address": hex(&message.from_address.0),
+                    "to_address": hex(&message.to_address.0),
+                    "payload": hex_array(&message.payload),
+                })
+            })
+            .collect();
+
+        let mut receipt = json!({
+            "type": tx_type,
+            "transaction_hash": hex(&self.transaction_hash),
+            "actual_fee": {
+                "amount": hex(&Felt252::from(self.actual_fee)),
+                "unit": self.fee_unit,
+            },
+            "execution_status": self.execution_status,
+            "finality_status": self.finality_status,
+            "events": events,
+            "messages_sent": messages_sent,
+            "execution_resources": self.execution_resources,
+        });
+        if let Some(revert_reason) = &self.revert_reason {
+            receipt["revert_reason"] = json!(revert_reason);
+        }
+        if let Some(contract_address) = &self.contract_address {
+            receipt["contract_address"] = json!(hex(&contract_address.0));
+        }
+        if let Some(message_hash) = &self.message_hash {
+            receipt["message_hash"] = json!(format!("0x{}", ::hex::encode(message_hash)));
+        }
+
+        receipt.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        execution::{CallInfo, OrderedEvent, OrderedL2ToL1Message},
+        transaction::{InvokeFunction, L1Handler},
+    };
+    use coverage_helper::test;
+    use pretty_assertions_sorted::assert_eq;
+    use std::collections::HashMap;
+
+    fn execution_info(tx_type: TransactionType) -> TransactionExecutionInfo {
+        let call_info = CallInfo {
+            caller_address: Address(0x42.into()),
+            contract_address: Address(0x42.into()),
+            events: vec![
+                OrderedEvent::new(1, vec![2.into()], vec![]),
+                OrderedEvent::new(0, vec![1.into()], vec![3.into()]),
+            ],
+            l2_to_l1_messages: vec![OrderedL2ToL1Message::new(
+                0,
+                Address(0x99.into()),
+                vec![5.into()],
+            )],
+            ..Default::default()
+        };
+        TransactionExecutionInfo {
+            call_info: Some(call_info),
+            actual_fee: 1000,
+            actual_resources: HashMap::from([
+                ("n_steps".to_string(), 1500),
+                ("n_memory_holes".to_string(), 30),
+                ("pedersen_builtin".to_string(), 10),
+                ("range_check_builtin".to_string(), 20),
+                ("l1_gas_usage".to_string(), 5000),
+            ]),
+            tx_type: Some(tx_type),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn invoke_receipt() {
+        let tx = Transaction::InvokeFunction(
+            InvokeFunction::new_with_tx_hash(
+                Address(0x42.into()),
+                0x43.into(),
+                1000,
+                1.into(),
+                vec![],
+                vec![],
+                Some(0.into()),
+                0x1234.into(),
+            )
+            .unwrap(),
+        );
+        let receipt =
+            TransactionReceipt::new(&tx, &execution_info(TransactionType::InvokeFunction)).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&receipt).unwrap(),
+            json!({
+                "type": "INVOKE",
+                "transaction_hash": "0x1234",
+                "actual_fee": { "amount": "0x3e8", "unit": "WEI" },
+                "execution_status": "SUCCEEDED",
+                "finality_status": "ACCEPTED_ON_L2",
+                "events": [
+                    { "from_address": "0x42", "keys": ["0x1"], "data": ["0x3"] },
+                    { "from_address": "0x42", "keys": ["0x2"], "data": [] },
+                ],
+                "messages_sent": [
+                    { "from_address": "0x42", "to_address": "0x99", "payload": ["0x5"] },
+                ],
+                "execution_resources": {
+                    "steps": 1470,
+                    "memory_holes": 30,
+                    "range_check_builtin_applications": 20,
+                    "pedersen_builtin_applications": 10,
+                    "poseidon_builtin_applications": 0,
+                    "ec_op_builtin_applications": 0,
+                    "ecdsa_builtin_applications": 0,
+                    "bitwise_builtin_applications": 0,
+                    "keccak_builtin_applications": 0,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn reverted_l1_handler_receipt() {
+        let tx = Transaction::L1Handler(
+            L1Handler::new_with_tx_hash(
+                Address(0x42.into()),
+                0x43.into(),
+                vec![0x8359.into(), 1.into(), 2.into()],
+                7.into(),
+                None,
+                0x1234.into(),
+            )
+            .unwrap(),
+        );
+        let execution_info = execution_info(TransactionType::L1Handler).to_revert_error("failed");
+        let receipt = TransactionReceipt::new(&tx, &execution_info).unwrap();
+
+        assert_eq!(receipt.execution_status, ExecutionStatus::Reverted);
+        assert_eq!(receipt.revert_reason.as_deref(), Some("failed"));
+        assert!(receipt.events.is_empty());
+        assert_eq!(receipt.contract_address, None);
+
+        let message = StarknetMessageToL2::new(
+            Address(0x8359.into()),
+            Address(0x42.into()),
+            0x43.into(),
+            vec![1.into(), 2.into()],
+            7.into(),
+        );
+        assert_eq!(receipt.message_hash, Some(message.get_hash()));
+
+        let json = serde_json::to_value(&receipt).unwrap();
+        assert_eq!(json["type"], "L1_HANDLER");
+        assert_eq!(json["execution_status"], "REVERTED");
+        assert_eq!(json["revert_reason"], "failed");
+        assert_eq!(
+            json["message_hash"],
+            format!("0x{}", ::hex::encode(message.get_hash()))
+        );
+    }
+}
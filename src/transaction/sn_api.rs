@@ -0,0 +1,617 @@
+//! # starknet_api transactions
+//!
+//! Conversions between [`Transaction`] and the transactions of the `starknet_api` crate.
+//!
+//! `starknet_api` transactions don't carry their hash, nor the classes declared by declare (and
+//! deploy) transactions, nor the fee paid on L1 for L1 handlers. A [`StarknetApiTransaction`]
+//! bundles them together so that the conversion is lossless both ways:
+//!
+//! ```ignore
+//! let tx = rpc_state.get_transaction(&tx_hash)?;
+//! let tx: Transaction = StarknetApiTransaction::new(tx, tx_hash)
+//!     .with_contract_class(contract_class)
+//!     .try_into()?;
+//!
+//! let StarknetApiTransaction { tx, tx_hash, .. } = tx.try_into()?;
+//! ```
+
+use super::{
+    error::TransactionError, Declare, DeclareV2, Deploy, DeployAccount, InvokeFunction, L1Handler,
+    Transaction,
+};
+use crate::{
+    definitions::{constants::EXECUTE_ENTRY_POINT_SELECTOR, transaction_type::TransactionType},
+    hash_utils::calculate_contract_address,
+    services::api::contract_classes::compiled_class::CompiledClass,
+    utils::{Address, ClassHash},
+};
+use cairo_lang_starknet::contract_class::ContractClass as SierraContractClass;
+use cairo_vm::felt::Felt252;
+use num_traits::{ToPrimitive, Zero};
+use starknet_api::{
+    core::{
+        ClassHash as SNClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce,
+        PatriciaKey,
+    },
+    hash::StarkFelt,
+    transaction::{
+        Calldata, ContractAddressSalt, DeclareTransaction, DeclareTransactionV0V1,
+        DeclareTransactionV2, DeployAccountTransaction, DeployTransaction, Fee, InvokeTransaction,
+        InvokeTransactionV0, InvokeTransactionV1, L1HandlerTransaction,
+        Transaction as SNTransaction, TransactionHash, TransactionSignature, TransactionVersion,
+    },
+};
+use std::sync::Arc;
+
+/// A `starknet_api` transaction along with the data it doesn't carry. See the
+/// [module docs](self).
+#[derive(Debug, Clone)]
+pub struct StarknetApiTransaction {
+    pub tx: SNTransaction,
+    pub tx_hash: TransactionHash,
+    /// The class declared or deployed by the transaction. Cairo 0 declares and deploys need it,
+    /// while a Cairo 1 declare may carry its compiled class.
+    pub contract_class: Option<CompiledClass>,
+    /// The Sierra class a Cairo 1 declare may carry.
+    pub sierra_contract_class: Option<SierraContractClass>,
+    /// The fee paid on L1 for an L1 handler.
+    pub paid_fee_on_l1: Option<Fee>,
+}
+
+impl StarknetApiTransaction {
+    pub fn new(tx: SNTransaction, tx_hash: TransactionHash) -> Self {
+        Self {
+            tx,
+            tx_hash,
+            contract_class: None,
+            sierra_contract_class: None,
+            paid_fee_on_l1: None,
+        }
+    }
+
+    pub fn with_contract_class(self, contract_class: CompiledClass) -> Self {
+        Self {
+            contract_class: Some(contract_class),
+            ..self
+        }
+    }
+
+    pub fn with_sierra_contract_class(self, sierra_contract_class: SierraContractClass) -> Self {
+        Self {
+            sierra_contract_class: Some(sierra_contract_class),
+            ..self
+        }
+    }
+
+    pub fn with_paid_fee_on_l1(self, paid_fee_on_l1: Fee) -> Self {
+        Self {
+            paid_fee_on_l1: Some(paid_fee_on_l1),
+            ..self
+        }
+    }
+}
+
+// ----------------------------------
+//      Try from starknet api
+// ----------------------------------
+
+impl TryFrom<StarknetApiTransaction> for Transaction {
+    type Error = TransactionError;
+
+    fn try_from(value: StarknetApiTransaction) -> Result<Self, TransactionError> {
+        let StarknetApiTransaction {
+            tx,
+            tx_hash,
+            contract_class,
+            sierra_contract_class,
+            paid_fee_on_l1,
+        } = value;
+        let hash_value = from_stark_felt(&tx_hash.0);
+
+        match tx {
+            SNTransaction::Declare(DeclareTransaction::V0(tx)) => {
+                convert_declare(tx, 0, hash_value, contract_class)
+            }
+            SNTransaction::Declare(DeclareTransaction::V1(tx)) => {
+                convert_declare(tx, 1, hash_value, contract_class)
+            }
+            SNTransaction::Declare(DeclareTransaction::V2(tx)) => {
+                let casm_class = match contract_class {
+                    Some(CompiledClass::Casm(casm_class)) => Some(casm_class.as_ref().clone()),
+                    None => None,
+                    Some(_) => {
+                        return Err(TransactionError::ContractClassMismatch(
+                            TransactionType::Declare,
+                        ))
+                    }
+                };
+                let tx = DeclareV2::new_with_sierra_class_hash_and_tx_hash(
+                    sierra_contract_class,
+                    from_stark_felt(&tx.class_hash.0),
+                    casm_class,
+                    from_stark_felt(&tx.compiled_class_hash.0),
+                    from_contract_address(&tx.sender_address),
+                    tx.max_fee.0,
+                    2.into(),
+                    from_stark_felts(&tx.signature.0),
+                    from_stark_felt(&tx.nonce.0),
+                    hash_value,
+                )?;
+                Ok(Transaction::DeclareV2(Box::new(tx)))
+            }
+            SNTransaction::Deploy(tx) => {
+                let contract_class = contract_class.ok_or(
+                    TransactionError::MissingContractClass(TransactionType::Deploy),
+                )?;
+                let contract_address_salt = from_stark_felt(&tx.contract_address_salt.0);
+                let constructor_calldata = from_stark_felts(&tx.constructor_calldata.0);
+                let contract_address = Address(calculate_contract_address(
+                    &contract_address_salt,
+                    &from_stark_felt(&tx.class_hash.0),
+                    &constructor_calldata,
+                    Address(Felt252::zero()),
+                )?);
+
+                Ok(Transaction::Deploy(Deploy {
+                    hash_value,
+                    version: from_stark_felt(&tx.version.0),
+                    contract_address,
+                    contract_address_salt,
+                    contract_hash: from_class_hash(&tx.class_hash),
+                    contract_class,
+                    constructor_calldata,
+                    skip_validate: false,
+                    skip_execute: false,
+                    skip_fee_transfer: false,
+                }))
+            }
+            SNTransaction::DeployAccount(tx) => {
+                let tx = DeployAccount::new_with_tx_hash(
+                    from_class_hash(&tx.class_hash),
+                    tx.max_fee.0,
+                    from_stark_felt(&tx.version.0),
+                    from_stark_felt(&tx.nonce.0),
+                    from_stark_felts(&tx.constructor_calldata.0),
+                    from_stark_felts(&tx.signature.0),
+                    from_stark_felt(&tx.contract_address_salt.0),
+                    hash_value,
+                )?;
+                Ok(Transaction::DeployAccount(tx))
+            }
+            SNTransaction::Invoke(InvokeTransaction::V0(tx)) => {
+                let tx = InvokeFunction::new_with_tx_hash(
+                    from_contract_address(&tx.contract_address),
+                    from_stark_felt(&tx.entry_point_selector.0),
+                    tx.max_fee.0,
+                    0.into(),
+                    from_stark_felts(&tx.calldata.0),
+                    from_stark_felts(&tx.signature.0),
+                    None,
+                    hash_value,
+                )?;
+                Ok(Transaction::InvokeFunction(tx))
+            }
+            SNTransaction::Invoke(InvokeTransaction::V1(tx)) => {
+                let tx = InvokeFunction::new_with_tx_hash(
+                    from_contract_address(&tx.sender_address),
+                    EXECUTE_ENTRY_POINT_SELECTOR.clone(),
+                    tx.max_fee.0,
+                    1.into(),
+                    from_stark_felts(&tx.calldata.0),
+                    from_stark_felts(&tx.signature.0),
+                    Some(from_stark_felt(&tx.nonce.0)),
+                    hash_value,
+                )?;
+                Ok(Transaction::InvokeFunction(tx))
+            }
+            SNTransaction::L1Handler(tx) => {
+                let tx = L1Handler::new_with_tx_hash(
+                    from_contract_address(&tx.contract_address),
+                    from_stark_felt(&tx.entry_point_selector.0),
+                    from_stark_felts(&tx.calldata.0),
+                    from_stark_felt(&tx.nonce.0),
+                    paid_fee_on_l1.map(|fee| Felt252::from(fee.0)),
+                    hash_value,
+                )?;
+                Ok(Transaction::L1Handler(tx))
+            }
+        }
+    }
+}
+
+fn convert_declare(
+    tx: DeclareTransactionV0V1,
+    version: u64,
+    hash_value: Felt252,
+    contract_class: Option<CompiledClass>,
+) -> Result<Transaction, TransactionError> {
+    let contract_class = match contract_class {
+        Some(CompiledClass::Deprecated(contract_class)) => contract_class.as_ref().clone(),
+        Some(_) => {
+            return Err(TransactionError::ContractClassMismatch(
+                TransactionType::Declare,
+            ))
+        }
+        None => {
+            return Err(TransactionError::MissingContractClass(
+                TransactionType::Declare,
+            ))
+        }
+    };
+
+    let tx = Declare::new_with_tx_and_class_hash(
+        contract_class,
+        from_contract_address(&tx.sender_address),
+        tx.max_fee.0,
+        version.into(),
+        from_stark_felts(&tx.signature.0),
+        from_stark_felt(&tx.nonce.0),
+        hash_value,
+        from_class_hash(&tx.class_hash),
+    )?;
+    Ok(Transaction::Declare(tx))
+}
+
+// ----------------------------------
+//      Try into starknet api
+// ----------------------------------
+
+impl TryFrom<Transaction> for StarknetApiTransaction {
+    type Error = TransactionError;
+
+    fn try_from(value: Transaction) -> Result<Self, TransactionError> {
+        let tx_hash = TransactionHash(to_stark_felt(value.hash_value())?);
+
+        let (tx, contract_class, sierra_contract_class, paid_fee_on_l1) = match value {
+            Transaction::Declare(tx) => {
+                let declare = DeclareTransactionV0V1 {
+                    max_fee: Fee(tx.max_fee),
+                    signature: to_signature(&tx.signature)?,
+                    nonce: Nonce(to_stark_felt(&tx.nonce)?),
+                    class_hash: to_class_hash(&tx.class_hash)?,
+                    sender_address: to_contract_address(&tx.sender_address)?,
+                };
+                let declare = if tx.version.is_zero() {
+                    DeclareTransaction::V0(declare)
+                } else {
+                    DeclareTransaction::V1(declare)
+                };
+                let contract_class = CompiledClass::Deprecated(Arc::new(tx.contract_class));
+                (
+                    SNTransaction::Declare(declare),
+                    Some(contract_class),
+                    None,
+                    None,
+                )
+            }
+            Transaction::DeclareV2(tx) => {
+                let declare = DeclareTransactionV2 {
+                    max_fee: Fee(tx.max_fee),
+                    signature: to_signature(&tx.signature)?,
+                    nonce: Nonce(to_stark_felt(&tx.nonce)?),
+                    class_hash: SNClassHash(to_stark_felt(&tx.sierra_class_hash)?),
+                    compiled_class_hash: CompiledClassHash(to_stark_felt(&tx.compiled_class_hash)?),
+                    sender_address: to_contract_address(&tx.sender_address)?,
+                };
+                let contract_class = tx
+                    .casm_class
+                    .map(|casm_class| CompiledClass::Casm(Arc::new(casm_class)));
+                (
+                    SNTransaction::Declare(DeclareTransaction::V2(declare)),
+                    contract_class,
+                    tx.sierra_contract_class,
+                    None,
+                )
+            }
+            Transaction::Deploy(tx) => {
+                let deploy = DeployTransaction {
+                    version: TransactionVersion(to_stark_felt(&tx.version)?),
+                    class_hash: to_class_hash(&tx.contract_hash)?,
+                    contract_address_salt: ContractAddressSalt(to_stark_felt(
+                        &tx.contract_address_salt,
+                    )?),
+                    constructor_calldata: to_calldata(&tx.constructor_calldata)?,
+                };
+                (
+                    SNTransaction::Deploy(deploy),
+                    Some(tx.contract_class),
+                    None,
+                    None,
+                )
+            }
+            Transaction::DeployAccount(tx) => {
+                let deploy_account = DeployAccountTransaction {
+                    max_fee: Fee(*tx.max_fee()),
+                    version: TransactionVersion(to_stark_felt(tx.version())?),
+                    signature: to_signature(tx.signature())?,
+                    nonce: Nonce(to_stark_felt(tx.nonce())?),
+                    class_hash: to_class_hash(tx.class_hash())?,
+                    contract_address_salt: ContractAddressSalt(to_stark_felt(
+                        tx.contract_address_salt(),
+                    )?),
+                    constructor_calldata: to_calldata(tx.constructor_calldata())?,
+                };
+                (
+                    SNTransaction::DeployAccount(deploy_account),
+                    None,
+                    None,
+                    None,
+                )
+            }
+            Transaction::InvokeFunction(tx) => {
+                let invoke = match tx.nonce() {
+                    None => InvokeTransaction::V0(InvokeTransactionV0 {
+                        max_fee: Fee(*tx.max_fee()),
+                        signature: to_signature(tx.signature())?,
+                        contract_address: to_contract_address(tx.contract_address())?,
+                        entry_point_selector: EntryPointSelector(to_stark_felt(
+                            tx.entry_point_selector(),
+                        )?),
+                        calldata: to_calldata(tx.calldata())?,
+                    }),
+                    Some(nonce) => InvokeTransaction::V1(InvokeTransactionV1 {
+                        max_fee: Fee(*tx.max_fee()),
+                        signature: to_signature(tx.signature())?,
+                        nonce: Nonce(to_stark_felt(nonce)?),
+                        sender_address: to_contract_address(tx.contract_address())?,
+                        calldata: to_calldata(tx.calldata())?,
+                    }),
+                };
+                (SNTransaction::Invoke(invoke), None, None, None)
+            }
+            Transaction::L1Handler(tx) => {
+                let l1_handler = L1HandlerTransaction {
+                    version: TransactionVersion(StarkFelt::from(0_u32)),
+                    nonce: Nonce(to_stark_felt(&tx.nonce().clone().unwrap_or_default())?),
+                    contract_address: to_contract_address(tx.contract_address())?,
+                    entry_point_selector: EntryPointSelector(to_stark_felt(
+                        tx.entry_point_selector(),
+                    )?),
+                    calldata: to_calldata(tx.calldata())?,
+                };
+                let paid_fee_on_l1 = tx
+                    .paid_fee_on_l1()
+                    .as_ref()
+                    .map(|fee| {
+                        fee.to_u128().map(Fee).ok_or_else(|| {
+                            TransactionError::Conversion("Felt252".to_string(), "Fee".to_string())
+                        })
+                    })
+                    .transpose()?;
+                (
+                    SNTransaction::L1Handler(l1_handler),
+                    None,
+                    None,
+                    paid_fee_on_l1,
+                )
+            }
+        };
+
+        Ok(StarknetApiTransaction {
+            tx,
+            tx_hash,
+            contract_class,
+            sierra_contract_class,
+            paid_fee_on_l1,
+        })
+    }
+}
+
+// ----------------------------------
+//             Helpers
+// ----------------------------------
+
+fn from_stark_felt(felt: &StarkFelt) -> Felt252 {
+    Felt252::from_bytes_be(felt.bytes())
+}
+
+fn from_stark_felts(felts: &[StarkFelt]) -> Vec<Felt252> {
+    felts.iter().map(from_stark_felt).collect()
+}
+
+fn from_contract_address(address: &ContractAddress) -> Address {
+    Address(from_stark_felt(address.0.key()))
+}
+
+fn from_class_hash(class_hash: &SNClassHash) -> ClassHash {
+    ClassHash::from(from_stark_felt(&class_hash.0))
+}
+
+fn to_stark_felt(felt: &Felt252) -> Result<StarkFelt, TransactionError> {
+    StarkFelt::new(felt.to_be_bytes())
+        .map_err(|_| TransactionError::Conversion("Felt252".to_string(), "StarkFelt".to_string()))
+}
+
+fn to_contract_address(address: &Address) -> Result<ContractAddress, TransactionError> {
+    let key = PatriciaKey::try_from(to_stark_felt(&address.0)?).map_err(|_| {
+        TransactionError::Conversion("Address".to_string(), "ContractAddress".to_string())
+    })?;
+    Ok(ContractAddress(key))
+}
+
+fn to_class_hash(class_hash: &ClassHash) -> Result<SNClassHash, TransactionError> {
+    Ok(SNClassHash(to_stark_felt(&Felt252::from_bytes_be(
+        class_hash.to_bytes_be(),
+    ))?))
+}
+
+fn to_signature(signature: &[Felt252]) -> Result<TransactionSignature, TransactionError> {
+    Ok(TransactionSignature(
+        signature
+            .iter()
+            .map(to_stark_felt)
+            .collect::<Result<_, _>>()?,
+    ))
+}
+
+fn to_calldata(calldata: &[Felt252]) -> Result<Calldata, TransactionError> {
+    Ok(Calldata(Arc::new(
+        calldata
+            .iter()
+            .map(to_stark_felt)
+            .collect::<Result<_, _>>()?,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::api::contract_classes::deprecated_contract_class::ContractClass;
+    use assert_matches::assert_matches;
+    use coverage_helper::test;
+    use pretty_assertions_sorted::assert_eq;
+
+    fn stark_felt(hex: &str) -> StarkFelt {
+        StarkFelt::try_from(hex).unwrap()
+    }
+
+    fn stark_felts(felts: &[u32]) -> Vec<StarkFelt> {
+        felts.iter().map(|felt| StarkFelt::from(*felt)).collect()
+    }
+
+    fn contract_address(hex: &str) -> ContractAddress {
+        ContractAddress(PatriciaKey::try_from(stark_felt(hex)).unwrap())
+    }
+
+    fn round_trip(sn_api_tx: StarknetApiTransaction) -> Transaction {
+        let tx = Transaction::try_from(sn_api_tx.clone()).unwrap();
+        let converted_back = StarknetApiTransaction::try_from(tx.clone()).unwrap();
+        assert_eq!(converted_back.tx, sn_api_tx.tx);
+        assert_eq!(converted_back.tx_hash, sn_api_tx.tx_hash);
+        assert_eq!(converted_back.paid_fee_on_l1, sn_api_tx.paid_fee_on_l1);
+        tx
+    }
+
+    #[test]
+    fn invoke_round_trip() {
+        let invoke_v0 = SNTransaction::Invoke(InvokeTransaction::V0(InvokeTransactionV0 {
+            max_fee: Fee(0),
+            signature: TransactionSignature(vec![]),
+            contract_address: contract_address("0x42"),
+            entry_point_selector: EntryPointSelector(stark_felt("0x43")),
+            calldata: Calldata(Arc::new(stark_felts(&[1, 2]))),
+        }));
+        let invoke_v1 = SNTransaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 {
+            max_fee: Fee(49000000000000),
+            signature: TransactionSignature(stark_felts(&[3, 4])),
+            nonce: Nonce(StarkFelt::from(22u32)),
+            sender_address: contract_address("0x42"),
+            calldata: Calldata(Arc::new(stark_felts(&[1, 2, 3]))),
+        }));
+
+        let tx = round_trip(StarknetApiTransaction::new(
+            invoke_v0,
+            TransactionHash(stark_felt("0x1234")),
+        ));
+        assert_matches!(tx, Transaction::InvokeFunction(tx) if tx.nonce().is_none());
+
+        let tx = round_trip(StarknetApiTransaction::new(
+            invoke_v1,
+            TransactionHash(stark_felt("0x1234")),
+        ));
+        assert_eq!(tx.hash_value(), &Felt252::from(0x1234));
+        assert_eq!(tx.nonce(), Some(&Felt252::from(22)));
+        assert_eq!(tx.max_fee(), 49000000000000);
+    }
+
+    #[test]
+    fn l1_handler_and_deploy_account_round_trip() {
+        let l1_handler = SNTransaction::L1Handler(L1HandlerTransaction {
+            version: TransactionVersion(StarkFelt::from(0u32)),
+            nonce: Nonce(StarkFelt::from(5u32)),
+            contract_address: contract_address("0x42"),
+            entry_point_selector: EntryPointSelector(stark_felt("0x43")),
+            calldata: Calldata(Arc::new(stark_felts(&[1, 2]))),
+        });
+        let tx = round_trip(
+            StarknetApiTransaction::new(l1_handler, TransactionHash(stark_felt("0x1")))
+                .with_paid_fee_on_l1(Fee(1000)),
+        );
+        assert_matches!(tx, Transaction::L1Handler(tx) if tx.paid_fee_on_l1() == &Some(1000.into()));
+
+        let deploy_account = SNTransaction::DeployAccount(DeployAccountTransaction {
+            max_fee: Fee(1000),
+            version: TransactionVersion(StarkFelt::from(1u32)),
+            signature: TransactionSignature(stark_felts(&[3, 4])),
+            nonce: Nonce(StarkFelt::from(0u32)),
+            class_hash: SNClassHash(stark_felt("0x99")),
+            contract_address_salt: ContractAddressSalt(stark_felt("0x7")),
+            constructor_calldata: Calldata(Arc::new(stark_felts(&[1]))),
+        });
+        round_trip(StarknetApiTransaction::new(
+            deploy_account,
+            TransactionHash(stark_felt("0x2")),
+        ));
+    }
+
+    #[test]
+    fn declare_and_deploy_need_their_class() {
+        let contract_class = ContractClass::from_path("starknet_programs/fibonacci.json").unwrap();
+        let declare = SNTransaction::Declare(DeclareTransaction::V1(DeclareTransactionV0V1 {
+            max_fee: Fee(1000),
+            signature: TransactionSignature(vec![]),
+            nonce: Nonce(StarkFelt::from(1u32)),
+            class_hash: SNClassHash(stark_felt("0x99")),
+            sender_address: contract_address("0x42"),
+        }));
+        let deploy = SNTransaction::Deploy(DeployTransaction {
+            version: TransactionVersion(StarkFelt::from(0u32)),
+            class_hash: SNClassHash(stark_felt("0x99")),
+            contract_address_salt: ContractAddressSalt(stark_felt("0x7")),
+            constructor_calldata: Calldata(Arc::new(vec![])),
+        });
+
+        for sn_api_tx in [declare, deploy] {
+            let sn_api_tx =
+                StarknetApiTransaction::new(sn_api_tx, TransactionHash(stark_felt("0x3")));
+            assert_matches!(
+                Transaction::try_from(sn_api_tx.clone()),
+                Err(TransactionError::MissingContractClass(_))
+            );
+
+            let sn_api_tx = sn_api_tx
+                .with_contract_class(CompiledClass::Deprecated(Arc::new(contract_class.clone())));
+            round_trip(sn_api_tx);
+        }
+    }
+
+    #[test]
+    fn declare_v2_carries_its_sierra_class() {
+        let sierra_contract_class: SierraContractClass = serde_json::from_slice(include_bytes!(
+            "../../starknet_programs/raw_contract_classes/fibonacci.sierra"
+        ))
+        .unwrap();
+        let declare = SNTransaction::Declare(DeclareTransaction::V2(DeclareTransactionV2 {
+            max_fee: Fee(1000),
+            signature: TransactionSignature(vec![]),
+            nonce: Nonce(StarkFelt::from(1u32)),
+            class_hash: SNClassHash(stark_felt("0x99")),
+            compiled_class_hash: CompiledClassHash(stark_felt("0x98")),
+            sender_address: contract_address("0x42"),
+        }));
+        let sn_api_tx = StarknetApiTransaction::new(declare, TransactionHash(stark_felt("0x4")))
+            .with_sierra_contract_class(sierra_contract_class.clone());
+
+        let tx = round_trip(sn_api_tx.clone());
+        assert_matches!(
+            &tx,
+            Transaction::DeclareV2(tx) if tx.sierra_contract_class.as_ref() == Some(&sierra_contract_class)
+        );
+        assert_eq!(
+            StarknetApiTransaction::try_from(tx)
+                .unwrap()
+                .sierra_contract_class,
+            Some(sierra_contract_class)
+        );
+
+        let contract_class = ContractClass::from_path("starknet_programs/fibonacci.json").unwrap();
+        assert_matches!(
+            Transaction::try_from(
+                sn_api_tx.with_contract_class(CompiledClass::Deprecated(Arc::new(contract_class)))
+            ),
+            Err(TransactionError::ContractClassMismatch(
+                TransactionType::Declare
+            ))
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use super::{error::TransactionError, Transaction};
+use crate::{
+    core::errors::validation_errors::ValidationError,
+    definitions::block_context::BlockContext,
+    execution::CallInfo,
+    state::{
+        cached_state::CachedState,
+        contract_class_cache::ContractClassCache,
+        state_api::{State, StateReader},
+    },
+};
+use cairo_vm::vm::{
+    errors::{
+        cairo_run_errors::CairoRunError, vm_errors::VirtualMachineError, vm_exception::VmException,
+    },
+    runners::cairo_runner::ExecutionResources,
+};
+use std::collections::HashMap;
+
+#[cfg(feature = "cairo-native")]
+use {
+    crate::utils::ClassHash,
+    cairo_native::cache::ProgramCache,
+    std::{cell::RefCell, rc::Rc},
+};
+
+/// The outcome of a transaction which passed validation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationInfo {
+    /// The call to the account's validate entry point, if the transaction has one.
+    pub validate_info: Option<CallInfo>,
+    /// The resources used by the validation, as they would be charged for.
+    pub actual_resources: HashMap<String, usize>,
+}
+
+impl ValidationInfo {
+    /// The Cairo resources used by the account's validate entry point.
+    pub fn validate_resources(&self) -> Option<&ExecutionResources> {
+        self.validate_info
+            .as_ref()
+            .and_then(|info| info.execution_resources.as_ref())
+    }
+}
+
+impl Transaction {
+    /// Runs the admission checks of the transaction without executing it: the fee balance, the
+    /// nonce, the account's validate entry point (which checks the signature) and the validation
+    /// step limit. The state isn't modified.
+    ///
+    /// Deploy account transactions also run the account's constructor, as it must run before
+    /// the validation. Deploy and L1 handler transactions aren't sent by accounts, so they can't
+    /// be validated.
+    pub fn validate<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &CachedState<S, C>,
+        block_context: &BlockContext,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<ValidationInfo, ValidationError> {
+        self.run_validation(
+            state,
+            block_context,
+            false,
+            #[cfg(feature = "cairo-native")]
+            program_cache,
+        )
+    }
+
+    /// Runs the checks of [`Transaction::validate`], optionally skipping the nonce check for
+    /// callers which accept nonces ahead of the account's, like the mempool.
+    pub(crate) fn run_validation<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &CachedState<S, C>,
+        block_context: &BlockContext,
+        skip_nonce_check: bool,
+        #[cfg(feature = "cairo-native")] program_cache: Option<
+            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        >,
+    ) -> Result<ValidationInfo, ValidationError> {
+        let mut state = state.create_transactional()?;
+        self.check_fee_balance(&mut state, block_context)?;
+
+        // Only invoke transactions run an entry point after the validation.
+        let skip_execute = matches!(self, Transaction::InvokeFunction(_));
+        // The validate entry point runs with the validation step limit, so the VM stops once it's
+        // reached.
+        let max_n_steps = block_context.validate_max_n_steps();
+        let execution_info = match self
+            .create_for_simulation(false, skip_execute, true, true, skip_nonce_check)
+            .execute_with_gas_budget(
+                &mut state,
+                block_context,
+                None,
+                #[cfg(feature = "cairo-native")]
+                program_cache,
+            ) {
+            Err(TransactionError::CairoRunner(CairoRunError::VmException(VmException {
+                inner_exc: VirtualMachineError::UnfinishedExecution,
+                ..
+            }))) => return Err(ValidationError::StepLimitExceeded(max_n_steps)),
+            result => result?,
+        };
+
+        let validation_info = ValidationInfo {
+            validate_info: execution_info.validate_info,
+            actual_resources: execution_info.actual_resources,
+        };
+
+        // Calls made by the validate entry point run with their own step limit, so the limit is
+        // checked on the whole validation too.
+        if let Some(resources) = validation_info.validate_resources() {
+            if resources.n_steps as u64 > max_n_steps {
+                return Err(ValidationError::StepLimitExceeded(max_n_steps));
+            }
+        }
+
+        Ok(validation_info)
+    }
+
+    /// Checks the max fee against the estimated minimal fee and the sender's balance. The check is
+    /// done here as the fee transfer is skipped during validation, which also skips it.
+    fn check_fee_balance<S: State + StateReader>(
+        &self,
+        state: &mut S,
+        block_context: &BlockContext,
+    ) -> Result<(), ValidationError> {
+        let result: Result<(), TransactionError> = match self {
+            Transaction::Declare(tx) => tx.check_fee_balance(state, block_context),
+            Transaction::DeclareV2(tx) => tx.check_fee_balance(state, block_context),
+            Transaction::DeployAccount(tx) => tx.check_fee_balance(state, block_context),
+            Transaction::InvokeFunction(tx) => tx.check_fee_balance(state, block_context),
+            Transaction::Deploy(_) | Transaction::L1Handler(_) => {
+                return Err(ValidationError::NotValidated(self.tx_type()))
+            }
+        };
+        Ok(result?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        definitions::{block_context::StarknetChainId, constants::EXECUTE_ENTRY_POINT_SELECTOR},
+        state::{
+            contract_class_cache::PermanentContractClassCache,
+            in_memory_state_reader::InMemoryStateReader,
+        },
+        transaction::{InvokeFunction, L1Handler},
+        utils::{
+            test_utils::{
+                create_account_tx_test_state, TEST_ACCOUNT_CONTRACT_ADDRESS, TEST_CONTRACT_ADDRESS,
+            },
+            Address,
+        },
+    };
+    use assert_matches::assert_matches;
+    use cairo_vm::felt::Felt252;
+    use coverage_helper::test;
+    use num_traits::Zero;
+    use pretty_assertions_sorted::assert_eq;
+    use std::sync::Arc;
+
+    fn state(
+        validate_max_n_steps: u64,
+    ) -> (
+        BlockContext,
+        CachedState<InMemoryStateReader, PermanentContractClassCache>,
+    ) {
+        let (mut block_context, state) = create_account_tx_test_state().unwrap();
+        Arc::make_mut(block_context.versioned_constants_mut()).validate_max_n_steps =
+            validate_max_n_steps;
+        (block_context, state)
+    }
+
+    fn invoke(nonce: u64, max_fee: u128) -> Transaction {
+        Transaction::InvokeFunction(
+            InvokeFunction::new(
+                TEST_ACCOUNT_CONTRACT_ADDRESS.clone(),
+                EXECUTE_ENTRY_POINT_SELECTOR.clone(),
+                max_fee,
+                1.into(),
+                vec![TEST_CONTRACT_ADDRESS.0.clone(), 0.into(), 0.into()],
+                vec![],
+                StarknetChainId::TestNet.to_felt(),
+                Some(nonce.into()),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn validate_invoke() {
+        let (block_context, state) = state(1_000_000);
+
+        let validation_info = invoke(0, 0)
+            .validate(
+                &state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+            .unwrap();
+        let validate_info = validation_info.validate_info.as_ref().unwrap();
+        assert_eq!(
+            validate_info.contract_address,
+            *TEST_ACCOUNT_CONTRACT_ADDRESS
+        );
+        assert_eq!(
+            validation_info.validate_resources(),
+            validate_info.execution_resources.as_ref()
+        );
+        assert!(validation_info.actual_resources.contains_key("n_steps"));
+
+        // The nonce isn't incremented.
+        assert_eq!(
+            StateReader::get_nonce_at(&state, &TEST_ACCOUNT_CONTRACT_ADDRESS).unwrap(),
+            Felt252::zero()
+        );
+    }
+
+    #[test]
+    fn rejection_reasons() {
+        let (block_context, state) = state(1_000_000);
+        let validate = |tx: Transaction| {
+            tx.validate(
+                &state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            )
+        };
+
+        assert_matches!(
+            validate(invoke(1, 0)),
+            Err(ValidationError::InvalidNonce(expected, actual))
+                if expected == "0" && actual == "1"
+        );
+        assert_matches!(
+            validate(invoke(0, 1)),
+            Err(ValidationError::MaxFeeTooLow(1, _))
+        );
+        assert_matches!(
+            validate(invoke(0, 10_000_001)),
+            Err(ValidationError::InsufficientBalance(10_000_001, _, _))
+        );
+
+        let l1_handler = L1Handler::new_with_tx_hash(
+            Address(0x42.into()),
+            0x43.into(),
+            vec![],
+            0.into(),
+            None,
+            0x1234.into(),
+        )
+        .unwrap();
+        assert_matches!(
+            validate(Transaction::L1Handler(l1_handler)),
+            Err(ValidationError::NotValidated(_))
+        );
+    }
+
+    #[test]
+    fn step_limit() {
+        let (block_context, state) = state(1);
+
+        assert_matches!(
+            invoke(0, 0).validate(
+                &state,
+                &block_context,
+                #[cfg(feature = "cairo-native")]
+                None,
+            ),
+            Err(ValidationError::StepLimitExceeded(1))
+        );
+    }
+}
@@ -2,9 +2,9 @@ use crate::core::errors::hash_errors::HashError;
 use crate::services::api::contract_classes::deprecated_contract_class::EntryPointType;
 use crate::state::state_api::{State, StateChangesCount};
 use crate::{
-    definitions::transaction_type::TransactionType,
+    definitions::{block_context::BlockContext, transaction_type::TransactionType},
     execution::{
-        gas_usage::calculate_tx_gas_usage, os_usage::get_additional_os_resources, CallInfo,
+        gas_usage::calculate_tx_gas_vector, os_usage::get_additional_os_resources, CallInfo,
     },
     state::ExecutionResourcesManager,
     state::{cached_state::UNINITIALIZED_CLASS_HASH, state_cache::StorageEntry},
@@ -21,6 +21,7 @@ use num_integer::Integer;
 use num_traits::{Num, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::digest::generic_array::GenericArray;
 use sha3::{Digest, Keccak256};
 use starknet::core::types::FromByteArrayError;
 use starknet_api::core::L2_ADDRESS_UPPER_BOUND;
@@ -262,6 +263,7 @@ pub fn calculate_tx_resources(
     state_changes: StateChangesCount,
     l1_handler_payload_size: Option<usize>,
     n_reverted_steps: usize,
+    block_context: &BlockContext,
 ) -> Result<HashMap<String, usize>, TransactionError> {
     let non_optional_calls: Vec<CallInfo> = call_info.iter().flatten().cloned().collect();
 
@@ -271,14 +273,23 @@ pub fn calculate_tx_resources(
         l2_to_l1_messages.extend(call_info.get_sorted_l2_to_l1_messages()?)
     }
 
-    let l1_gas_usage =
-        calculate_tx_gas_usage(l2_to_l1_messages, &state_changes, l1_handler_payload_size);
+    let use_kzg_da = block_context.block_info.use_kzg_da;
+    let gas_usage = calculate_tx_gas_vector(
+        l2_to_l1_messages,
+        &state_changes,
+        l1_handler_payload_size,
+        use_kzg_da,
+    );
 
     let cairo_usage = resources_manager.cairo_usage.clone();
     let tx_syscall_counter = resources_manager.syscall_counter;
 
     // Add additional Cairo resources needed for the OS to run the transaction.
-    let additional_resources = get_additional_os_resources(tx_syscall_counter, &tx_type)?;
+    let additional_resources = get_additional_os_resources(
+        tx_syscall_counter,
+        &tx_type,
+        block_context.versioned_constants().os_resources(),
+    )?;
     let new_resources = &cairo_usage + &additional_resources;
     let mut filtered_builtins = new_resources.filter_unused_builtins();
 
@@ -290,11 +301,21 @@ pub fn calculate_tx_resources(
             .unwrap_or(0);
 
     let mut resources: HashMap<String, usize> = HashMap::new();
-    resources.insert("l1_gas_usage".to_string(), l1_gas_usage);
+    resources.insert("l1_gas_usage".to_string(), gas_usage.l1_gas);
+    if use_kzg_da {
+        resources.insert("l1_data_gas_usage".to_string(), gas_usage.l1_data_gas);
+    }
     resources.insert(
         "n_steps".to_string(),
         n_steps + filtered_builtins.n_memory_holes,
     );
+    // The memory holes are already charged as steps, they're only reported.
+    if filtered_builtins.n_memory_holes > 0 {
+        resources.insert(
+            "n_memory_holes".to_string(),
+            filtered_builtins.n_memory_holes,
+        );
+    }
     for (builtin, value) in filtered_builtins.builtin_instance_counter {
         resources.insert(builtin, value);
     }
@@ -469,6 +490,20 @@ pub fn calculate_sn_keccak(data: &[u8]) -> [u8; 32] {
     result
 }
 
+/// Number of u32 words in a SHA-256 block.
+pub(crate) const SHA256_BLOCK_SIZE: usize = 16;
+/// Number of u32 words in a SHA-256 state.
+pub(crate) const SHA256_STATE_SIZE: usize = 8;
+
+/// Applies the SHA-256 compression function to `state` with a block of big-endian words.
+pub(crate) fn sha256_compress(
+    state: &mut [u32; SHA256_STATE_SIZE],
+    block: &[u32; SHA256_BLOCK_SIZE],
+) {
+    let bytes: Vec<u8> = block.iter().flat_map(|word| word.to_be_bytes()).collect();
+    sha2::compress256(state, &[GenericArray::clone_from_slice(&bytes)]);
+}
+
 //* ------------------------
 //*      Other utils
 //* ------------------------
@@ -774,7 +809,7 @@ pub mod test_utils {
             0,
             DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS.clone(),
             1_000_000,
-            0,
+            1_000_000,
             BlockInfo::empty(TEST_SEQUENCER_ADDRESS.clone()),
             HashMap::default(),
             true,
@@ -1015,4 +1050,26 @@ mod test {
         let class_hash = ClassHash::from(Felt252::from(123456789));
         assert_eq!(format!("{}", class_hash), "0x75bcd15".to_string());
     }
+
+    #[test]
+    fn sha256_compress_single_block() {
+        // The padded block of the message "abc".
+        let mut block = [0u32; 16];
+        block[0] = 0x61626380;
+        block[15] = 0x18;
+        let mut state = [
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+            0x5be0cd19,
+        ];
+
+        sha256_compress(&mut state, &block);
+
+        assert_eq!(
+            state,
+            [
+                0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+                0xf20015ad,
+            ]
+        );
+    }
 }
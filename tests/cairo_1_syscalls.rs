@@ -23,6 +23,7 @@ use starknet_in_rust::{
         state_api::StateReader,
         ExecutionResourcesManager,
     },
+    transaction::{InvokeFunction, Transaction},
     utils::{Address, ClassHash},
     EntryPointType,
 };
@@ -672,6 +673,65 @@ fn emit_event() {
     )
 }
 
+#[test]
+fn emit_event_exceeding_the_limits_reverts_the_transaction() {
+    #[cfg(not(feature = "cairo_1_tests"))]
+    let program_data = include_bytes!("../starknet_programs/cairo2/emit_event.casm");
+    #[cfg(feature = "cairo_1_tests")]
+    let program_data = include_bytes!("../starknet_programs/cairo1/emit_event.casm");
+    let contract_class: CasmContractClass = serde_json::from_slice(program_data).unwrap();
+    let entrypoint_selector = contract_class.entry_points_by_type.external[0]
+        .selector
+        .clone();
+
+    let contract_class_cache = PermanentContractClassCache::default();
+    let address = Address(1111.into());
+    let class_hash: ClassHash = ClassHash([1; 32]);
+    contract_class_cache
+        .set_contract_class(class_hash, CompiledClass::Casm(Arc::new(contract_class)));
+    let mut state_reader = InMemoryStateReader::default();
+    state_reader
+        .address_to_class_hash_mut()
+        .insert(address.clone(), class_hash);
+    state_reader
+        .address_to_nonce_mut()
+        .insert(address.clone(), Felt252::zero());
+    let mut state = CachedState::new(Arc::new(state_reader), Arc::new(contract_class_cache));
+
+    // Every event emitted by the contract has one felt of data.
+    let mut block_context = BlockContext::default();
+    Arc::make_mut(block_context.versioned_constants_mut())
+        .event_limits_mut()
+        .max_data_length = 0;
+
+    let invoke = InvokeFunction::new(
+        address,
+        Felt252::new(entrypoint_selector),
+        0,
+        TRANSACTION_VERSION.clone(),
+        vec![],
+        vec![],
+        block_context.starknet_os_config().chain_id().clone(),
+        Some(Felt252::zero()),
+    )
+    .unwrap();
+    let exec_info = Transaction::InvokeFunction(invoke)
+        .execute(
+            &mut state,
+            &block_context,
+            u128::MAX,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+
+    assert!(exec_info.call_info.is_none());
+    assert!(exec_info
+        .revert_error
+        .unwrap()
+        .contains("Event data has length 1, exceeding the limit of 0"));
+}
+
 #[test]
 fn deploy_cairo1_from_cairo1() {
     // data to deploy
@@ -860,6 +860,7 @@ fn call_events_contract_test() {
         internal_calls: Vec::new(),
         gas_consumed: 9640,
         failure_flag: false,
+        syscall_trace: None,
     };
 
     let event = Event {
@@ -1,3 +1,4 @@
+use assert_matches::assert_matches;
 use cairo_vm::{
     felt::{felt_str, Felt252},
     vm::runners::cairo_runner::ExecutionResources,
@@ -6,7 +7,9 @@ use lazy_static::lazy_static;
 use num_traits::Zero;
 use starknet_in_rust::EntryPointType;
 use starknet_in_rust::{
-    core::contract_address::compute_deprecated_class_hash,
+    core::{
+        contract_address::compute_deprecated_class_hash, errors::validation_errors::ValidationError,
+    },
     definitions::{
         block_context::StarknetChainId,
         constants::{CONSTRUCTOR_ENTRY_POINT_SELECTOR, VALIDATE_DEPLOY_ENTRY_POINT_SELECTOR},
@@ -17,13 +20,14 @@ use starknet_in_rust::{
     services::api::contract_classes::{
         compiled_class::CompiledClass, deprecated_contract_class::ContractClass,
     },
+    signer::Signer,
     state::{
         cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
         in_memory_state_reader::InMemoryStateReader, state_api::State,
     },
-    transaction::DeployAccount,
+    transaction::{DeployAccount, Transaction},
     utils::{Address, ClassHash},
-    CasmContractClass,
+    validate_transaction, CasmContractClass,
 };
 use std::{collections::HashSet, sync::Arc};
 
@@ -293,6 +297,7 @@ fn internal_deploy_account_cairo1() {
             0,
             [
                 ("n_steps", n_steps),
+                ("n_memory_holes", 2),
                 ("pedersen_builtin", 23),
                 ("range_check_builtin", 87),
                 ("l1_gas_usage", 5508)
@@ -304,3 +309,138 @@ fn internal_deploy_account_cairo1() {
         ),
     );
 }
+
+#[test]
+fn deploy_account_validates_the_signature() {
+    let state_reader = Arc::new(InMemoryStateReader::default());
+    let mut state = CachedState::new(
+        state_reader,
+        Arc::new(PermanentContractClassCache::default()),
+    );
+
+    let contract_class = ContractClass::from_path("starknet_programs/Account.json").unwrap();
+    let class_hash_felt = compute_deprecated_class_hash(&contract_class).unwrap();
+    let class_hash = ClassHash::from(class_hash_felt.clone());
+    state
+        .set_contract_class(
+            &class_hash,
+            &CompiledClass::Deprecated(Arc::new(contract_class)),
+        )
+        .unwrap();
+
+    let signer = Signer::new(felt_str!("1234")).unwrap();
+    let salt = Felt252::from(7);
+    let deploy_account = |signer: &Signer| {
+        let tx = DeployAccount::new(
+            class_hash,
+            0,
+            1.into(),
+            Felt252::zero(),
+            vec![signer.public_key().clone()],
+            vec![],
+            salt.clone(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap();
+        assert_eq!(
+            tx.contract_address(),
+            &signer.account_address(&class_hash_felt, &salt).unwrap()
+        );
+        Transaction::DeployAccount(tx)
+    };
+
+    // Signed by a key other than the one the account is deployed with.
+    let forged_tx = Signer::new(felt_str!("5678"))
+        .unwrap()
+        .sign_transaction(deploy_account(&signer))
+        .unwrap();
+    assert!(forged_tx
+        .execute(
+            &mut state.create_transactional().unwrap(),
+            &Default::default(),
+            0,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .is_err());
+
+    let tx = signer.sign_transaction(deploy_account(&signer)).unwrap();
+    let tx_info = tx
+        .execute(
+            &mut state,
+            &Default::default(),
+            0,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+    assert_eq!(tx_info.revert_error, None);
+    assert!(tx_info.validate_info.is_some());
+}
+
+#[test]
+fn validate_transaction_rejects_forged_signatures() {
+    let state_reader = Arc::new(InMemoryStateReader::default());
+    let mut state = CachedState::new(
+        state_reader,
+        Arc::new(PermanentContractClassCache::default()),
+    );
+
+    let contract_class = ContractClass::from_path("starknet_programs/Account.json").unwrap();
+    let class_hash_felt = compute_deprecated_class_hash(&contract_class).unwrap();
+    let class_hash = ClassHash::from(class_hash_felt);
+    state
+        .set_contract_class(
+            &class_hash,
+            &CompiledClass::Deprecated(Arc::new(contract_class)),
+        )
+        .unwrap();
+
+    let signer = Signer::new(felt_str!("1234")).unwrap();
+    let deploy_account = Transaction::DeployAccount(
+        DeployAccount::new(
+            class_hash,
+            0,
+            1.into(),
+            Felt252::zero(),
+            vec![signer.public_key().clone()],
+            vec![],
+            7.into(),
+            StarknetChainId::TestNet.to_felt(),
+        )
+        .unwrap(),
+    );
+    let block_context = Default::default();
+
+    let forged_tx = Signer::new(felt_str!("5678"))
+        .unwrap()
+        .sign_transaction(deploy_account.clone())
+        .unwrap();
+    assert_matches!(
+        validate_transaction(
+            &forged_tx,
+            &state,
+            &block_context,
+            #[cfg(feature = "cairo-native")]
+            None,
+        ),
+        Err(ValidationError::ValidateFailed(_))
+    );
+
+    let tx = signer.sign_transaction(deploy_account).unwrap();
+    let validation_info = validate_transaction(
+        &tx,
+        &state,
+        &block_context,
+        #[cfg(feature = "cairo-native")]
+        None,
+    )
+    .unwrap();
+    assert!(validation_info.validate_resources().is_some());
+
+    // The account isn't deployed by the validation.
+    assert_eq!(
+        state.get_class_hash_at(&tx.contract_address()).unwrap(),
+        ClassHash::default()
+    );
+}
@@ -116,7 +116,7 @@ pub fn new_starknet_block_context_for_testing() -> BlockContext {
         0,
         DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS.clone(),
         1_000_000,
-        0,
+        1_000_000,
         BlockInfo::empty(TEST_SEQUENCER_ADDRESS.clone()),
         HashMap::default(),
         true,
@@ -774,6 +774,7 @@ fn expected_fee_transfer_info(fee: u128) -> CallInfo {
                 119, 136, 76, 21, 186, 42, 176, 242, 36, 27, 8, 13, 235,
             ]),
         ]),
+        syscall_trace: None,
     }
 }
 
@@ -838,6 +839,7 @@ fn expected_fib_fee_transfer_info(fee: u128) -> CallInfo {
                 135, 45, 239, 215, 171, 219, 145, 2, 61, 180, 101, 136, 19,
             ]),
         ]),
+        syscall_trace: None,
     }
 }
 
@@ -882,6 +884,7 @@ fn declarev2_tx() -> DeclareV2 {
         sierra_contract_class: Some(sierra_contract_class),
         sierra_class_hash,
         casm_class: casm_class.into(),
+        extended_tx_info: Default::default(),
         skip_execute: false,
         skip_fee_transfer: false,
         skip_validate: false,
@@ -1678,6 +1681,125 @@ fn test_invoke_with_declarev2_tx() {
     assert_eq_sorted!(result, expected_execution_info);
 }
 
+#[test]
+fn test_invoke_declarev2_class_reuses_prepared_program() {
+    let (block_context, state) = &mut create_account_tx_test_state().unwrap();
+
+    let declare_tx = declarev2_tx();
+    let compiled_class_hash = ClassHash::from(declare_tx.compiled_class_hash.clone());
+    declare_tx
+        .execute(
+            state,
+            block_context,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+    deploy_fib_syscall()
+        .execute(
+            state,
+            block_context,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+
+    let Address(test_contract_address) = TEST_FIB_CONTRACT_ADDRESS.clone();
+    let calldata = vec![
+        test_contract_address,
+        Felt252::from_bytes_be(&calculate_sn_keccak(b"fib")),
+        Felt252::from(3),
+        Felt252::from(42),
+        Felt252::from(0),
+        Felt252::from(0),
+    ];
+
+    // The first execution prepares the program and caches it under the compiled class hash,
+    // which is where the declared class itself is stored.
+    invoke_tx_with_nonce(calldata.clone(), u64::MAX as u128, Felt252::one())
+        .execute(
+            state,
+            block_context,
+            5551,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+    let prepared_program = state
+        .contract_class_cache()
+        .get_prepared_program(compiled_class_hash)
+        .expect("the prepared program should be cached");
+
+    // The second execution reuses it.
+    invoke_tx_with_nonce(calldata, u64::MAX as u128, Felt252::from(2))
+        .execute(
+            state,
+            block_context,
+            5551,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+    assert!(Arc::ptr_eq(
+        &prepared_program,
+        &state
+            .contract_class_cache()
+            .get_prepared_program(compiled_class_hash)
+            .unwrap()
+    ));
+}
+
+#[test]
+fn test_invoke_deprecated_class_reuses_compiled_hints() {
+    let (block_context, state) = &mut create_account_tx_test_state().unwrap();
+    let Address(test_contract_address) = TEST_CONTRACT_ADDRESS.clone();
+    let calldata = vec![
+        test_contract_address,
+        Felt252::from_bytes_be(&calculate_sn_keccak(b"return_result")),
+        Felt252::from(1),
+        Felt252::from(2),
+    ];
+
+    // The first execution compiles the hints of the account and caches them along with its
+    // program.
+    let first_execution_info = invoke_tx_with_nonce(calldata.clone(), u128::MAX, Felt252::zero())
+        .execute(
+            state,
+            block_context,
+            0,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+    let prepared_program = state
+        .contract_class_cache()
+        .get_prepared_program(*TEST_ACCOUNT_CONTRACT_CLASS_HASH)
+        .expect("the prepared program should be cached");
+    assert!(!prepared_program.deprecated_hints().is_empty());
+
+    // The second execution reuses them.
+    let second_execution_info = invoke_tx_with_nonce(calldata, u128::MAX, Felt252::one())
+        .execute(
+            state,
+            block_context,
+            0,
+            #[cfg(feature = "cairo-native")]
+            None,
+        )
+        .unwrap();
+    assert!(Arc::ptr_eq(
+        &prepared_program,
+        &state
+            .contract_class_cache()
+            .get_prepared_program(*TEST_ACCOUNT_CONTRACT_CLASS_HASH)
+            .unwrap()
+    ));
+    assert_eq!(
+        first_execution_info.actual_resources,
+        second_execution_info.actual_resources
+    );
+}
+
 #[test]
 fn test_deploy_account() {
     let (block_context, mut state) = create_account_tx_test_state().unwrap();